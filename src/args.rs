@@ -1,10 +1,13 @@
 use clap; // an argument parser
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct Args {
     pub filename: Option<String>,
     pub headless_mode: HeadlessMode,
     pub no_contour_ops: bool,
+    pub serial_contour_ops: bool,
+    pub round: Option<f32>,
+    pub quadratic: Option<f32>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -56,6 +59,34 @@ pub fn parse_args() -> Args {
                 .takes_value(false)
                 .help(r#"For either the save or the export operation, remove all contour operations, don't apply them."#)
         )
+        .arg(
+            clap::Arg::new("serial-contour-ops")
+                .long("serial-contour-ops")
+                .takes_value(false)
+                .help(r#"Build contour operations one after another on a single thread, not in parallel."#)
+        )
+        .arg(
+            clap::Arg::new("round")
+                .long("round")
+                .short('r')
+                .takes_value(true)
+                .require_equals(true)
+                .min_values(0)
+                .default_missing_value("1")
+                .value_name("UNIT")
+                .help(r#"When flattening or exporting, round all coordinates to multiples of UNIT (default 1, i.e. integers)."#)
+        )
+        .arg(
+            clap::Arg::new("quadratic")
+                .long("quadratic")
+                .short('q')
+                .takes_value(true)
+                .require_equals(true)
+                .min_values(0)
+                .default_missing_value("1")
+                .value_name("TOLERANCE")
+                .help(r#"When flattening or exporting, write quadratic outlines for TrueType, within TOLERANCE units of the cubics (default 1)."#)
+        )
         .get_matches();
 
     let headless_mode = if matches.is_present("export") {
@@ -69,11 +100,25 @@ pub fn parse_args() -> Args {
     };
 
     let no_contour_ops = matches.is_present("no-contour-ops");
+    let serial_contour_ops = matches.is_present("serial-contour-ops");
+
+    let round = matches.value_of("round").map(|unit| {
+        unit.parse::<f32>()
+            .unwrap_or_else(|_| panic!("Rounding unit must be a number, got {}", unit))
+    });
+
+    let quadratic = matches.value_of("quadratic").map(|tolerance| {
+        tolerance.parse::<f32>()
+            .unwrap_or_else(|_| panic!("Quadratic tolerance must be a number, got {}", tolerance))
+    });
 
     let args = Args {
         filename: matches.value_of("GLIF").map(|s| s.to_string()),
         headless_mode,
         no_contour_ops,
+        serial_contour_ops,
+        round,
+        quadratic,
     };
 
     args
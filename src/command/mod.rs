@@ -22,6 +22,7 @@ pub enum CommandType {
     ToggleConsole,
     ExecState,
     PathOp,
+    Align,
     Debug,
 }
 
@@ -66,6 +67,11 @@ pub enum Command {
     ToolGuidelines,
     ToolGrid,
     ToolImages,
+    ToolKnife,
+    ToolPencil,
+    ToolCorners,
+    ToolNib,
+    ToolExpandStroke,
 
     // selection
     DeleteSelection,
@@ -97,6 +103,19 @@ pub enum Command {
     // misc
     Quit,
     ReverseContour,
+    AddExtrema,
+    SimplifySelection,
+    RoundToGrid,
+
+    // align and distribute
+    AlignLeft,
+    AlignRight,
+    AlignTop,
+    AlignBottom,
+    AlignHorizontalCenter,
+    AlignVerticalCenter,
+    DistributeHorizontally,
+    DistributeVertically,
 
     // debug
     SkiaDump,
@@ -111,9 +130,8 @@ impl Command {
             | NudgeLeft | NudgeBigLeft | NudgeTinyLeft | NudgeRight | NudgeBigRight
             | NudgeTinyRight => CommandType::Nudge,
             ToolPan | ToolPen | ToolSelect | ToolZoom | ToolDash | ToolPAP | ToolVWS
-            | ToolMeasure | ToolAnchors | ToolShapes | ToolGuidelines | ToolImages => {
-                CommandType::ToolSelect
-            }
+            | ToolMeasure | ToolAnchors | ToolShapes | ToolGuidelines | ToolImages | ToolKnife
+            | ToolPencil | ToolCorners | ToolNib | ToolExpandStroke => CommandType::ToolSelect,
             DeleteSelection
             | SelectAll
             | CopySelection
@@ -125,7 +143,11 @@ impl Command {
             ToolGrid | TogglePointLabels | TogglePreviewMode => CommandType::ViewMode,
             ToggleConsole => CommandType::ToggleConsole,
             Quit => CommandType::ExecState,
-            ReverseContour => CommandType::PathOp,
+            ReverseContour | AddExtrema | SimplifySelection | RoundToGrid => CommandType::PathOp,
+            AlignLeft | AlignRight | AlignTop | AlignBottom | AlignHorizontalCenter
+            | AlignVerticalCenter | DistributeHorizontally | DistributeVertically => {
+                CommandType::Align
+            }
             SkiaDump => CommandType::Debug,
         }
     }
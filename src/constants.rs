@@ -5,6 +5,14 @@ pub static OFFSET_FACTOR: f32 = 10.;
 pub static BIG_OFFSET_FACTOR: f32 = OFFSET_FACTOR * 10.;
 pub static TINY_OFFSET_FACTOR: f32 = 1.;
 
+/* Snapping */
+/// How close, in screen pixels, a dragged position must be to a target to snap to it.
+pub static SNAP_DISTANCE: f32 = 6.;
+pub static SNAP_INDICATOR_STROKE: u32 = 0xff_ff00ff;
+
+/* Knife */
+pub static KNIFE_STROKE: u32 = 0xff_e03030;
+
 /// TODO: Deprecate this hack.
 /// See https://github.com/emilk/egui/issues/2639.
 #[rustfmt::skip]
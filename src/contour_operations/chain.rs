@@ -0,0 +1,142 @@
+use glifparser::glif::contour::MFEKContourCommon;
+use glifparser::glif::contour_operations::ContourOperations;
+use glifparser::glif::{MFEKContour, MFEKOutline};
+use glifparser::MFEKPointData;
+use serde::{Deserialize, Serialize};
+
+use super::ContourOperationBuild;
+use crate::editor::contour_data::EditorOperation;
+
+/// An operation in a chain, either one glifparser can store on a contour or one only the editor
+/// keeps.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ChainOperation {
+    Contour(ContourOperations<MFEKPointData>),
+    Editor(EditorOperation),
+}
+
+impl ChainOperation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChainOperation::Contour(ContourOperations::VariableWidthStroke { .. }) => {
+                "Variable Width Stroke"
+            }
+            ChainOperation::Contour(ContourOperations::PatternAlongPath { .. }) => {
+                "Pattern Along Path"
+            }
+            ChainOperation::Contour(ContourOperations::DashAlongPath { .. }) => "Dash Along Path",
+            ChainOperation::Contour(_) => "Unknown",
+            ChainOperation::Editor(EditorOperation::RoundedCorners(_)) => "Rounded Corners",
+            ChainOperation::Editor(EditorOperation::ExpandStroke(_)) => "Expand Stroke",
+            ChainOperation::Editor(EditorOperation::Nib(_)) => "Nib",
+        }
+    }
+
+    /// Builds the operation on a contour, ignoring any operation the contour has of its own.
+    fn build_on(&self, contour: MFEKContour<MFEKPointData>) -> MFEKOutline<MFEKPointData> {
+        let mut contour = MFEKContour::new(contour.inner().clone(), None);
+        match fit_to_contour(self, &contour) {
+            ChainOperation::Contour(op) => {
+                contour.set_operation(Some(op));
+                contour.operation().build(&contour)
+            }
+            ChainOperation::Editor(op) => op.build(&contour),
+        }
+    }
+}
+
+/// One operation in a chain, which can be switched off without removing it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChainedOperation {
+    pub enabled: bool,
+    pub operation: ChainOperation,
+}
+
+impl ChainedOperation {
+    pub fn new(operation: ChainOperation) -> Self {
+        Self {
+            enabled: true,
+            operation,
+        }
+    }
+}
+
+/// Contour operations applied in order, each to every contour the one before it built. A contour
+/// with a single operation is a chain of one.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct OperationChain {
+    pub operations: Vec<ChainedOperation>,
+}
+
+impl From<Option<ContourOperations<MFEKPointData>>> for OperationChain {
+    fn from(op: Option<ContourOperations<MFEKPointData>>) -> Self {
+        Self {
+            operations: op
+                .into_iter()
+                .map(|op| ChainedOperation::new(ChainOperation::Contour(op)))
+                .collect(),
+        }
+    }
+}
+
+impl OperationChain {
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Applies the chain's enabled operations in turn to contours something else already built.
+    pub fn build_from(
+        &self,
+        mut outline: MFEKOutline<MFEKPointData>,
+    ) -> MFEKOutline<MFEKPointData> {
+        for chained in self.operations.iter().filter(|c| c.enabled) {
+            outline = outline
+                .into_iter()
+                .flat_map(|contour| chained.operation.build_on(contour))
+                .collect();
+        }
+        outline
+    }
+}
+
+/// Contours built by an earlier operation don't have the points later operations' per-point data
+/// was made for, so a variable width stroke uses its first handle's widths all along them, rounded
+/// corners their first radius, and a nib no overrides. A variable width stroke can have a handle
+/// for each point or one more, so either fits.
+pub fn fit_to_contour(op: &ChainOperation, contour: &MFEKContour<MFEKPointData>) -> ChainOperation {
+    match op {
+        ChainOperation::Contour(ContourOperations::VariableWidthStroke { data })
+            if data.handles.len() != contour.len() && data.handles.len() != contour.len() + 1 =>
+        {
+            let mut data = data.clone();
+            if let Some(handle) = data.handles.first().cloned() {
+                data.handles = vec![handle; contour.len()];
+            }
+            ChainOperation::Contour(ContourOperations::VariableWidthStroke { data })
+        }
+        ChainOperation::Editor(EditorOperation::RoundedCorners(data))
+            if data.handles.len() != contour.len() =>
+        {
+            let mut data = data.clone();
+            if let Some(handle) = data.handles.first().copied() {
+                data.handles = vec![handle; contour.len()];
+            }
+            ChainOperation::Editor(EditorOperation::RoundedCorners(data))
+        }
+        ChainOperation::Editor(EditorOperation::Nib(data))
+            if data.handles.len() != contour.len() =>
+        {
+            let mut data = data.clone();
+            data.handles = vec![Default::default(); contour.len()];
+            ChainOperation::Editor(EditorOperation::Nib(data))
+        }
+        op => op.clone(),
+    }
+}
+
+impl ContourOperationBuild for OperationChain {
+    fn build(&self, contour: &MFEKContour<MFEKPointData>) -> MFEKOutline<MFEKPointData> {
+        self.build_from(vec![MFEKContour::new(contour.inner().clone(), None)])
+    }
+}
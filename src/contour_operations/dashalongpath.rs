@@ -1,25 +1,412 @@
 use glifparser::glif::contour::MFEKContourCommon;
 use glifparser::glif::{contour_operations::dash::DashContour, MFEKContour, MFEKOutline};
-use glifparser::{Glif, MFEKPointData};
+use glifparser::{Contour, Glif, Handle, MFEKPointData, Point, PointType};
+use serde::{Deserialize, Serialize};
 use MFEKmath::mfek::ResolveCubic;
+use MFEKmath::{Bezier, Evaluate, Vector};
 
 use super::ContourOperationBuild;
 
+use std::collections::BTreeMap;
+
+// How many times each segment is sampled to measure distances along it.
+const ARC_SAMPLES: usize = 32;
+
+/// Dash settings glifparser's `DashContour` has no fields for.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DashExtras {
+    /// How far into the dash pattern the contour starts.
+    pub phase: f32,
+    /// Dash patterns used instead of the contour's on single segments, by segment index.
+    pub segment_dashes: BTreeMap<usize, Vec<f32>>,
+}
+
+impl DashExtras {
+    pub fn is_empty(&self) -> bool {
+        self.phase == 0. && self.segment_dashes.is_empty()
+    }
+
+    /// Moves segment patterns along with the points of their segments, as `map` gives the old
+    /// index of every point of a contour which had `old_len` points. A segment keeps its pattern
+    /// only while both its points are still next to each other.
+    pub fn remap_segments(&mut self, map: &[Option<usize>], old_len: usize) {
+        let len = map.len();
+        self.segment_dashes = (0..len)
+            .filter_map(|seg| {
+                let (from, to) = (map[seg]?, map[(seg + 1) % len]?);
+                if to != (from + 1) % old_len.max(1) {
+                    return None;
+                }
+                let pattern = self.segment_dashes.get(&from)?;
+                Some((seg, pattern.clone()))
+            })
+            .collect();
+    }
+}
+
+/// Consecutive segments of a contour which share a dash pattern. The pattern starts over at the
+/// beginning of every run.
+#[derive(Clone, Debug)]
+pub struct DashRun {
+    /// Index of the run's first segment in the contour.
+    pub first_segment: usize,
+    pub beziers: Vec<Bezier>,
+    pub pattern: Vec<f32>,
+    /// Whether the pattern is a segment override rather than the contour's.
+    pub overridden: bool,
+    // (bezier index, t, distance from the start of the run)
+    samples: Vec<(usize, f64, f64)>,
+}
+
+impl DashRun {
+    fn new(
+        first_segment: usize,
+        beziers: Vec<Bezier>,
+        pattern: Vec<f32>,
+        overridden: bool,
+    ) -> Self {
+        let mut samples = vec![(0, 0., 0.)];
+        let mut distance = 0.;
+        for (bi, bezier) in beziers.iter().enumerate() {
+            let mut last = bezier.at(0.);
+            for step in 1..=ARC_SAMPLES {
+                let t = step as f64 / ARC_SAMPLES as f64;
+                let at = bezier.at(t);
+                distance += (at - last).magnitude();
+                samples.push((bi, t, distance));
+                last = at;
+            }
+        }
+        Self {
+            first_segment,
+            beziers,
+            pattern,
+            overridden,
+            samples,
+        }
+    }
+
+    pub fn segments(&self) -> std::ops::Range<usize> {
+        self.first_segment..self.first_segment + self.beziers.len()
+    }
+
+    pub fn length(&self) -> f64 {
+        self.samples.last().map(|s| s.2).unwrap_or(0.)
+    }
+
+    /// The bezier and parameter `distance` along the run.
+    fn at_distance(&self, distance: f64) -> (usize, f64) {
+        let next = self
+            .samples
+            .iter()
+            .position(|s| s.2 >= distance)
+            .unwrap_or(self.samples.len() - 1)
+            .max(1);
+        let (bi, t1, d1) = self.samples[next];
+        let (b0, t0, d0) = self.samples[next - 1];
+        // The sample before the first of a bezier is the end of the one before it.
+        let t0 = if b0 != bi { 0. } else { t0 };
+        let f = if d1 > d0 {
+            (distance - d0) / (d1 - d0)
+        } else {
+            0.
+        };
+        (bi, t0 + (t1 - t0) * f.clamp(0., 1.))
+    }
+
+    pub fn point_at(&self, distance: f64) -> Vector {
+        let (bi, t) = self.at_distance(distance);
+        self.beziers[bi].at(t)
+    }
+
+    pub fn tangent_at(&self, distance: f64) -> Vector {
+        let (bi, t) = self.at_distance(distance);
+        self.beziers[bi].tangent_at(t).normalize()
+    }
+
+    /// How far along the run the place closest to `point` is.
+    pub fn distance_to(&self, point: Vector) -> f64 {
+        self.samples
+            .iter()
+            .map(|(bi, t, d)| ((self.beziers[*bi].at(*t) - point).magnitude(), *d))
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+            .map(|(_, d)| d)
+            .unwrap_or(0.)
+    }
+
+    /// Every place the pattern switches between dash and gap, with the index in the pattern of
+    /// the interval ending there.
+    pub fn boundaries(&self, phase: f32) -> Vec<(f64, usize)> {
+        let mut boundaries = vec![];
+        self.walk(phase, |_, end, idx| boundaries.push((end, idx)));
+        boundaries
+    }
+
+    /// The first place along the run each interval of the pattern ends, with its index, for dragging
+    /// to change that interval's length.
+    pub fn handles(&self, phase: f32) -> Vec<(f64, usize)> {
+        let mut handles: Vec<(f64, usize)> = vec![];
+        for (at, idx) in self.boundaries(phase) {
+            if at < self.length() && !handles.iter().any(|(_, seen)| *seen == idx) {
+                handles.push((at, idx));
+            }
+        }
+        handles
+    }
+
+    /// Where the dashes start and end.
+    pub fn dashes(&self, phase: f32) -> Vec<(f64, f64)> {
+        let mut dashes = vec![];
+        self.walk(phase, |start, end, idx| {
+            if idx % 2 == 0 {
+                dashes.push((start, end));
+            }
+        });
+        dashes
+    }
+
+    /// Calls `f` with the start, end and pattern index of every interval along the run, clipped to
+    /// the run. A pattern which adds up to nothing is one dash the length of the run.
+    fn walk(&self, phase: f32, mut f: impl FnMut(f64, f64, usize)) {
+        let len = self.length();
+        let period: f64 = self.pattern.iter().map(|i| *i as f64).sum();
+        if period <= 0. || self.pattern.is_empty() {
+            f(0., len, 0);
+            return;
+        }
+
+        let mut at = -(phase as f64).rem_euclid(period);
+        let mut idx = 0;
+        while at < len {
+            let end = at + self.pattern[idx] as f64;
+            if end > 0. && end > at {
+                f(at.max(0.), end.min(len), idx);
+            }
+            at = end;
+            idx = (idx + 1) % self.pattern.len();
+        }
+    }
+
+    /// The part of the run between two distances along it, as an open contour.
+    pub fn piece(&self, from: f64, to: f64) -> Contour<MFEKPointData> {
+        let ((b0, t0), (b1, t1)) = (self.at_distance(from), self.at_distance(to));
+        let mut curves = vec![];
+        for bi in b0..=b1 {
+            let (start, end) = (
+                if bi == b0 { t0 } else { 0. },
+                if bi == b1 { t1 } else { 1. },
+            );
+            if end > start {
+                curves.push(sub_bezier(&self.beziers[bi], start, end));
+            }
+        }
+        contour_from_beziers(&curves)
+    }
+}
+
+/// The part of a bezier between two of its parameters.
+fn sub_bezier(bezier: &Bezier, from: f64, to: f64) -> [Vector; 4] {
+    let left = match bezier.subdivide(to) {
+        Some((left, _)) if to < 1. => left,
+        _ => bezier.clone(),
+    };
+    let piece = match left.subdivide(from / to) {
+        Some((_, right)) if from > 0. => right,
+        _ => left,
+    };
+    let points = piece.to_control_points();
+    [points[0], points[1], points[2], points[3]]
+}
+
+fn contour_from_beziers(curves: &[[Vector; 4]]) -> Contour<MFEKPointData> {
+    let mut contour: Contour<MFEKPointData> = vec![];
+    for (ci, curve) in curves.iter().enumerate() {
+        if ci == 0 {
+            contour.push(Point::from_x_y_a_b_type(
+                (curve[0].x as f32, curve[0].y as f32),
+                (curve[1].to_handle(), Handle::Colocated),
+                PointType::Move,
+            ));
+        } else {
+            contour.last_mut().unwrap().a = curve[1].to_handle();
+        }
+        contour.push(Point::from_x_y_a_b_type(
+            (curve[3].x as f32, curve[3].y as f32),
+            (Handle::Colocated, curve[2].to_handle()),
+            PointType::Curve,
+        ));
+    }
+    contour
+}
+
+/// Splits a contour into runs of segments with the same dash pattern.
+pub fn dash_runs(
+    data: &DashContour,
+    contour: &MFEKContour<MFEKPointData>,
+    extras: &DashExtras,
+) -> Vec<DashRun> {
+    let cubic = contour.to_cubic();
+    let points = match cubic.cubic() {
+        Some(points) if points.len() > 1 => points,
+        _ => return vec![],
+    };
+    let len = points.len();
+    let seg_count = if cubic.is_open() { len - 1 } else { len };
+
+    let mut runs: Vec<DashRun> = vec![];
+    let mut current: Option<(usize, Vec<Bezier>, Option<&Vec<f32>>)> = None;
+    for seg_idx in 0..seg_count {
+        let bezier = Bezier::from(&points[seg_idx], &points[(seg_idx + 1) % len]);
+        let pattern = extras.segment_dashes.get(&seg_idx);
+        match current.as_mut() {
+            Some((_, beziers, run_pattern)) if *run_pattern == pattern => beziers.push(bezier),
+            _ => {
+                if let Some((first, beziers, pattern)) = current.replace((seg_idx, vec![], pattern))
+                {
+                    runs.push(run_for(data, first, beziers, pattern));
+                }
+                current.as_mut().unwrap().1.push(bezier);
+            }
+        }
+    }
+    if let Some((first, beziers, pattern)) = current {
+        runs.push(run_for(data, first, beziers, pattern));
+    }
+    runs
+}
+
+fn run_for(
+    data: &DashContour,
+    first_segment: usize,
+    beziers: Vec<Bezier>,
+    pattern: Option<&Vec<f32>>,
+) -> DashRun {
+    match pattern {
+        Some(pattern) => DashRun::new(first_segment, beziers, pattern.clone(), true),
+        None => DashRun::new(first_segment, beziers, data.dash_desc.clone(), false),
+    }
+}
+
+fn stroke_dashes(data: &DashContour, glif: &Glif<MFEKPointData>) -> MFEKOutline<MFEKPointData> {
+    let dash_output = MFEKmath::dash_along_glif(glif, data);
+
+    let mut output: MFEKOutline<MFEKPointData> = Vec::new();
+    if let Some(outline) = dash_output.outline {
+        for contour in outline {
+            output.push(contour.into());
+        }
+    }
+
+    output
+}
+
+/// Builds dashes with a phase or per-segment patterns. The dashes are cut here, and each is then
+/// stroked by MFEKmath as a single dash so caps, joins and culling work as they always do.
+pub fn build_with_extras(
+    data: &DashContour,
+    contour: &MFEKContour<MFEKPointData>,
+    extras: &DashExtras,
+) -> MFEKOutline<MFEKPointData> {
+    let runs = dash_runs(data, contour, extras);
+    let mut glif = Glif::default();
+    glif.outline = Some(
+        runs.iter()
+            .flat_map(|run| {
+                run.dashes(extras.phase)
+                    .into_iter()
+                    .map(move |(from, to)| run.piece(from, to))
+            })
+            .filter(|piece| piece.len() > 1)
+            .collect(),
+    );
+
+    let longest = runs.iter().map(|r| r.length()).fold(0., f64::max);
+    let mut whole = data.clone();
+    whole.dash_desc = vec![longest as f32 + 1., 0.];
+    stroke_dashes(&whole, &glif)
+}
+
 impl ContourOperationBuild for DashContour {
     fn build(&self, contour: &MFEKContour<MFEKPointData>) -> MFEKOutline<MFEKPointData> {
         let mut glif = Glif::default();
 
         // TODO: Get rid of this call to resolve to cubic and use some internal cache.
         glif.outline = Some(vec![contour.to_cubic().cubic().unwrap().clone()]);
-        let dash_output = MFEKmath::dash_along_glif(&glif, self);
+        stroke_dashes(self, &glif)
+    }
+}
 
-        let mut output: MFEKOutline<MFEKPointData> = Vec::new();
-        if let Some(outline) = dash_output.outline {
-            for contour in outline {
-                output.push(contour.into());
-            }
-        }
+#[cfg(test)]
+fn straight_run(lengths: &[f64], pattern: Vec<f32>) -> DashRun {
+    let mut x = 0.;
+    let beziers = lengths
+        .iter()
+        .map(|length| {
+            let from = Point::<MFEKPointData>::from_x_y_type((x as f32, 0.), PointType::Line);
+            x += length;
+            let to = Point::<MFEKPointData>::from_x_y_type((x as f32, 0.), PointType::Line);
+            Bezier::from(&from, &to)
+        })
+        .collect();
+    DashRun::new(0, beziers, pattern, false)
+}
 
-        output
-    }
+#[test]
+fn dash_run_walk_test() {
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-6;
+    let run = straight_run(&[100.], vec![10., 5.]);
+    assert!(close(run.length(), 100.));
+
+    let mut intervals = vec![];
+    run.walk(0., |start, end, idx| intervals.push((start, end, idx)));
+    assert_eq!(intervals.len(), 13);
+    assert_eq!(intervals[..2], [(0., 10., 0), (10., 15., 1)]);
+    // the last dash is clipped to the end of the run
+    let (start, end, idx) = intervals[12];
+    assert!(close(start, 90.) && close(end, 100.) && idx == 0);
+
+    // a phase starts the run partway into the pattern
+    let dashes = run.dashes(5.);
+    assert_eq!(dashes[..2], [(0., 5.), (10., 20.)]);
+    assert_eq!(run.dashes(15.), run.dashes(0.));
+
+    // a pattern adding up to nothing is a single dash
+    let solid = straight_run(&[100.], vec![0., 0.]);
+    assert_eq!(solid.dashes(0.).len(), 1);
+}
+
+#[test]
+fn dash_run_at_distance_test() {
+    let run = straight_run(&[100., 50.], vec![10., 5.]);
+    assert!((run.length() - 150.).abs() < 1e-6);
+
+    let (bi, t) = run.at_distance(0.);
+    assert_eq!((bi, t), (0, 0.));
+    let (bi, _) = run.at_distance(99.);
+    assert_eq!(bi, 0);
+    let (bi, t) = run.at_distance(125.);
+    assert!(bi == 1 && (t - 0.5).abs() < 1e-6);
+    // past the end is the end
+    let (bi, t) = run.at_distance(200.);
+    assert_eq!((bi, t), (1, 1.));
+    assert!((run.point_at(125.).x - 125.).abs() < 1e-6);
+}
+
+#[test]
+fn remap_segments_test() {
+    let mut extras = DashExtras::default();
+    extras.segment_dashes.insert(0, vec![1., 1.]);
+    extras.segment_dashes.insert(2, vec![2., 2.]);
+    extras.segment_dashes.insert(3, vec![3., 3.]);
+
+    // a point inserted into the second segment of a closed contour of four
+    extras.remap_segments(&[Some(0), Some(1), None, Some(2), Some(3)], 4);
+    let expected: BTreeMap<_, _> = [(0, vec![1., 1.]), (3, vec![2., 2.]), (4, vec![3., 3.])].into();
+    assert_eq!(extras.segment_dashes, expected);
+
+    // the first point deleted, which joins its two segments into one
+    extras.remap_segments(&[Some(1), Some(2), Some(3), Some(4)], 5);
+    let expected: BTreeMap<_, _> = [(2, vec![2., 2.])].into();
+    assert_eq!(extras.segment_dashes, expected);
 }
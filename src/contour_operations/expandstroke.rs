@@ -0,0 +1,95 @@
+use glifparser::glif::{MFEKContour, MFEKOutline};
+use glifparser::outline::skia::{FromSkiaPath as _, ToSkiaPaths as _};
+use glifparser::{MFEKPointData, Outline};
+use serde::{Deserialize, Serialize};
+use skia_safe::{PaintCap, PaintJoin, Path, StrokeRec};
+use MFEKmath::mfek::ResolveCubic;
+
+use super::ContourOperationBuild;
+
+/// Strokes a contour with a constant width, using Skia's stroker, and removes the overlaps in the
+/// result. glifparser can't store it, so it's kept in the editor's contour data, and layers with
+/// one are unioned when exported.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExpandStrokeContour {
+    pub stroke_width: f32,
+    #[serde(with = "paint_join")]
+    pub paint_join: PaintJoin,
+    #[serde(with = "paint_cap")]
+    pub paint_cap: PaintCap,
+    pub miter_limit: f32,
+}
+
+// Skia's joins and caps are saved by name.
+mod paint_join {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use skia_safe::PaintJoin;
+
+    pub fn serialize<S: Serializer>(join: &PaintJoin, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match join {
+            PaintJoin::Round => "round",
+            PaintJoin::Bevel => "bevel",
+            _ => "miter",
+        })
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PaintJoin, D::Error> {
+        Ok(match String::deserialize(deserializer)?.as_str() {
+            "round" => PaintJoin::Round,
+            "bevel" => PaintJoin::Bevel,
+            _ => PaintJoin::Miter,
+        })
+    }
+}
+
+mod paint_cap {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use skia_safe::PaintCap;
+
+    pub fn serialize<S: Serializer>(cap: &PaintCap, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match cap {
+            PaintCap::Round => "round",
+            PaintCap::Square => "square",
+            _ => "butt",
+        })
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PaintCap, D::Error> {
+        Ok(match String::deserialize(deserializer)?.as_str() {
+            "round" => PaintCap::Round,
+            "square" => PaintCap::Square,
+            _ => PaintCap::Butt,
+        })
+    }
+}
+
+impl Default for ExpandStrokeContour {
+    fn default() -> Self {
+        Self {
+            stroke_width: 20.,
+            paint_join: PaintJoin::Miter,
+            paint_cap: PaintCap::Butt,
+            miter_limit: 4.,
+        }
+    }
+}
+
+impl ContourOperationBuild for ExpandStrokeContour {
+    fn build(&self, contour: &MFEKContour<MFEKPointData>) -> MFEKOutline<MFEKPointData> {
+        let source: MFEKOutline<MFEKPointData> = vec![contour.to_cubic()];
+        let path = source.to_skia_paths(None).combined();
+
+        let mut stroke_rec = StrokeRec::new_hairline();
+        stroke_rec.set_stroke_style(self.stroke_width, false);
+        stroke_rec.set_stroke_params(self.paint_cap, self.paint_join, self.miter_limit);
+
+        let mut stroked = Path::new();
+        if !stroke_rec.apply_to_path(&mut stroked, &path) {
+            return vec![];
+        }
+        let stroked = stroked.simplify().unwrap_or(stroked);
+
+        let outline: Outline<MFEKPointData> = Outline::from_skia_path(&stroked);
+        outline.iter().map(|c| c.into()).collect()
+    }
+}
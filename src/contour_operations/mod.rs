@@ -1,5 +1,9 @@
+pub mod chain;
 pub mod dashalongpath;
+pub mod expandstroke;
+pub mod nib;
 pub mod patternalongpath;
+pub mod roundedcorners;
 pub mod variablewidthstroke;
 
 use glifparser::glif::contour_operations::{unknown_op_outline, ContourOperations};
@@ -26,3 +30,22 @@ impl ContourOperationBuild for Option<ContourOperations<MFEKPointData>> {
         }
     }
 }
+
+/// The operation for a contour made of the points at `indices` of another contour, in that order.
+/// Per-point data follows its points; operations without any are copied as they are.
+pub fn sub_contour_operation(
+    op: &Option<ContourOperations<MFEKPointData>>,
+    indices: &[usize],
+) -> Option<ContourOperations<MFEKPointData>> {
+    match op {
+        Some(ContourOperations::VariableWidthStroke { data }) => {
+            let mut data = data.clone();
+            data.handles = indices
+                .iter()
+                .filter_map(|idx| data.handles.get(*idx).cloned())
+                .collect();
+            Some(ContourOperations::VariableWidthStroke { data })
+        }
+        op => op.clone(),
+    }
+}
@@ -0,0 +1,300 @@
+use glifparser::glif::contour::MFEKContourCommon;
+use glifparser::glif::{MFEKContour, MFEKOutline};
+use glifparser::outline::skia::FromSkiaPath as _;
+use glifparser::{MFEKPointData, Outline};
+use serde::{Deserialize, Serialize};
+use skia_safe::{Path, Point as SkPoint};
+use MFEKmath::mfek::ResolveCubic;
+use MFEKmath::{Bezier, Evaluate};
+
+use super::ContourOperationBuild;
+
+// How many sides an elliptical nib is approximated with.
+const ELLIPSE_SIDES: usize = 32;
+// How many times each segment of the path is sampled when sweeping the nib along it.
+const SWEEP_SAMPLES: usize = 32;
+// Sines of angles smaller than this between sides of a nib are taken as no turn at all.
+const COLLINEAR_EPSILON: f64 = 1e-9;
+
+type Vec2 = (f64, f64);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum NibShape {
+    Ellipse,
+    Rectangle,
+    /// The points of a closed convex contour, scaled to fit the nib's width and height.
+    Contour(Vec<(f32, f32)>),
+}
+
+/// Per-point overrides of the nib's angle, in degrees, and of its size, as a factor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NibHandle {
+    pub angle: Option<f32>,
+    pub scale: Option<f32>,
+}
+
+/// Sweeps a pen nib along a contour. The outline is the union of the nib at every place along the
+/// path. Only convex nibs can be swept, see [`shape_from_contour`]. glifparser can't store it, so
+/// it's kept in the editor's contour data.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NibContour {
+    pub shape: NibShape,
+    pub width: f32,
+    pub height: f32,
+    /// Degrees counterclockwise.
+    pub angle: f32,
+    pub handles: Vec<NibHandle>,
+}
+
+impl Default for NibContour {
+    fn default() -> Self {
+        Self {
+            shape: NibShape::Ellipse,
+            width: 60.,
+            height: 10.,
+            angle: 30.,
+            handles: vec![],
+        }
+    }
+}
+
+/// Whether a polygon is convex: it turns the same way at every vertex, and only once around.
+fn is_convex(polygon: &[Vec2]) -> bool {
+    let len = polygon.len();
+    if len < 3 {
+        return false;
+    }
+
+    let (mut sign, mut turning) = (0., 0.);
+    for i in 0..len {
+        let (a, b, c) = (polygon[i], polygon[(i + 1) % len], polygon[(i + 2) % len]);
+        let (ab, bc) = ((b.0 - a.0, b.1 - a.1), (c.0 - b.0, c.1 - b.1));
+        let cross = ab.0 * bc.1 - ab.1 * bc.0;
+        let dot = ab.0 * bc.0 + ab.1 * bc.1;
+        // Points along a straight side are skipped, however imprecisely they were sampled.
+        if cross.abs() <= COLLINEAR_EPSILON * ab.0.hypot(ab.1) * bc.0.hypot(bc.1) {
+            continue;
+        }
+        if sign * cross < 0. {
+            return false;
+        }
+        sign = cross.signum();
+        turning += cross.atan2(dot);
+    }
+    (turning.abs() - std::f64::consts::TAU).abs() < 1e-6
+}
+
+/// The convex hull of a set of points, counterclockwise.
+fn convex_hull(mut points: Vec<Vec2>) -> Vec<Vec2> {
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    let cross = |o: Vec2, a: Vec2, b: Vec2| (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0);
+    let mut hull: Vec<Vec2> = vec![];
+    for pass in 0..2 {
+        let start = hull.len();
+        let iter: Box<dyn Iterator<Item = &Vec2>> = if pass == 0 {
+            Box::new(points.iter())
+        } else {
+            Box::new(points.iter().rev())
+        };
+        for p in iter {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], *p) <= 0.
+            {
+                hull.pop();
+            }
+            hull.push(*p);
+        }
+        hull.pop();
+    }
+    hull
+}
+
+impl NibContour {
+    /// The nib's outline centered on the origin, unrotated and at its full size.
+    fn base_polygon(&self) -> Vec<Vec2> {
+        let (w, h) = (self.width as f64 / 2., self.height as f64 / 2.);
+        match &self.shape {
+            NibShape::Ellipse => (0..ELLIPSE_SIDES)
+                .map(|i| {
+                    let theta = i as f64 / ELLIPSE_SIDES as f64 * std::f64::consts::TAU;
+                    (w * theta.cos(), h * theta.sin())
+                })
+                .collect(),
+            NibShape::Rectangle => vec![(-w, -h), (w, -h), (w, h), (-w, h)],
+            NibShape::Contour(points) => {
+                if points.is_empty() {
+                    return vec![];
+                }
+                let (mut min, mut max) = ((f64::MAX, f64::MAX), (f64::MIN, f64::MIN));
+                for (x, y) in points {
+                    min = (min.0.min(*x as f64), min.1.min(*y as f64));
+                    max = (max.0.max(*x as f64), max.1.max(*y as f64));
+                }
+                let center = ((min.0 + max.0) / 2., (min.1 + max.1) / 2.);
+                let scale = (
+                    w * 2. / (max.0 - min.0).max(f64::EPSILON),
+                    h * 2. / (max.1 - min.1).max(f64::EPSILON),
+                );
+                points
+                    .iter()
+                    .map(|(x, y)| {
+                        (
+                            (*x as f64 - center.0) * scale.0,
+                            (*y as f64 - center.1) * scale.1,
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// The nib's angle, in degrees, and size at point `idx`.
+    fn at_point(&self, idx: usize) -> (f64, f64) {
+        let handle = self.handles.get(idx).copied().unwrap_or_default();
+        (
+            handle.angle.unwrap_or(self.angle) as f64,
+            handle.scale.unwrap_or(1.) as f64,
+        )
+    }
+
+    /// The nib's outline placed at `center`, turned by `angle` degrees and scaled by `scale`.
+    pub fn polygon_at(&self, center: Vec2, angle: f64, scale: f64) -> Vec<Vec2> {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        self.base_polygon()
+            .into_iter()
+            .map(|(x, y)| {
+                let (x, y) = (x * scale, y * scale);
+                (center.0 + x * cos - y * sin, center.1 + x * sin + y * cos)
+            })
+            .collect()
+    }
+
+    /// The nib as it's placed on the point at `idx`.
+    pub fn polygon_at_point(&self, center: Vec2, idx: usize) -> Vec<Vec2> {
+        let (angle, scale) = self.at_point(idx);
+        self.polygon_at(center, angle, scale)
+    }
+}
+
+fn add_polygon(path: &mut Path, polygon: &[Vec2]) {
+    let points: Vec<SkPoint> = polygon
+        .iter()
+        .map(|(x, y)| SkPoint::new(*x as f32, *y as f32))
+        .collect();
+    path.add_poly(&points, true);
+}
+
+impl ContourOperationBuild for NibContour {
+    fn build(&self, contour: &MFEKContour<MFEKPointData>) -> MFEKOutline<MFEKPointData> {
+        let cubic = contour.to_cubic();
+        let points = cubic.cubic().unwrap();
+        let len = points.len();
+        if len == 0 {
+            return vec![];
+        }
+
+        // Every sweep between two placements of the nib is the convex hull of both, which is exact
+        // as the nib is convex. They all wind the same way, so simplifying the path unions them.
+        let mut path = Path::new();
+        let seg_count = if cubic.is_open() { len - 1 } else { len };
+        if seg_count == 0 {
+            let point = &points[0];
+            add_polygon(
+                &mut path,
+                &self.polygon_at_point((point.x as f64, point.y as f64), 0),
+            );
+        }
+        for seg_idx in 0..seg_count {
+            let next_idx = (seg_idx + 1) % len;
+            let bezier = Bezier::from(&points[seg_idx], &points[next_idx]);
+            let ((angle0, scale0), (angle1, scale1)) =
+                (self.at_point(seg_idx), self.at_point(next_idx));
+
+            let mut previous: Option<Vec<Vec2>> = None;
+            for step in 0..=SWEEP_SAMPLES {
+                let t = step as f64 / SWEEP_SAMPLES as f64;
+                let at = bezier.at(t);
+                let polygon = self.polygon_at(
+                    (at.x, at.y),
+                    angle0 + (angle1 - angle0) * t,
+                    scale0 + (scale1 - scale0) * t,
+                );
+                if let Some(mut sweep) = previous.replace(polygon.clone()) {
+                    sweep.extend(polygon);
+                    add_polygon(&mut path, &convex_hull(sweep));
+                }
+            }
+        }
+
+        let path = path.simplify().unwrap_or(path);
+        let outline: Outline<MFEKPointData> = Outline::from_skia_path(&path);
+        outline.iter().map(|c| c.into()).collect()
+    }
+}
+
+/// A nib shaped like a closed contour, flattened into a polygon. Sweeping a nib that isn't convex
+/// would need more than the hulls of its placements, so those are refused.
+pub fn shape_from_contour(contour: &MFEKContour<MFEKPointData>) -> Result<NibShape, String> {
+    if contour.is_open() {
+        return Err("A nib must be a closed contour.".to_string());
+    }
+    let cubic = contour.to_cubic();
+    let points = cubic.cubic().unwrap();
+    let len = points.len();
+    if len < 2 {
+        return Err("A nib needs at least two points.".to_string());
+    }
+
+    let mut polygon = vec![];
+    for seg_idx in 0..len {
+        let bezier = Bezier::from(&points[seg_idx], &points[(seg_idx + 1) % len]);
+        for step in 0..SWEEP_SAMPLES {
+            let at = bezier.at(step as f64 / SWEEP_SAMPLES as f64);
+            polygon.push((at.x as f32, at.y as f32));
+        }
+    }
+
+    let as_f64: Vec<Vec2> = polygon
+        .iter()
+        .map(|(x, y)| (*x as f64, *y as f64))
+        .collect();
+    if !is_convex(&as_f64) {
+        return Err("Only convex contours can be used as nibs.".to_string());
+    }
+    Ok(NibShape::Contour(polygon))
+}
+
+#[test]
+fn convex_hull_test() {
+    // a square with a point inside it and one on an edge
+    let points = vec![
+        (0., 0.),
+        (10., 0.),
+        (10., 10.),
+        (0., 10.),
+        (5., 5.),
+        (5., 0.),
+    ];
+    let hull = convex_hull(points);
+    assert_eq!(hull, vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.)]);
+    assert!(is_convex(&hull));
+
+    // an arrowhead, notched on one side
+    let arrow = vec![(0., 0.), (10., 5.), (0., 10.), (3., 5.)];
+    assert!(!is_convex(&arrow));
+    assert_eq!(convex_hull(arrow), vec![(0., 0.), (10., 5.), (0., 10.)]);
+
+    // a pentagram turns the same way at every vertex, but twice around
+    let star: Vec<Vec2> = (0..5)
+        .map(|i| {
+            let theta = (i * 2) as f64 / 5. * std::f64::consts::TAU;
+            (theta.cos(), theta.sin())
+        })
+        .collect();
+    assert!(!is_convex(&star));
+}
@@ -0,0 +1,49 @@
+use glifparser::glif::{MFEKContour, MFEKOutline};
+use glifparser::MFEKPointData;
+use serde::{Deserialize, Serialize};
+use MFEKmath::mfek::ResolveCubic;
+
+use super::ContourOperationBuild;
+use crate::editor::corners::{treat_corners_by, CornerParams, CornerStyle};
+
+/// The radius a single point's corner is rounded by. Zero leaves the corner sharp.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoundedCornerHandle {
+    pub radius: f32,
+}
+
+/// Rounds every corner of a contour, each by the radius stored for its point, the way
+/// [`VWSContour`](glifparser::glif::contour_operations::vws::VWSContour) stores a handle per point.
+/// glifparser can't store it, so it's kept in the editor's contour data.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoundedCornersContour {
+    pub handles: Vec<RoundedCornerHandle>,
+}
+
+impl RoundedCornersContour {
+    /// Rounds all `len` points of a contour by the same radius.
+    pub fn new(len: usize, radius: f32) -> Self {
+        Self {
+            handles: vec![RoundedCornerHandle { radius }; len],
+        }
+    }
+}
+
+impl ContourOperationBuild for RoundedCornersContour {
+    fn build(&self, contour: &MFEKContour<MFEKPointData>) -> MFEKOutline<MFEKPointData> {
+        let cubic = contour.to_cubic();
+        let rounded = treat_corners_by(&cubic, |pi| {
+            self.handles
+                .get(pi)
+                .filter(|handle| handle.radius > 0.)
+                .map(|handle| CornerParams {
+                    style: CornerStyle::Round,
+                    radius: handle.radius,
+                    ..CornerParams::default()
+                })
+        })
+        .unwrap_or(cubic);
+
+        vec![MFEKContour::new(rounded.inner().clone(), None)]
+    }
+}
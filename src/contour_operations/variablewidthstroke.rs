@@ -1,27 +1,77 @@
 use glifparser::glif::contour_operations::vws::VWSContour;
 use glifparser::glif::{MFEKContour, MFEKOutline};
-use glifparser::MFEKPointData;
+use glifparser::{CapType, Glif, MFEKPointData};
+use serde::{Deserialize, Serialize};
 use MFEKmath::mfek::ResolveCubic;
 use MFEKmath::{variable_width_stroke, Piecewise, VWSSettings};
 
 use super::ContourOperationBuild;
 
-impl ContourOperationBuild for VWSContour {
-    fn build(&self, contour: &MFEKContour<MFEKPointData>) -> MFEKOutline<MFEKPointData> {
-        let contour_pw = Piecewise::from(contour.to_cubic());
+/// Outlines drawn at the ends of a variable width stroke whose cap type is custom, each fitted to
+/// the stroke's width there.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VWSCaps {
+    pub start: Option<MFEKOutline<MFEKPointData>>,
+    pub end: Option<MFEKOutline<MFEKPointData>>,
+}
 
-        let settings = VWSSettings::<MFEKPointData> {
-            cap_custom_start: None,
-            cap_custom_end: None,
-        };
+impl VWSCaps {
+    pub fn is_empty(&self) -> bool {
+        self.start.is_none() && self.end.is_none()
+    }
+}
 
-        let vws_output = variable_width_stroke(&contour_pw, self, &settings);
+/// A cap made from an outline, as the glyph MFEKmath expects custom caps to be given as.
+fn cap_from_outline(outline: &MFEKOutline<MFEKPointData>) -> Glif<MFEKPointData> {
+    let mut glif = Glif::default();
+    glif.outline = Some(
+        outline
+            .iter()
+            .filter_map(|contour| contour.to_cubic().cubic().cloned())
+            .collect(),
+    );
+    glif
+}
+
+/// A custom cap type with no cap to draw, as when the cap was lost, falls back to round.
+fn cap_type_or_round(cap_type: CapType, cap: &Option<MFEKOutline<MFEKPointData>>) -> CapType {
+    if cap_type == CapType::Custom && cap.is_none() {
+        log::warn!("A variable width stroke has a custom cap type but no cap, using round caps.");
+        CapType::Round
+    } else {
+        cap_type
+    }
+}
 
-        let mut output: MFEKOutline<MFEKPointData> = Vec::new();
-        for contour in vws_output.segs {
-            output.push(contour.to_contour().into());
-        }
+/// Builds a variable width stroke with custom caps.
+pub fn build_with_caps(
+    data: &VWSContour,
+    contour: &MFEKContour<MFEKPointData>,
+    caps: &VWSCaps,
+) -> MFEKOutline<MFEKPointData> {
+    let contour_pw = Piecewise::from(contour.to_cubic());
 
-        output
+    let mut data = data.clone();
+    data.cap_start_type = cap_type_or_round(data.cap_start_type, &caps.start);
+    data.cap_end_type = cap_type_or_round(data.cap_end_type, &caps.end);
+    let settings = VWSSettings::<MFEKPointData> {
+        cap_custom_start: caps.start.as_ref().map(cap_from_outline),
+        cap_custom_end: caps.end.as_ref().map(cap_from_outline),
+    };
+
+    let vws_output = variable_width_stroke(&contour_pw, &data, &settings);
+
+    let mut output: MFEKOutline<MFEKPointData> = Vec::new();
+    for contour in vws_output.segs {
+        output.push(contour.to_contour().into());
+    }
+
+    output
+}
+
+impl ContourOperationBuild for VWSContour {
+    fn build(&self, contour: &MFEKContour<MFEKPointData>) -> MFEKOutline<MFEKPointData> {
+        build_with_caps(self, contour, &VWSCaps::default())
     }
 }
@@ -0,0 +1,197 @@
+use glifparser::glif::{contour::MFEKContourCommon, point::MFEKPointCommon};
+
+use super::Editor;
+use crate::command::Command;
+
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// Line up the horizontal centers, on a vertical line.
+    HorizontalCenter,
+    /// Line up the vertical centers, on a horizontal line.
+    VerticalCenter,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DistributeAxis {
+    Horizontal,
+    Vertical,
+}
+
+impl Alignment {
+    pub fn from_command(command: Command) -> Option<Self> {
+        Some(match command {
+            Command::AlignLeft => Self::Left,
+            Command::AlignRight => Self::Right,
+            Command::AlignTop => Self::Top,
+            Command::AlignBottom => Self::Bottom,
+            Command::AlignHorizontalCenter => Self::HorizontalCenter,
+            Command::AlignVerticalCenter => Self::VerticalCenter,
+            _ => return None,
+        })
+    }
+}
+
+impl DistributeAxis {
+    pub fn from_command(command: Command) -> Option<Self> {
+        Some(match command {
+            Command::DistributeHorizontally => Self::Horizontal,
+            Command::DistributeVertically => Self::Vertical,
+            _ => return None,
+        })
+    }
+}
+
+/// Something which is aligned as a unit: either a single point or a whole contour.
+#[derive(Clone, Debug)]
+struct AlignItem {
+    points: Vec<(usize, usize)>,
+    // left, bottom, right, top of the on-curve points
+    bounds: (f32, f32, f32, f32),
+}
+
+impl AlignItem {
+    fn center(&self) -> (f32, f32) {
+        let (l, b, r, t) = self.bounds;
+        ((l + r) / 2., (b + t) / 2.)
+    }
+}
+
+impl Editor {
+    /// Groups the selection into the things we align. If every selected point belongs to a fully
+    /// selected contour, and there's more than one of them, contours move as units. Otherwise each
+    /// selected on-curve point moves on its own.
+    fn align_items(&self) -> Vec<AlignItem> {
+        let selection = self.transform_selection();
+        let layer = self.get_active_layer_ref();
+
+        let mut by_contour: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (ci, pi) in &selection {
+            by_contour.entry(*ci).or_default().push(*pi);
+        }
+
+        let whole_contours = by_contour.len() > 1
+            && by_contour
+                .iter()
+                .all(|(ci, pis)| pis.len() == layer.outline[*ci].len());
+
+        let groups: Vec<Vec<(usize, usize)>> = if whole_contours {
+            by_contour
+                .into_iter()
+                .map(|(ci, pis)| pis.into_iter().map(|pi| (ci, pi)).collect())
+                .collect()
+        } else {
+            let mut points: Vec<_> = selection.into_iter().collect();
+            points.sort_unstable();
+            points.into_iter().map(|p| vec![p]).collect()
+        };
+
+        groups
+            .into_iter()
+            .map(|points| {
+                let mut bounds = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+                for (ci, pi) in &points {
+                    let point = layer.outline[*ci].get_point(*pi).unwrap();
+                    bounds.0 = bounds.0.min(point.x());
+                    bounds.1 = bounds.1.min(point.y());
+                    bounds.2 = bounds.2.max(point.x());
+                    bounds.3 = bounds.3.max(point.y());
+                }
+                AlignItem { points, bounds }
+            })
+            .collect()
+    }
+
+    /// Moves each item by its offset, with handles following their points.
+    fn move_align_items(&mut self, description: &str, moves: Vec<(AlignItem, (f32, f32))>) {
+        let moves: Vec<_> = moves
+            .into_iter()
+            .filter(|(_, (dx, dy))| *dx != 0. || *dy != 0.)
+            .collect();
+        if moves.is_empty() {
+            return;
+        }
+
+        self.begin_modification(description, false);
+        let layer = self.get_active_layer_mut();
+        let mut moved = HashSet::new();
+        for (item, (dx, dy)) in moves {
+            for (ci, pi) in item.points {
+                if !moved.insert((ci, pi)) {
+                    continue;
+                }
+                let point = layer.outline[ci].get_point_mut(pi).unwrap();
+                point.set_position(point.x() + dx, point.y() + dy);
+            }
+        }
+        self.end_modification();
+    }
+
+    pub fn align_selection(&mut self, alignment: Alignment) {
+        let items = self.align_items();
+        if items.len() < 2 {
+            return;
+        }
+
+        let left = items.iter().map(|i| i.bounds.0).fold(f32::MAX, f32::min);
+        let bottom = items.iter().map(|i| i.bounds.1).fold(f32::MAX, f32::min);
+        let right = items.iter().map(|i| i.bounds.2).fold(f32::MIN, f32::max);
+        let top = items.iter().map(|i| i.bounds.3).fold(f32::MIN, f32::max);
+
+        let moves = items
+            .into_iter()
+            .map(|item| {
+                let (l, b, r, t) = item.bounds;
+                let (cx, cy) = item.center();
+                let offset = match alignment {
+                    Alignment::Left => (left - l, 0.),
+                    Alignment::Right => (right - r, 0.),
+                    Alignment::Top => (0., top - t),
+                    Alignment::Bottom => (0., bottom - b),
+                    Alignment::HorizontalCenter => ((left + right) / 2. - cx, 0.),
+                    Alignment::VerticalCenter => (0., (bottom + top) / 2. - cy),
+                };
+                (item, offset)
+            })
+            .collect();
+
+        self.move_align_items("Align selection.", moves);
+    }
+
+    /// Spaces the centers of the selected items evenly between the two outermost ones.
+    pub fn distribute_selection(&mut self, axis: DistributeAxis) {
+        let mut items = self.align_items();
+        if items.len() < 3 {
+            return;
+        }
+
+        let along = |item: &AlignItem| match axis {
+            DistributeAxis::Horizontal => item.center().0,
+            DistributeAxis::Vertical => item.center().1,
+        };
+        items.sort_by(|a, b| along(a).partial_cmp(&along(b)).unwrap());
+
+        let first = along(&items[0]);
+        let step = (along(items.last().unwrap()) - first) / (items.len() - 1) as f32;
+
+        let moves = items
+            .into_iter()
+            .enumerate()
+            .map(|(idx, item)| {
+                let delta = first + step * idx as f32 - along(&item);
+                let offset = match axis {
+                    DistributeAxis::Horizontal => (delta, 0.),
+                    DistributeAxis::Vertical => (0., delta),
+                };
+                (item, offset)
+            })
+            .collect();
+
+        self.move_align_items("Distribute selection.", moves);
+    }
+}
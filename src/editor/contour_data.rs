@@ -0,0 +1,533 @@
+//! Data the editor keeps for contours which glifparser's types have nowhere to store: operations
+//! `ContourOperations` has no variant for, and where their settings came from. It's saved into
+//! .glifjson files beside the glyph, and taken into the undo history with it.
+//!
+//! The data is kept in the order of each layer's contours. Edits don't say which contours they
+//! added, removed or moved, so afterwards the data is matched back up with the contours by what
+//! they looked like before (see [`ContourDataStore::reconcile`]).
+
+use glifparser::glif::contour::MFEKContourCommon;
+use glifparser::glif::contour_operations::ContourOperations;
+use glifparser::glif::point::MFEKPointCommon;
+use glifparser::glif::{MFEKContour, MFEKGlif, MFEKOutline};
+use glifparser::MFEKPointData;
+use serde::{Deserialize, Serialize};
+
+use super::Editor;
+use crate::contour_operations::chain::{ChainOperation, ChainedOperation, OperationChain};
+use crate::contour_operations::dashalongpath::DashExtras;
+use crate::contour_operations::expandstroke::ExpandStrokeContour;
+use crate::contour_operations::nib::NibContour;
+use crate::contour_operations::roundedcorners::RoundedCornersContour;
+use crate::contour_operations::variablewidthstroke::VWSCaps;
+use crate::contour_operations::ContourOperationBuild;
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash as _, Hasher as _};
+
+/// The key contour data is saved under in .glifjson files.
+pub const GLIFJSON_KEY: &str = "com.mfek.glif.contour_data";
+
+// Past this many cells, points are assumed to have kept their indices rather than lined up.
+const MAX_ALIGNMENT_CELLS: usize = 1 << 22;
+// How much of a contour which was split off or joined from others has to be points one of them
+// had for it to take that one's data.
+const MIN_SHARED_POINTS: f32 = 1. / 3.;
+
+/// A contour operation glifparser's `ContourOperations` can't store.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum EditorOperation {
+    RoundedCorners(RoundedCornersContour),
+    ExpandStroke(ExpandStrokeContour),
+    Nib(NibContour),
+}
+
+impl EditorOperation {
+    /// Moves per-point data along with its points. `map` has the old index of every point, or
+    /// `None` where a point is new.
+    fn remap_points(&mut self, map: &[Option<usize>]) {
+        match self {
+            EditorOperation::RoundedCorners(data) => {
+                data.handles = remap_per_point(&data.handles, map, Default::default())
+            }
+            EditorOperation::Nib(data) => {
+                data.handles = remap_per_point(&data.handles, map, Default::default())
+            }
+            EditorOperation::ExpandStroke(_) => (),
+        }
+    }
+}
+
+impl ContourOperationBuild for EditorOperation {
+    fn build(&self, contour: &MFEKContour<MFEKPointData>) -> MFEKOutline<MFEKPointData> {
+        match self {
+            EditorOperation::RoundedCorners(data) => data.build(contour),
+            EditorOperation::ExpandStroke(data) => data.build(contour),
+            EditorOperation::Nib(data) => data.build(contour),
+        }
+    }
+}
+
+/// Everything the editor keeps for one contour.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContourData {
+    /// Built instead of the contour's own operation, which is left unset.
+    pub operation: Option<EditorOperation>,
+    /// Operations built after the contour's own, on what it built.
+    pub chain: OperationChain,
+    /// The glyph a Pattern Along Path took its pattern from, whose changes it's refreshed with.
+    pub pattern_source: Option<String>,
+    /// The custom caps of a variable width stroke.
+    pub vws_caps: VWSCaps,
+    /// A dash's phase and per-segment patterns.
+    pub dash_extras: DashExtras,
+}
+
+impl ContourData {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Moves per-point data along with its points. `map` has the old index of every point of a
+    /// contour which had `old_len` points, or `None` where a point is new.
+    fn remap_points(&mut self, map: &[Option<usize>], old_len: usize) {
+        self.dash_extras.remap_segments(map, old_len);
+        if let Some(operation) = self.operation.as_mut() {
+            operation.remap_points(map);
+        }
+        for chained in self.chain.operations.iter_mut() {
+            if let ChainOperation::Editor(operation) = &mut chained.operation {
+                operation.remap_points(map);
+            }
+        }
+    }
+}
+
+/// Per-point values moved to follow their points, as `map` gives the old index of every point.
+/// New points get `default`.
+pub fn remap_per_point<T: Clone>(values: &[T], map: &[Option<usize>], default: T) -> Vec<T> {
+    map.iter()
+        .map(|old| {
+            old.and_then(|old| values.get(old).cloned())
+                .unwrap_or_else(|| default.clone())
+        })
+        .collect()
+}
+
+/// Where a contour's points were when its data was last matched with it. The hash is compared
+/// first, as the quicker check.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Fingerprint {
+    hash: u64,
+    points: Vec<(u32, u32)>,
+}
+
+impl Fingerprint {
+    fn of(contour: &MFEKContour<MFEKPointData>) -> Self {
+        let points: Vec<_> = (0..contour.len())
+            .filter_map(|pi| contour.get_point(pi))
+            .map(|point| (point.x().to_bits(), point.y().to_bits()))
+            .collect();
+        let mut hasher = DefaultHasher::new();
+        points.hash(&mut hasher);
+        Self {
+            hash: hasher.finish(),
+            points,
+        }
+    }
+}
+
+/// For every point of `new`, the index it had in `old`, found by lining the points up where they
+/// didn't move. When the number of points didn't change, they're taken to have kept their indices.
+fn align_points(old: &[(u32, u32)], new: &[(u32, u32)]) -> Vec<Option<usize>> {
+    let (n, m) = (old.len(), new.len());
+    if n == m || (n + 1) * (m + 1) > MAX_ALIGNMENT_CELLS {
+        return (0..m).map(|i| (i < n).then_some(i)).collect();
+    }
+
+    // Longest common subsequence, walked back from the end.
+    let mut lengths = vec![0u32; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[at(i, j)] = if old[i] == new[j] {
+                lengths[at(i + 1, j + 1)] + 1
+            } else {
+                lengths[at(i + 1, j)].max(lengths[at(i, j + 1)])
+            };
+        }
+    }
+
+    let mut map = vec![None; m];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            map[j] = Some(i);
+            i += 1;
+            j += 1;
+        } else if lengths[at(i + 1, j)] >= lengths[at(i, j + 1)] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    map
+}
+
+/// For every point of `new`, the index of a point of `old` at the same place, if any. Unlike
+/// [`align_points`] this doesn't keep the points in order, as where a contour was split or joined
+/// with others, its points can start anywhere.
+fn shared_points(old: &[(u32, u32)], new: &[(u32, u32)]) -> Vec<Option<usize>> {
+    let mut at: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+    for (i, point) in old.iter().enumerate().rev() {
+        at.entry(*point).or_default().push(i);
+    }
+    new.iter()
+        .map(|point| at.get_mut(point).and_then(|indices| indices.pop()))
+        .collect()
+}
+
+/// The data of every contour of a glyph, by layer and contour index.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ContourDataStore {
+    layers: Vec<Vec<ContourData>>,
+    // Per layer and contour, what the contours looked like when the data was last matched to
+    // them. Empty when the data was just read from a file.
+    #[serde(skip)]
+    fingerprints: Vec<Vec<Fingerprint>>,
+}
+
+impl ContourDataStore {
+    pub fn is_empty(&self) -> bool {
+        self.layers.iter().flatten().all(ContourData::is_empty)
+    }
+
+    /// Whether any contour of a layer has an operation which means the layer is unioned when it's
+    /// exported.
+    pub fn unions_layer(&self, layer_idx: usize) -> bool {
+        self.layers.get(layer_idx).map_or(false, |layer| {
+            layer.iter().any(|data| {
+                matches!(data.operation, Some(EditorOperation::ExpandStroke(_)))
+                    || data.chain.operations.iter().any(|c| {
+                        c.enabled
+                            && matches!(
+                                c.operation,
+                                ChainOperation::Editor(EditorOperation::ExpandStroke(_))
+                            )
+                    })
+            })
+        })
+    }
+
+    pub fn get(&self, layer_idx: usize, contour_idx: usize) -> Option<&ContourData> {
+        self.layers
+            .get(layer_idx)?
+            .get(contour_idx)
+            .filter(|data| !data.is_empty())
+    }
+
+    /// Sets a contour's data, first matching the store up with the glyph.
+    pub fn set(
+        &mut self,
+        glyph: &MFEKGlif<MFEKPointData>,
+        layer_idx: usize,
+        contour_idx: usize,
+        data: ContourData,
+    ) {
+        if data.is_empty() && self.get(layer_idx, contour_idx).is_none() {
+            return;
+        }
+        // Reconciling an empty store clears it, and it's then sized to the glyph afresh.
+        self.reconcile(glyph);
+        self.adopt(glyph);
+        self.layers[layer_idx][contour_idx] = data;
+    }
+
+    /// Matches the data up with the glyph's contours by index, as it is when read from a file.
+    fn adopt(&mut self, glyph: &MFEKGlif<MFEKPointData>) {
+        if !self.fingerprints.is_empty() {
+            return;
+        }
+        self.layers.resize(glyph.layers.len(), vec![]);
+        for (layer, data) in glyph.layers.iter().zip(self.layers.iter_mut()) {
+            data.resize(layer.outline.len(), ContourData::default());
+            self.fingerprints
+                .push(layer.outline.iter().map(Fingerprint::of).collect());
+        }
+    }
+
+    /// Matches the data back up with the glyph's contours after they were edited. Contours whose
+    /// points are all where they were keep their data wherever they moved. Of the rest, if as many
+    /// contours changed as disappeared, they're taken to have been edited in place, and their
+    /// per-point data follows the points that didn't move. Otherwise contours were split or
+    /// joined, and each takes the data of the old contour it shares the most points with, if it
+    /// shares enough. Data no contour takes is dropped.
+    pub fn reconcile(&mut self, glyph: &MFEKGlif<MFEKPointData>) {
+        if self.is_empty() {
+            self.layers.clear();
+            self.fingerprints.clear();
+            return;
+        }
+        self.adopt(glyph);
+
+        let mut layers = vec![];
+        let mut fingerprints = vec![];
+        for (li, layer) in glyph.layers.iter().enumerate() {
+            let old_data = self
+                .layers
+                .get_mut(li)
+                .map(std::mem::take)
+                .unwrap_or_default();
+            let old_prints = self
+                .fingerprints
+                .get_mut(li)
+                .map(std::mem::take)
+                .unwrap_or_default();
+            let new_prints: Vec<_> = layer.outline.iter().map(Fingerprint::of).collect();
+
+            let mut from: Vec<Option<usize>> = vec![None; new_prints.len()];
+            let mut taken = vec![false; old_prints.len()];
+            for (ni, print) in new_prints.iter().enumerate() {
+                let same = |oi: usize| !taken[oi] && old_prints[oi] == *print;
+                let oi = Some(ni)
+                    .filter(|&oi| oi < old_prints.len() && same(oi))
+                    .or_else(|| (0..old_prints.len()).find(|&oi| same(oi)));
+                if let Some(oi) = oi {
+                    taken[oi] = true;
+                    from[ni] = Some(oi);
+                }
+            }
+            let unmatched_old: Vec<_> = (0..old_prints.len()).filter(|&oi| !taken[oi]).collect();
+            let unmatched_new: Vec<_> = (0..new_prints.len())
+                .filter(|&ni| from[ni].is_none())
+                .collect();
+            let edited = unmatched_old.len() == unmatched_new.len();
+
+            let mut data = vec![ContourData::default(); new_prints.len()];
+            for (ni, slot) in data.iter_mut().enumerate() {
+                if let Some(oi) = from[ni] {
+                    *slot = old_data.get(oi).cloned().unwrap_or_default();
+                }
+            }
+            let mut kept = vec![false; old_prints.len()];
+            if edited {
+                for (&oi, &ni) in unmatched_old.iter().zip(unmatched_new.iter()) {
+                    let mut moved = old_data.get(oi).cloned().unwrap_or_default();
+                    if old_prints[oi].points.len() != new_prints[ni].points.len() {
+                        let old_points = &old_prints[oi].points;
+                        let map = align_points(old_points, &new_prints[ni].points);
+                        moved.remap_points(&map, old_points.len());
+                    }
+                    data[ni] = moved;
+                    kept[oi] = true;
+                }
+            } else {
+                for &ni in &unmatched_new {
+                    let new_points = &new_prints[ni].points;
+                    let best = unmatched_old
+                        .iter()
+                        .filter(|&&oi| old_data.get(oi).map_or(false, |d| !d.is_empty()))
+                        .map(|&oi| (oi, shared_points(&old_prints[oi].points, new_points)))
+                        .max_by_key(|(_, map)| map.iter().flatten().count());
+                    let (oi, map) = match best {
+                        Some(best) => best,
+                        None => continue,
+                    };
+                    let shared = map.iter().flatten().count();
+                    if shared > 0 && shared as f32 >= new_points.len() as f32 * MIN_SHARED_POINTS {
+                        let mut moved = old_data[oi].clone();
+                        moved.remap_points(&map, old_prints[oi].points.len());
+                        data[ni] = moved;
+                        kept[oi] = true;
+                    }
+                }
+            }
+            for &oi in unmatched_old.iter().filter(|&&oi| !kept[oi]) {
+                if old_data.get(oi).map_or(false, |d| !d.is_empty()) {
+                    log::warn!(
+                        "Dropped the editor data of contour {} in layer {}, as no contour matches it any more",
+                        oi,
+                        li
+                    );
+                }
+            }
+
+            layers.push(data);
+            fingerprints.push(new_prints);
+        }
+        self.layers = layers;
+        self.fingerprints = fingerprints;
+    }
+
+    /// Keeps the data in step with a layer inserted into the glyph at `idx`.
+    pub fn insert_layer(&mut self, idx: usize) {
+        if idx <= self.layers.len() {
+            self.layers.insert(idx, vec![]);
+        }
+        if idx <= self.fingerprints.len() && !self.fingerprints.is_empty() {
+            self.fingerprints.insert(idx, vec![]);
+        }
+    }
+
+    /// Keeps the data in step with the layer at `idx` being removed from the glyph.
+    pub fn remove_layer(&mut self, idx: usize) {
+        if idx < self.layers.len() {
+            self.layers.remove(idx);
+        }
+        if idx < self.fingerprints.len() {
+            self.fingerprints.remove(idx);
+        }
+    }
+
+    /// Keeps the data in step with two of the glyph's layers trading places.
+    pub fn swap_layers(&mut self, a: usize, b: usize) {
+        if a.max(b) < self.layers.len() {
+            self.layers.swap(a, b);
+        }
+        if a.max(b) < self.fingerprints.len() {
+            self.fingerprints.swap(a, b);
+        }
+    }
+
+    /// Takes contour data saved into a .glifjson file out of its JSON, so the rest can be read as
+    /// a glyph.
+    pub fn take_from_json(json: &mut serde_json::Value) -> Self {
+        json.as_object_mut()
+            .and_then(|object| object.remove(GLIFJSON_KEY))
+            .map(|data| {
+                serde_json::from_value(data).unwrap_or_else(|e| {
+                    log::error!("Failed to read contour data, it will be lost: {}", e);
+                    Self::default()
+                })
+            })
+            .unwrap_or_default()
+    }
+
+    /// Saves the contour data into a glyph's .glifjson JSON, if there's any.
+    pub fn put_into_json(&self, json: &mut serde_json::Value) {
+        if self.is_empty() {
+            return;
+        }
+        if let Some(object) = json.as_object_mut() {
+            object.insert(
+                GLIFJSON_KEY.to_string(),
+                serde_json::to_value(self).unwrap(),
+            );
+        }
+    }
+}
+
+impl Editor {
+    /// The data the editor keeps for a contour on the active layer, if there's any.
+    pub fn contour_data(&self, contour_idx: usize) -> Option<&ContourData> {
+        self.contour_data.get(self.get_active_layer(), contour_idx)
+    }
+
+    /// Sets the data of a contour on the active layer. Like `get_active_layer_mut`, this must be
+    /// called during a modification so it's undoable.
+    pub fn set_contour_data(&mut self, contour_idx: usize, data: ContourData) {
+        if !self.modifying {
+            panic!("A modification is not in progress!")
+        }
+
+        self.dirty = true;
+        self.mark_preview_dirty();
+        let layer_idx = self.get_active_layer();
+        self.contour_data
+            .set(self.glyph.as_ref().unwrap(), layer_idx, contour_idx, data);
+    }
+
+    /// The operations of a contour on the active layer in the order they're built: its own, or the
+    /// one kept in its data, then its chain. A contour with one operation is a stack of one.
+    pub fn operation_stack(&self, contour_idx: usize) -> Vec<ChainedOperation> {
+        let contour = &self.get_active_layer_ref().outline[contour_idx];
+        let data = self.contour_data(contour_idx);
+        let head = match data.and_then(|data| data.operation.clone()) {
+            Some(operation) => Some(ChainOperation::Editor(operation)),
+            None => contour.operation().clone().map(ChainOperation::Contour),
+        };
+
+        head.into_iter()
+            .map(ChainedOperation::new)
+            .chain(
+                data.into_iter()
+                    .flat_map(|d| d.chain.operations.iter().cloned()),
+            )
+            .collect()
+    }
+
+    /// Sets the operations of a contour on the active layer, in the order they're built. The first
+    /// is stored as the contour's own when it's enabled, so files stay readable by tools that don't
+    /// know about chains; the rest are kept in its data. Operations are made for the contour's own
+    /// points, so the first is stored as it is. Must be called during a modification.
+    pub fn set_operation_stack(&mut self, contour_idx: usize, mut stack: Vec<ChainedOperation>) {
+        let mut data = self.contour_data(contour_idx).cloned().unwrap_or_default();
+        let mut contour_operation = None;
+        data.operation = None;
+
+        if stack.first().map_or(false, |first| first.enabled) {
+            match stack.remove(0).operation {
+                ChainOperation::Contour(operation) => contour_operation = Some(operation),
+                ChainOperation::Editor(operation) => data.operation = Some(operation),
+            }
+        }
+        data.chain.operations = stack;
+
+        self.set_contour_data(contour_idx, data);
+        self.get_active_layer_mut().outline[contour_idx].set_operation(contour_operation);
+    }
+
+    /// Gives a contour on the active layer an operation glifparser stores, which replaces any its
+    /// data kept instead. Must be called during a modification.
+    pub fn set_contour_operation(
+        &mut self,
+        contour_idx: usize,
+        operation: Option<ContourOperations<MFEKPointData>>,
+    ) {
+        if let Some(mut data) = self.contour_data(contour_idx).cloned() {
+            if data.operation.take().is_some() {
+                self.set_contour_data(contour_idx, data);
+            }
+        }
+        self.get_active_layer_mut().outline[contour_idx].set_operation(operation);
+    }
+
+    /// Matches the contour data back up with the glyph's contours after they were edited.
+    pub(super) fn reconcile_contour_data(&mut self) {
+        if let Some(glyph) = self.glyph.as_ref() {
+            self.contour_data.reconcile(glyph);
+        }
+    }
+}
+
+#[test]
+fn align_points_test() {
+    let (a, b, c, d) = ((0, 0), (1, 0), (2, 0), (3, 0));
+    // a point inserted after the first
+    assert_eq!(
+        align_points(&[a, b, c], &[a, d, b, c]),
+        vec![Some(0), None, Some(1), Some(2)]
+    );
+    // the second point deleted
+    assert_eq!(align_points(&[a, b, c], &[a, c]), vec![Some(0), Some(2)]);
+    // points moved in place keep their indices
+    assert_eq!(align_points(&[a, b], &[c, d]), vec![Some(0), Some(1)]);
+}
+
+#[test]
+fn contour_data_chain_serde_test() {
+    // data saved before chains existed reads as a chain of none
+    let old: ContourData = serde_json::from_str(r#"{"operation":null}"#).unwrap();
+    assert!(old.chain.is_empty());
+    assert!(old.is_empty());
+
+    let mut data = ContourData::default();
+    data.chain.operations.push(ChainedOperation {
+        enabled: false,
+        operation: ChainOperation::Editor(EditorOperation::ExpandStroke(Default::default())),
+    });
+    let json = serde_json::to_string(&data).unwrap();
+    assert_eq!(serde_json::from_str::<ContourData>(&json).unwrap(), data);
+}
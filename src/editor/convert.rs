@@ -0,0 +1,509 @@
+//! Conversions between contour types.
+//!
+//! Quadratic to cubic is exact, a quadratic is just a cubic with its handles two thirds of the way to
+//! the off-curve point. Cubic to quadratic splits each segment into as many equal pieces as it
+//! needs for every piece's midpoint quadratic to be within the tolerance. Cubic to hyperbezier keeps
+//! the on-curve points and their handles as the hyperbezier's tangents.
+
+use glifparser::glif::{
+    contour::MFEKContourCommon,
+    contour_operations::ContourOperation,
+    inner::{hyper::MFEKHyperInner, MFEKContourInner, MFEKContourInnerType},
+    point::{
+        hyper::{HyperPoint, HyperPointType},
+        quad::QPoint,
+        MFEKPointCommon as _,
+    },
+    MFEKContour,
+};
+use glifparser::{Contour, Glif, Handle, MFEKPointData, PointData, PointType, WhichHandle};
+use MFEKmath::mfek::ResolveCubic;
+
+use super::Editor;
+
+use std::collections::BTreeSet;
+
+/// The most pieces we'll split a single cubic segment into.
+const MAX_QUAD_SPLITS: usize = 64;
+
+/// Which contours a conversion applies to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConvertScope {
+    /// Every contour with a selected point.
+    Selection,
+    Layer,
+}
+
+type Vec2 = (f64, f64);
+type CubicSegment = [Vec2; 4];
+
+fn lerp(a: Vec2, b: Vec2, t: f64) -> Vec2 {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+fn handle_or(handle: Handle, point: Vec2) -> Vec2 {
+    match handle {
+        Handle::At(x, y) => (x as f64, y as f64),
+        Handle::Colocated => point,
+    }
+}
+
+fn is_line<PD: PointData>(points: &Contour<PD>, seg_idx: usize) -> bool {
+    let next = (seg_idx + 1) % points.len();
+    points[seg_idx].a == Handle::Colocated && points[next].b == Handle::Colocated
+}
+
+fn cubic_segments<PD: PointData>(points: &Contour<PD>, open: bool) -> Vec<CubicSegment> {
+    let len = points.len();
+    let seg_count = if open { len.saturating_sub(1) } else { len };
+    (0..seg_count)
+        .map(|si| {
+            let (from, to) = (&points[si], &points[(si + 1) % len]);
+            let p0 = (from.x as f64, from.y as f64);
+            let p3 = (to.x as f64, to.y as f64);
+            [p0, handle_or(from.a, p0), handle_or(to.b, p3), p3]
+        })
+        .collect()
+}
+
+/// How many pieces a cubic segment must be split into to be approximated by quadratics. The midpoint
+/// quadratic of a cubic is off by at most √3/36 of the cubic's third difference, which shrinks with
+/// the cube of the number of pieces.
+fn quad_split_count(seg: &CubicSegment, tolerance: f64) -> usize {
+    let [p0, c1, c2, p3] = *seg;
+    let d = (
+        p3.0 - 3. * c2.0 + 3. * c1.0 - p0.0,
+        p3.1 - 3. * c2.1 + 3. * c1.1 - p0.1,
+    );
+    let error = 3f64.sqrt() / 36. * f64::hypot(d.0, d.1);
+    let pieces = (error / tolerance.max(f64::EPSILON)).cbrt().ceil() as usize;
+    pieces.clamp(1, MAX_QUAD_SPLITS)
+}
+
+/// Splits a cubic into `pieces` equal pieces and returns the off-curve and end point of each piece's
+/// quadratic.
+fn cubic_to_quad_pieces(seg: &CubicSegment, pieces: usize) -> Vec<(Vec2, Vec2)> {
+    let mut remaining = *seg;
+    let mut ret = Vec::with_capacity(pieces);
+    for k in 0..pieces {
+        let piece = if k + 1 == pieces {
+            remaining
+        } else {
+            let t = 1. / (pieces - k) as f64;
+            let [p0, c1, c2, p3] = remaining;
+            let (ab, bc, cd) = (lerp(p0, c1, t), lerp(c1, c2, t), lerp(c2, p3, t));
+            let (abc, bcd) = (lerp(ab, bc, t), lerp(bc, cd, t));
+            let mid = lerp(abc, bcd, t);
+            remaining = [mid, bcd, cd, p3];
+            [p0, ab, abc, mid]
+        };
+        let [p0, c1, c2, p3] = piece;
+        let control = (
+            (3. * (c1.0 + c2.0) - p0.0 - p3.0) / 4.,
+            (3. * (c1.1 + c2.1) - p0.1 - p3.1) / 4.,
+        );
+        ret.push((control, p3));
+    }
+    ret
+}
+
+/// Split counts for each segment of a contour, taking the largest count any of its curves needs in
+/// the other layers where it has the same number of points. A segment which is straight everywhere
+/// isn't split. Converting each layer in turn then gives compatible quadratics.
+fn compatible_split_counts<PD: PointData>(
+    others: &[(&Contour<PD>, bool)],
+    points: &Contour<PD>,
+    open: bool,
+    tolerance: f64,
+) -> Vec<usize> {
+    let curve_counts = |points: &Contour<PD>| -> Vec<usize> {
+        cubic_segments(points, open)
+            .iter()
+            .enumerate()
+            .map(|(si, seg)| {
+                if is_line(points, si) {
+                    1
+                } else {
+                    quad_split_count(seg, tolerance)
+                }
+            })
+            .collect()
+    };
+    let mut counts = curve_counts(points);
+
+    for (other, other_open) in others {
+        if other.len() != points.len() || *other_open != open {
+            continue;
+        }
+        for (count, other_count) in counts.iter_mut().zip(curve_counts(other)) {
+            *count = (*count).max(other_count);
+        }
+    }
+
+    counts
+}
+
+/// Converts a cubic contour to a quadratic one. New points are inserted into the contour operation
+/// so existing point data stays with its points.
+pub fn cubic_to_quad(
+    contour: &MFEKContour<MFEKPointData>,
+    split_counts: &[usize],
+) -> MFEKContour<MFEKPointData> {
+    let cubic = contour.to_cubic();
+    let points = cubic.cubic().unwrap();
+    let open = contour.is_open();
+
+    let mut quad_points = vec![];
+    let mut inserted = vec![];
+    for (si, seg) in cubic_segments(points, open).iter().enumerate() {
+        let original = &points[si];
+        let mut start = QPoint {
+            x: original.x,
+            y: original.y,
+            a: Handle::Colocated,
+            name: original.name.clone(),
+            ptype: original.ptype,
+            smooth: false,
+            data: original.data.clone(),
+        };
+
+        // A line which is a curve in another layer gets as many points as the curve, evenly
+        // spaced along it.
+        if is_line(points, si) {
+            quad_points.push(start);
+            let pieces = split_counts[si];
+            for k in 1..pieces {
+                let (x, y) = lerp(seg[0], seg[3], k as f64 / pieces as f64);
+                inserted.push(quad_points.len());
+                quad_points.push(QPoint {
+                    x: x as f32,
+                    y: y as f32,
+                    a: Handle::Colocated,
+                    name: None,
+                    ptype: PointType::Line,
+                    smooth: true,
+                    data: None,
+                });
+            }
+            continue;
+        }
+
+        let pieces = cubic_to_quad_pieces(seg, split_counts[si]);
+        let last = pieces.len() - 1;
+        for (k, (control, end)) in pieces.into_iter().enumerate() {
+            start.a = Handle::At(control.0 as f32, control.1 as f32);
+            quad_points.push(start);
+            if k == last {
+                break;
+            }
+            inserted.push(quad_points.len());
+            start = QPoint {
+                x: end.0 as f32,
+                y: end.1 as f32,
+                a: Handle::Colocated,
+                name: None,
+                ptype: PointType::Curve,
+                smooth: true,
+                data: None,
+            };
+        }
+    }
+    if open {
+        let original = points.last().unwrap();
+        quad_points.push(QPoint {
+            x: original.x,
+            y: original.y,
+            a: Handle::Colocated,
+            name: original.name.clone(),
+            ptype: original.ptype,
+            smooth: false,
+            data: original.data.clone(),
+        });
+    }
+
+    let mut ret = MFEKContour::new(MFEKContourInner::Quad(quad_points), contour.operation().clone());
+    for idx in inserted {
+        ret.operation_mut().insert_op(idx);
+    }
+    ret
+}
+
+/// The directions a contour leaves and arrives at point `idx` in: along its handles, or towards
+/// the other end of the segment where a handle is colocated.
+fn tangents<PD: PointData>(
+    points: &Contour<PD>,
+    idx: usize,
+    open: bool,
+) -> (Option<Vec2>, Option<Vec2>) {
+    let len = points.len();
+    let point = &points[idx];
+    let at = (point.x as f64, point.y as f64);
+    let direction = |handle: Handle, other: usize, other_handle: Handle| {
+        let other = (points[other].x as f64, points[other].y as f64);
+        [handle_or(handle, at), handle_or(other_handle, other), other]
+            .into_iter()
+            .map(|to| (to.0 - at.0, to.1 - at.1))
+            .find(|d| f64::hypot(d.0, d.1) > f64::EPSILON)
+    };
+    let next = (idx + 1) % len;
+    let prev = (idx + len - 1) % len;
+    let out = (!open || idx + 1 < len)
+        .then(|| direction(point.a, next, points[next].b))
+        .flatten();
+    let inc = (!open || idx > 0)
+        .then(|| direction(point.b, prev, points[prev].a))
+        .flatten();
+    (out, inc)
+}
+
+/// Converts a contour to a hyperbezier, placing a hyperbezier point at every on-curve point and
+/// keeping its handles, which the hyperbezier solver uses as tangents. Points which end a straight
+/// segment become line points, and points the contour passes straight through become smooth.
+pub fn to_hyper(contour: &MFEKContour<MFEKPointData>) -> MFEKContour<MFEKPointData> {
+    let cubic = contour.to_cubic();
+    let points = cubic.cubic().unwrap();
+    let open = contour.is_open();
+    let len = points.len();
+
+    let hyper_points = points
+        .iter()
+        .enumerate()
+        .map(|(idx, point)| {
+            let smooth = match tangents(points, idx, open) {
+                (Some(out), Some(inc)) => {
+                    let cross = out.0 * inc.1 - out.1 * inc.0;
+                    let dot = out.0 * inc.0 + out.1 * inc.1;
+                    let lengths = f64::hypot(out.0, out.1) * f64::hypot(inc.0, inc.1);
+                    dot < 0. && cross.abs() <= 0.01 * lengths
+                }
+                _ => false,
+            };
+            let arrives_straight = (!open || idx > 0) && is_line(points, (idx + len - 1) % len);
+            let ptype = if arrives_straight { HyperPointType::Line } else { HyperPointType::Curve };
+
+            let mut hyper = HyperPoint::new(point.x, point.y, ptype, smooth);
+            hyper.set_handle(WhichHandle::A, point.a);
+            hyper.set_handle(WhichHandle::B, point.b);
+            hyper
+        })
+        .collect();
+
+    MFEKContour::new(
+        MFEKContourInner::Hyper(MFEKHyperInner::new(hyper_points, open)),
+        contour.operation().clone(),
+    )
+}
+
+/// Rewrites an exported glyph's cubic outline as UFO quadratic curves, with each off-curve point
+/// stored in the following on-curve point's B handle. `others` are the glyph's other exported
+/// layers, whose contours are split the same way where they're compatible with this one's.
+pub fn quadratic_outline<PD: PointData>(
+    glif: &mut Glif<PD>,
+    others: &[&Glif<PD>],
+    tolerance: f32,
+) {
+    let outline = match glif.outline.as_mut() {
+        Some(outline) => outline,
+        None => return,
+    };
+    let is_open = |contour: &Contour<PD>| contour[0].ptype == PointType::Move;
+
+    for (ci, contour) in outline.iter_mut().enumerate() {
+        let len = contour.len();
+        if len < 2 {
+            continue;
+        }
+        let open = is_open(contour);
+        let other_contours: Vec<_> = others
+            .iter()
+            .filter_map(|other| other.outline.as_ref()?.get(ci))
+            .filter(|other| !other.is_empty())
+            .map(|other| (other, is_open(other)))
+            .collect();
+        let counts = compatible_split_counts(&other_contours, contour, open, tolerance as f64);
+
+        // Each segment contributes the points it arrives at: any points from splitting it, then
+        // its end point.
+        let mut quad: Contour<PD> = vec![];
+        if open {
+            let mut start = contour[0].clone();
+            start.a = Handle::Colocated;
+            quad.push(start);
+        }
+        for (si, seg) in cubic_segments(contour, open).iter().enumerate() {
+            let mut end = contour[(si + 1) % len].clone();
+            end.a = Handle::Colocated;
+
+            // A line which is a curve in another layer gets as many points as the curve, evenly
+            // spaced along it.
+            if is_line(contour, si) {
+                end.b = Handle::Colocated;
+                end.ptype = PointType::Line;
+                for k in 1..counts[si] {
+                    let (x, y) = lerp(seg[0], seg[3], k as f64 / counts[si] as f64);
+                    let mut point = end.clone();
+                    point.x = x as f32;
+                    point.y = y as f32;
+                    point.name = None;
+                    point.data = None;
+                    quad.push(point);
+                }
+                quad.push(end);
+                continue;
+            }
+
+            let pieces = cubic_to_quad_pieces(seg, counts[si]);
+            let last = pieces.len() - 1;
+            for (k, (control, piece_end)) in pieces.into_iter().enumerate() {
+                let mut point = end.clone();
+                if k != last {
+                    point.x = piece_end.0 as f32;
+                    point.y = piece_end.1 as f32;
+                    point.name = None;
+                    point.data = None;
+                }
+                point.b = Handle::At(control.0 as f32, control.1 as f32);
+                point.ptype = PointType::QCurve;
+                quad.push(point);
+            }
+        }
+        if !open {
+            // the last segment of a closed contour arrives back at its first point
+            quad.rotate_right(1);
+        }
+        *contour = quad;
+    }
+}
+
+impl Editor {
+    /// Indices of the contours in the active layer a conversion with this scope applies to.
+    pub fn conversion_targets(&self, scope: ConvertScope) -> Vec<usize> {
+        match scope {
+            ConvertScope::Selection => self
+                .transform_selection()
+                .into_iter()
+                .map(|(ci, _)| ci)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+            ConvertScope::Layer => (0..self.get_active_layer_ref().outline.len()).collect(),
+        }
+    }
+
+    /// Converts the contours at `targets` in the active layer to `to`. The tolerance is the largest
+    /// distance allowed between a cubic and its quadratic, and is only used when converting to
+    /// quadratic.
+    pub fn convert_contours(&mut self, targets: Vec<usize>, to: MFEKContourInnerType, tolerance: f32) {
+        let layer_idx = self.layer_idx.unwrap();
+        let targets: Vec<usize> = targets
+            .into_iter()
+            .filter(|ci| self.get_active_layer_ref().outline[*ci].get_type() != to)
+            .collect();
+        if targets.is_empty() {
+            return;
+        }
+
+        let converted: Vec<(usize, MFEKContour<MFEKPointData>)> = self.with_glyph(|glyph| {
+            let others: Vec<_> = glyph
+                .layers
+                .iter()
+                .enumerate()
+                .filter(|(li, _)| *li != layer_idx)
+                .map(|(_, layer)| layer)
+                .collect();
+            let layer = &glyph.layers[layer_idx];
+
+            targets
+                .iter()
+                .map(|ci| {
+                    let contour = &layer.outline[*ci];
+                    let new_contour = match to {
+                        MFEKContourInnerType::Cubic => contour.to_cubic(),
+                        MFEKContourInnerType::Quad => {
+                            let cubic = contour.to_cubic();
+                            let others: Vec<_> = others
+                                .iter()
+                                .filter_map(|layer| layer.outline.get(*ci))
+                                .map(|other| (other.to_cubic(), other.is_open()))
+                                .collect();
+                            let others: Vec<_> = others
+                                .iter()
+                                .map(|(other, open)| (other.cubic().unwrap(), *open))
+                                .collect();
+                            let counts = compatible_split_counts(
+                                &others,
+                                cubic.cubic().unwrap(),
+                                contour.is_open(),
+                                tolerance as f64,
+                            );
+                            cubic_to_quad(&cubic, &counts)
+                        }
+                        MFEKContourInnerType::Hyper => to_hyper(contour),
+                    };
+                    (*ci, new_contour)
+                })
+                .collect()
+        });
+
+        self.begin_modification("Converted contours.", false);
+        let layer = self.get_active_layer_mut();
+        for (ci, contour) in converted {
+            layer.outline[ci] = contour;
+        }
+        self.end_modification();
+
+        self.point_idx = None;
+        self.contour_idx = None;
+        self.selected.clear();
+    }
+}
+
+#[test]
+fn compatible_split_counts_test() {
+    // The same contour in two layers, straight in one and curved in the other.
+    let line: Contour<MFEKPointData> = vec![
+        glifparser::Point::from_x_y_type((0., 0.), PointType::Move),
+        glifparser::Point::from_x_y_type((300., 0.), PointType::Line),
+    ];
+    let mut curve = line.clone();
+    curve[0].a = Handle::At(0., 200.);
+    curve[1].b = Handle::At(300., 200.);
+    let (line, curve): (MFEKContour<_>, MFEKContour<_>) = (line.into(), curve.into());
+
+    let (line_points, curve_points) = (line.cubic().unwrap(), curve.cubic().unwrap());
+    let counts = compatible_split_counts(&[(curve_points, true)], line_points, true, 1.);
+    assert!(counts[0] > 1);
+    assert_eq!(counts, compatible_split_counts(&[(line_points, true)], curve_points, true, 1.));
+
+    // Both layers convert to the same number of points, the line's evenly spaced along it.
+    let (line, curve) = (cubic_to_quad(&line, &counts), cubic_to_quad(&curve, &counts));
+    assert_eq!(line.len(), curve.len());
+    assert_eq!(line.len(), counts[0] + 1);
+}
+
+#[test]
+fn quadratic_outline_test() {
+    // Exported layers are split the same way too.
+    let line: Contour<MFEKPointData> = vec![
+        glifparser::Point::from_x_y_type((0., 0.), PointType::Move),
+        glifparser::Point::from_x_y_type((300., 0.), PointType::Line),
+    ];
+    let mut curve = line.clone();
+    curve[0].a = Handle::At(0., 200.);
+    curve[1].b = Handle::At(300., 200.);
+    let mut line_glif = Glif::new();
+    line_glif.outline = Some(vec![line]);
+    let mut curve_glif = Glif::new();
+    curve_glif.outline = Some(vec![curve]);
+
+    let curve_other = curve_glif.clone();
+    quadratic_outline(&mut line_glif, &[&curve_other], 1.);
+    let line_other = line_glif.clone();
+    quadratic_outline(&mut curve_glif, &[&line_other], 1.);
+
+    let (line, curve) = (&line_glif.outline.unwrap()[0], &curve_glif.outline.unwrap()[0]);
+    assert!(line.len() > 2);
+    assert_eq!(line.len(), curve.len());
+    assert!(line[1..].iter().all(|p| p.ptype == PointType::Line && p.y == 0.));
+    assert!(curve[1..].iter().all(|p| p.ptype == PointType::QCurve));
+}
@@ -0,0 +1,406 @@
+//! Corner treatments. A sharp corner can be replaced by a round of a given radius, a straight
+//! chamfer, or an ink trap: a V shaped notch cut into the corner so ink spreading into it on press
+//! doesn't clog it.
+
+use glifparser::glif::{
+    contour::MFEKContourCommon, contour_operations::ContourOperation, MFEKContour,
+};
+use glifparser::{Contour, Handle, MFEKPointData, Point, PointType};
+use MFEKmath::{Bezier, Evaluate, Vector};
+
+use super::contour_data::EditorOperation;
+use super::Editor;
+use crate::contour_operations::roundedcorners::RoundedCornersContour;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+// Corners which turn by less than this many radians are considered smooth and left alone.
+const CORNER_ANGLE_EPSILON: f64 = 0.01;
+const SETBACK_BISECTIONS: usize = 40;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CornerStyle {
+    Round,
+    Chamfer,
+    InkTrap,
+}
+
+impl CornerStyle {
+    pub const ALL: [Self; 3] = [Self::Round, Self::Chamfer, Self::InkTrap];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Round => "Round",
+            Self::Chamfer => "Chamfer",
+            Self::InkTrap => "Ink trap",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CornerParams {
+    pub style: CornerStyle,
+    /// The radius of a round, or how far back along each side a chamfer starts.
+    pub radius: f32,
+    /// How far an ink trap's notch reaches past the corner.
+    pub depth: f32,
+    /// How wide an ink trap's notch is where it meets the outline.
+    pub width: f32,
+}
+
+impl Default for CornerParams {
+    fn default() -> Self {
+        Self {
+            style: CornerStyle::Round,
+            radius: 20.,
+            depth: 15.,
+            width: 20.,
+        }
+    }
+}
+
+fn position(point: &Point<MFEKPointData>) -> Vector {
+    Vector::from_components(point.x as f64, point.y as f64)
+}
+
+fn handle_or(handle: Handle, point: Vector) -> Vector {
+    match handle {
+        Handle::At(x, y) => Vector::from_components(x as f64, y as f64),
+        Handle::Colocated => point,
+    }
+}
+
+fn offset(point: Vector, dir: Vector, distance: f64) -> Vector {
+    Vector::from_components(point.x + dir.x * distance, point.y + dir.y * distance)
+}
+
+/// The directions the outline arrives at and leaves the point at `pi`, falling back to the
+/// neighbouring points' handles and then the neighbours themselves where handles are colocated.
+fn corner_directions(points: &Contour<MFEKPointData>, pi: usize) -> Option<(Vector, Vector)> {
+    let len = points.len();
+    let (prev, next) = (&points[(pi + len - 1) % len], &points[(pi + 1) % len]);
+    let here = position(&points[pi]);
+
+    let towards = |candidates: [Vector; 3]| {
+        candidates
+            .into_iter()
+            .map(|c| here - c)
+            .find(|d| d.magnitude() > f64::EPSILON)
+            .map(|d| d.normalize())
+    };
+    let incoming = towards([
+        handle_or(points[pi].b, here),
+        handle_or(prev.a, here),
+        position(prev),
+    ])?;
+    let outgoing = -towards([
+        handle_or(points[pi].a, here),
+        handle_or(next.b, here),
+        position(next),
+    ])?;
+
+    Some((incoming, outgoing))
+}
+
+/// How far the outline turns at a point, in radians.
+fn turn_angle(incoming: Vector, outgoing: Vector) -> f64 {
+    incoming.dot(outgoing).clamp(-1., 1.).acos()
+}
+
+/// Is the point at `pi` a corner which can be treated?
+pub fn is_corner(points: &Contour<MFEKPointData>, pi: usize, open: bool) -> bool {
+    let len = points.len();
+    if len < 3 || (open && (pi == 0 || pi == len - 1)) {
+        return false;
+    }
+    match corner_directions(points, pi) {
+        Some((incoming, outgoing)) => turn_angle(incoming, outgoing) > CORNER_ANGLE_EPSILON,
+        None => false,
+    }
+}
+
+/// Finds the parameter on a segment which is `distance` away from the corner at one of its ends.
+fn setback_t(bezier: &Bezier, corner: Vector, distance: f64, corner_at_end: bool) -> f64 {
+    let (mut lo, mut hi) = (0., 1.);
+    for _ in 0..SETBACK_BISECTIONS {
+        let mid = (lo + hi) / 2.;
+        let too_far = (bezier.at(mid) - corner).magnitude() > distance;
+        if too_far == corner_at_end {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    (lo + hi) / 2.
+}
+
+/// Replaces the corner at `pi` with its treatment. `max_setback` limits how far back along either
+/// side the treatment may reach, so neighbouring treated corners don't overlap.
+fn treat_corner(
+    contour: &mut MFEKContour<MFEKPointData>,
+    pi: usize,
+    max_setback: f64,
+    params: &CornerParams,
+) {
+    let points = contour.cubic().unwrap();
+    let len = points.len();
+    let (prev, next) = ((pi + len - 1) % len, (pi + 1) % len);
+    let corner = position(&points[pi]);
+    let (incoming, outgoing) = match corner_directions(points, pi) {
+        Some(dirs) => dirs,
+        None => return,
+    };
+    let turn = turn_angle(incoming, outgoing);
+    if turn <= CORNER_ANGLE_EPSILON {
+        return;
+    }
+
+    let setback = match params.style {
+        CornerStyle::Round => params.radius as f64 * (turn / 2.).tan(),
+        CornerStyle::Chamfer => params.radius as f64,
+        CornerStyle::InkTrap => params.width as f64 / 2.,
+    }
+    .min(max_setback);
+    if setback <= f64::EPSILON {
+        return;
+    }
+
+    let bez_in = Bezier::from(&points[prev], &points[pi]);
+    let bez_out = Bezier::from(&points[pi], &points[next]);
+    let (t_in, t_out) = (
+        setback_t(&bez_in, corner, setback, true),
+        setback_t(&bez_out, corner, setback, false),
+    );
+    let (left, right) = match (bez_in.subdivide(t_in), bez_out.subdivide(t_out)) {
+        (Some((left, _)), Some((_, right))) => {
+            (left.to_control_points(), right.to_control_points())
+        }
+        _ => return,
+    };
+
+    let mut start = Point::from_x_y_a_b_type(
+        (left[3].x as f32, left[3].y as f32),
+        (Handle::Colocated, left[2].to_handle()),
+        points[pi].ptype,
+    );
+    let mut end = Point::from_x_y_a_b_type(
+        (right[0].x as f32, right[0].y as f32),
+        (right[1].to_handle(), Handle::Colocated),
+        PointType::Line,
+    );
+
+    let mut replacement = vec![];
+    match params.style {
+        CornerStyle::Round => {
+            // The handle length which best approximates a circular arc turning by `turn`.
+            let radius = setback / (turn / 2.).tan();
+            let handle = 4. / 3. * (turn / 4.).tan() * radius;
+            let (dir_in, dir_out) = (
+                bez_in.tangent_at(t_in).normalize(),
+                bez_out.tangent_at(t_out).normalize(),
+            );
+            start.a = offset(position(&start), dir_in, handle).to_handle();
+            end.b = offset(position(&end), dir_out, -handle).to_handle();
+            end.ptype = PointType::Curve;
+            replacement.push(start);
+            replacement.push(end);
+        }
+        CornerStyle::Chamfer => {
+            replacement.push(start);
+            replacement.push(end);
+        }
+        CornerStyle::InkTrap => {
+            // The notch points away from the angle the two sides make, which at an inner corner is
+            // into the ink.
+            let away = (incoming - outgoing).normalize();
+            let notch = offset(corner, away, params.depth as f64);
+            replacement.push(start);
+            replacement.push(Point::from_x_y_type(
+                (notch.x as f32, notch.y as f32),
+                PointType::Line,
+            ));
+            replacement.push(end);
+        }
+    }
+
+    for _ in 1..replacement.len() {
+        contour.operation_mut().insert_op(pi + 1);
+    }
+    let points = contour.cubic_mut().unwrap();
+    points[prev].a = left[1].to_handle();
+    points[next].b = right[2].to_handle();
+    points.splice(pi..=pi, replacement);
+}
+
+/// Treats every corner at the given indices of a cubic contour, returning the new contour if any
+/// of them were corners.
+pub fn treat_corners(
+    contour: &MFEKContour<MFEKPointData>,
+    indices: &HashSet<usize>,
+    params: &CornerParams,
+) -> Option<MFEKContour<MFEKPointData>> {
+    treat_corners_by(contour, |pi| indices.contains(&pi).then_some(*params))
+}
+
+/// Treats the corners of a cubic contour which `params_for` gives parameters for, by point index,
+/// returning the new contour if any of them were corners.
+pub fn treat_corners_by(
+    contour: &MFEKContour<MFEKPointData>,
+    params_for: impl Fn(usize) -> Option<CornerParams>,
+) -> Option<MFEKContour<MFEKPointData>> {
+    let points = contour.cubic()?;
+    let open = contour.is_open();
+    let len = points.len();
+
+    let corners: Vec<(usize, CornerParams)> = (0..len)
+        .filter(|pi| is_corner(points, *pi, open))
+        .filter_map(|pi| params_for(pi).map(|params| (pi, params)))
+        .collect();
+    if corners.is_empty() {
+        return None;
+    }
+
+    // Limit each treatment to half of the shorter of its sides, measured before anything changes.
+    let chord = |a: usize, b: usize| (position(&points[a]) - position(&points[b])).magnitude();
+    let max_setbacks: Vec<f64> = corners
+        .iter()
+        .map(|(pi, _)| {
+            let (prev, next) = ((pi + len - 1) % len, (pi + 1) % len);
+            chord(prev, *pi).min(chord(*pi, next)) / 2.
+        })
+        .collect();
+
+    // Work backwards so the indices of the corners we haven't visited yet stay valid.
+    let mut result = contour.clone();
+    for ((pi, params), max_setback) in corners.iter().zip(max_setbacks).rev() {
+        treat_corner(&mut result, *pi, max_setback, params);
+    }
+    Some(result)
+}
+
+impl Editor {
+    /// Builds the contours which treating the corners at `targets`, pairs of contour and point
+    /// indices in the active layer, would produce. Only cubic contours are treated.
+    pub fn build_corner_contours(
+        &self,
+        targets: &[(usize, usize)],
+        params: &CornerParams,
+    ) -> Vec<(usize, MFEKContour<MFEKPointData>)> {
+        let mut by_contour: HashMap<usize, HashSet<usize>> = HashMap::new();
+        for (ci, pi) in targets {
+            by_contour.entry(*ci).or_default().insert(*pi);
+        }
+
+        let layer = self.get_active_layer_ref();
+        let mut contours: Vec<_> = by_contour
+            .iter()
+            .filter(|(ci, _)| **ci < layer.outline.len())
+            .filter_map(|(ci, indices)| {
+                treat_corners(&layer.outline[*ci], indices, params).map(|c| (*ci, c))
+            })
+            .collect();
+        contours.sort_by_key(|(ci, _)| *ci);
+        contours
+    }
+
+    /// Replaces the corners at `targets` with a round, chamfer or ink trap.
+    pub fn treat_corners(&mut self, targets: &[(usize, usize)], params: &CornerParams) {
+        let treated = self.build_corner_contours(targets, params);
+        if treated.is_empty() {
+            return;
+        }
+
+        self.begin_modification("Treat corners.", false);
+        let layer = self.get_active_layer_mut();
+        for (contour_idx, contour) in treated {
+            layer.outline[contour_idx] = contour;
+        }
+
+        self.contour_idx = None;
+        self.point_idx = None;
+        self.selected.clear();
+        self.end_modification();
+    }
+
+    /// Rounds the corners at `targets` through their contours' rounded corners operations, which
+    /// leaves the points as they are so the radii can be changed later. A radius of zero makes the
+    /// corners sharp again. The operation replaces any other a contour had.
+    pub fn set_corner_radii(&mut self, targets: &[(usize, usize)], radius: f32) {
+        let mut by_contour: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (ci, pi) in targets {
+            by_contour.entry(*ci).or_default().push(*pi);
+        }
+        if by_contour.is_empty() {
+            return;
+        }
+
+        self.begin_modification("Round corners.", false);
+        for (ci, indices) in by_contour {
+            let len = self.get_active_layer_ref().outline[ci].len();
+            let mut data = self.contour_data(ci).cloned().unwrap_or_default();
+            let mut rounded = match data.operation {
+                Some(EditorOperation::RoundedCorners(rounded)) => rounded,
+                _ => RoundedCornersContour::new(len, 0.),
+            };
+            rounded.handles.resize(len, Default::default());
+            for pi in indices.into_iter().filter(|pi| *pi < len) {
+                rounded.handles[pi].radius = radius;
+            }
+            data.operation = Some(EditorOperation::RoundedCorners(rounded));
+            self.set_contour_data(ci, data);
+            self.get_active_layer_mut().outline[ci].set_operation(None);
+        }
+        self.end_modification();
+    }
+}
+
+#[test]
+fn treat_corner_test() {
+    let square: Contour<MFEKPointData> = [(0., 0.), (100., 0.), (100., 100.), (0., 100.)]
+        .into_iter()
+        .map(|position| Point::from_x_y_type(position, PointType::Line))
+        .collect();
+    let square: MFEKContour<MFEKPointData> = square.into();
+    let corner = HashSet::from([0]);
+    let near = |point: &Point<MFEKPointData>, x: f32, y: f32| {
+        (point.x - x).abs() < 0.01 && (point.y - y).abs() < 0.01
+    };
+    let treat = |style: CornerStyle| {
+        let params = CornerParams {
+            style,
+            ..CornerParams::default()
+        };
+        let treated = treat_corners(&square, &corner, &params).unwrap();
+        treated.cubic().unwrap().clone()
+    };
+
+    // A round at a right angle starts its radius back along each side, and its middle lies on the
+    // circle.
+    let round = treat(CornerStyle::Round);
+    assert_eq!(round.len(), 5);
+    assert!(near(&round[0], 0., 20.) && near(&round[1], 20., 0.));
+    let (a, b) = match (round[0].a, round[1].b) {
+        (Handle::At(ax, ay), Handle::At(bx, by)) => ((ax, ay), (bx, by)),
+        _ => panic!("Round without handles"),
+    };
+    let middle = (
+        (round[0].x + 3. * a.0 + 3. * b.0 + round[1].x) / 8.,
+        (round[0].y + 3. * a.1 + 3. * b.1 + round[1].y) / 8.,
+    );
+    assert!((f32::hypot(middle.0 - 20., middle.1 - 20.) - 20.).abs() < 0.01);
+
+    let chamfer = treat(CornerStyle::Chamfer);
+    assert_eq!(chamfer.len(), 5);
+    assert!(near(&chamfer[0], 0., 20.) && near(&chamfer[1], 20., 0.));
+    assert_eq!(chamfer[0].a, Handle::Colocated);
+
+    // An ink trap's notch points out of this corner, half its width back along each side.
+    let ink_trap = treat(CornerStyle::InkTrap);
+    assert_eq!(ink_trap.len(), 6);
+    let notch = -15. / std::f32::consts::SQRT_2;
+    assert!(near(&ink_trap[0], 0., 10.) && near(&ink_trap[2], 10., 0.));
+    assert!(near(&ink_trap[1], notch, notch));
+
+    // The other corners are left alone.
+    assert!(near(&round[3], 100., 100.) && near(&ink_trap[5], 0., 100.));
+}
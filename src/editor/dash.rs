@@ -0,0 +1,35 @@
+//! Dash phases and per-segment dash patterns. glifparser's `DashContour` has nowhere to keep them,
+//! so like custom VWS caps they're kept in the contour's data.
+
+use glifparser::glif::contour_operations::ContourOperations;
+
+use super::Editor;
+use crate::contour_operations::dashalongpath::{dash_runs, DashExtras, DashRun};
+
+impl Editor {
+    /// The phase and per-segment patterns of a contour on the active layer.
+    pub fn dash_extras(&self, contour_idx: usize) -> DashExtras {
+        self.contour_data(contour_idx)
+            .map(|data| data.dash_extras.clone())
+            .unwrap_or_default()
+    }
+
+    /// Sets the phase and per-segment patterns of a contour on the active layer. Must be called
+    /// during a modification.
+    pub fn set_dash_extras(&mut self, contour_idx: usize, extras: DashExtras) {
+        let mut data = self.contour_data(contour_idx).cloned().unwrap_or_default();
+        data.dash_extras = extras;
+        self.set_contour_data(contour_idx, data);
+    }
+
+    /// The runs of segments sharing a dash pattern of a dashed contour on the active layer.
+    pub fn dash_runs(&self, contour_idx: usize) -> Vec<DashRun> {
+        let contour = &self.get_active_layer_ref().outline[contour_idx];
+        match contour.operation() {
+            Some(ContourOperations::DashAlongPath { data }) => {
+                dash_runs(data, contour, &self.dash_extras(contour_idx))
+            }
+            _ => vec![],
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use glifparser::{
+    glif::{contour::MFEKContourCommon, contour_operations::ContourOperation},
+    Contour, MFEKPointData, Point, PointType,
+};
+use MFEKmath::{Bezier, Primitive};
+
+use super::Editor;
+
+use std::collections::HashSet;
+
+// t values closer than this to the ends of a segment are considered to already have a point.
+const EXTREMA_EPSILON: f64 = 0.001;
+
+/// Returns the parameters in (0, 1) at which a one dimensional cubic bezier with the given control
+/// values has a local minimum or maximum. This is where the derivative of the curve, a quadratic,
+/// crosses zero.
+pub fn extrema_t(p0: f64, p1: f64, p2: f64, p3: f64) -> Vec<f64> {
+    let (d0, d1, d2) = (p1 - p0, p2 - p1, p3 - p2);
+    let a = d0 - 2. * d1 + d2;
+    let b = 2. * (d1 - d0);
+    let c = d0;
+
+    let mut roots = vec![];
+    if a.abs() < f64::EPSILON {
+        if b.abs() > f64::EPSILON {
+            roots.push(-c / b);
+        }
+    } else {
+        let discriminant = b * b - 4. * a * c;
+        if discriminant >= 0. {
+            let sqrt = discriminant.sqrt();
+            roots.push((-b + sqrt) / (2. * a));
+            roots.push((-b - sqrt) / (2. * a));
+        }
+    }
+
+    roots
+        .into_iter()
+        .filter(|t| *t > EXTREMA_EPSILON && *t < 1. - EXTREMA_EPSILON)
+        .collect()
+}
+
+/// Finds the sorted, deduplicated parameters of the horizontal and vertical extrema of a cubic
+/// segment which don't already coincide with one of its end points.
+fn segment_extrema(from: &Point<MFEKPointData>, to: &Point<MFEKPointData>) -> Vec<f64> {
+    let cp = Bezier::from(from, to).to_control_points();
+
+    let mut ts = extrema_t(cp[0].x, cp[1].x, cp[2].x, cp[3].x);
+    ts.extend(extrema_t(cp[0].y, cp[1].y, cp[2].y, cp[3].y));
+    ts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    ts.dedup_by(|a, b| (*a - *b).abs() < EXTREMA_EPSILON);
+    ts
+}
+
+/// Splits the segment starting at seg_idx at t the same way the pen tool subdivides curves, inserting
+/// the new point at seg_idx + 1. Returns whether the segment could be split.
+pub fn split_segment(contour: &mut Contour<MFEKPointData>, seg_idx: usize, t: f64) -> bool {
+    let next_idx = (seg_idx + 1) % contour.len();
+    let bez = Bezier::from(&contour[seg_idx], &contour[next_idx]);
+
+    if let Some((left, right)) = bez.subdivide(t) {
+        let (left, right) = (left.to_control_points(), right.to_control_points());
+
+        contour[seg_idx].a = left[1].to_handle();
+        contour[next_idx].b = right[2].to_handle();
+        contour.insert(
+            seg_idx + 1,
+            Point::from_x_y_a_b_type(
+                (left[3].x as f32, left[3].y as f32),
+                (right[1].to_handle(), left[2].to_handle()),
+                PointType::Curve,
+            ),
+        );
+        true
+    } else {
+        false
+    }
+}
+
+impl Editor {
+    /// Inserts on-curve points at the horizontal and vertical extrema of cubic segments. If there is
+    /// a selection only segments with both of their end points selected are considered, otherwise
+    /// every cubic contour in the active layer is.
+    pub fn add_extrema(&mut self) {
+        let use_selection = !self.selected.is_empty();
+        let layer = self.get_active_layer_ref();
+
+        // (contour_idx, [(seg_idx, [t])]), both in ascending order
+        let mut splits: Vec<(usize, Vec<(usize, Vec<f64>)>)> = vec![];
+        for (contour_idx, contour) in layer.outline.iter().enumerate() {
+            let points = match contour.cubic() {
+                Some(points) => points,
+                None => continue,
+            };
+
+            let segment_count = if contour.is_open() {
+                points.len().saturating_sub(1)
+            } else {
+                points.len()
+            };
+
+            let mut contour_splits = vec![];
+            for seg_idx in 0..segment_count {
+                let next_idx = (seg_idx + 1) % points.len();
+                if use_selection
+                    && !(self.selected.contains(&(contour_idx, seg_idx))
+                        && self.selected.contains(&(contour_idx, next_idx)))
+                {
+                    continue;
+                }
+
+                let ts = segment_extrema(&points[seg_idx], &points[next_idx]);
+                if !ts.is_empty() {
+                    contour_splits.push((seg_idx, ts));
+                }
+            }
+
+            if !contour_splits.is_empty() {
+                splits.push((contour_idx, contour_splits));
+            }
+        }
+
+        if splits.is_empty() {
+            return;
+        }
+
+        self.begin_modification("Add extrema.", false);
+        let mut new_selection: HashSet<(usize, usize)> = HashSet::new();
+        for (contour_idx, contour_splits) in &splits {
+            let layer = self.get_active_layer_mut();
+
+            // Work backwards so that indices we haven't visited yet stay valid. Each split leaves
+            // [0, t] of the original segment at seg_idx, so the remaining t's are rescaled into it.
+            // The operation only gets data for a point once the point is really there.
+            let mut inserted: Vec<(usize, usize)> = vec![];
+            for (seg_idx, ts) in contour_splits.iter().rev() {
+                let mut upper = 1.;
+                let mut count = 0;
+                for t in ts.iter().rev() {
+                    let contour = layer.outline[*contour_idx].cubic_mut().unwrap();
+                    if split_segment(contour, *seg_idx, t / upper) {
+                        layer.outline[*contour_idx]
+                            .operation_mut()
+                            .insert_op(seg_idx + 1);
+                        count += 1;
+                        upper = *t;
+                    }
+                }
+                inserted.push((*seg_idx, count));
+            }
+
+            // Shift selected points past each split and select the points we added.
+            let inserted_before = |point_idx: usize| -> usize {
+                inserted
+                    .iter()
+                    .filter(|(seg_idx, _)| *seg_idx < point_idx)
+                    .map(|(_, count)| count)
+                    .sum()
+            };
+            for (ci, pi) in self.selected.iter().filter(|(ci, _)| ci == contour_idx) {
+                new_selection.insert((*ci, pi + inserted_before(*pi)));
+            }
+            if use_selection {
+                for (seg_idx, count) in &inserted {
+                    let first_new = seg_idx + inserted_before(*seg_idx) + 1;
+                    for offset in 0..*count {
+                        new_selection.insert((*contour_idx, first_new + offset));
+                    }
+                }
+            }
+        }
+
+        let touched: HashSet<usize> = splits.iter().map(|(ci, _)| *ci).collect();
+        new_selection.extend(
+            self.selected
+                .iter()
+                .filter(|(ci, _)| !touched.contains(ci))
+                .copied(),
+        );
+        self.selected = new_selection;
+        self.point_idx = None;
+        self.contour_idx = None;
+
+        self.end_modification();
+    }
+}
+
+#[test]
+fn extrema_t_test() {
+    // A symmetric arch peaks halfway along.
+    let ts = extrema_t(0., 1., 1., 0.);
+    assert_eq!(ts.len(), 1);
+    assert!((ts[0] - 0.5).abs() < 1e-9);
+
+    // Monotonic curves and straight lines have no extrema.
+    assert!(extrema_t(0., 1., 2., 3.).is_empty());
+    assert!(extrema_t(5., 5., 5., 5.).is_empty());
+}
@@ -59,6 +59,7 @@ impl Editor {
                                 ufo_or_dir,
                                 p.file_name().unwrap()
                             );
+                            self.changed_glifs.push(p);
                         }
                     } else {
                         log::debug!("Ignored write of file {:?}", p)
@@ -68,5 +69,6 @@ impl Editor {
                 Err(_) => panic!("Filesystem watcher disconnected!"),
             }
         }
+        self.refresh_linked_patterns();
     }
 }
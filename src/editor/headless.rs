@@ -27,6 +27,7 @@ impl Editor {
                     contour.set_operation(None);
                 }
             }
+            self.contour_data = Default::default();
         }
 
         match self.args.headless_mode {
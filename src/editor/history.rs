@@ -1,5 +1,6 @@
 use glifparser::{glif::HistoryEntry, MFEKPointData};
 
+use super::contour_data::ContourDataStore;
 use super::Editor;
 use std::mem;
 
@@ -7,13 +8,38 @@ use std::mem;
 pub struct History<PD: glifparser::PointData> {
     pub undo_stack: Vec<HistoryEntry<PD>>,
     pub redo_stack: Vec<HistoryEntry<PD>>,
+    // The editor's contour data at every entry above, which HistoryEntry has no field for. These
+    // are only pushed and popped along with their entries.
+    undo_contour_data: Vec<ContourDataStore>,
+    redo_contour_data: Vec<ContourDataStore>,
 }
 
 impl<PD: glifparser::PointData> History<PD> {
-    pub fn add_undo_entry(&mut self, entry: HistoryEntry<PD>) {
+    pub fn add_undo_entry(&mut self, entry: HistoryEntry<PD>, contour_data: ContourDataStore) {
         log::debug!("Added undo entry: {0}", entry.description);
-        self.undo_stack.push(entry);
+        self.push_undo(entry, contour_data);
         self.redo_stack.clear();
+        self.redo_contour_data.clear();
+    }
+
+    fn push_undo(&mut self, entry: HistoryEntry<PD>, contour_data: ContourDataStore) {
+        self.undo_stack.push(entry);
+        self.undo_contour_data.push(contour_data);
+    }
+
+    fn push_redo(&mut self, entry: HistoryEntry<PD>, contour_data: ContourDataStore) {
+        self.redo_stack.push(entry);
+        self.redo_contour_data.push(contour_data);
+    }
+
+    fn pop_undo(&mut self) -> Option<(HistoryEntry<PD>, ContourDataStore)> {
+        let entry = self.undo_stack.pop()?;
+        Some((entry, self.undo_contour_data.pop().unwrap_or_default()))
+    }
+
+    pub fn pop_redo(&mut self) -> Option<(HistoryEntry<PD>, ContourDataStore)> {
+        let entry = self.redo_stack.pop()?;
+        Some((entry, self.redo_contour_data.pop().unwrap_or_default()))
     }
 }
 
@@ -36,14 +62,14 @@ impl Editor {
             log::trace!("Tried to undo while modifying — dropped.");
             return;
         }
-        let entry = self.history.undo_stack.pop();
+        let entry = self.history.pop_undo();
         log::trace!("Popped undo stack: {:?}", &entry);
 
-        if let Some(undo_entry) = entry {
+        if let Some((undo_entry, contour_data)) = entry {
             log::debug!("Undid {}", &undo_entry.description);
-            self.history
-                .redo_stack
-                .push(entry_from_desc_and_editor("Undo", self));
+            let redo_entry = entry_from_desc_and_editor("Undo", self);
+            let redo_contour_data = mem::replace(&mut self.contour_data, contour_data);
+            self.history.push_redo(redo_entry, redo_contour_data);
 
             self.glyph = Some(undo_entry.glyph.clone());
             self.layer_idx = undo_entry.layer_idx;
@@ -62,20 +88,24 @@ impl Editor {
         if self.modifying {
             return;
         }
-        let entry = self.history.redo_stack.pop();
+        let entry = self.history.pop_redo();
         log::trace!("Popped redo stack: {:?}", &entry);
 
-        if let Some(redo_entry) = entry {
+        if let Some((redo_entry, contour_data)) = entry {
             log::debug!("Redid {}", &redo_entry.description);
-            self.history.undo_stack.push(HistoryEntry {
-                description: "Redo".to_owned(),
-                layer_idx: self.layer_idx,
-                contour_idx: self.contour_idx,
-                point_idx: self.point_idx,
-                guidelines: self.guidelines.clone(),
-                selected: Some(self.selected.clone()),
-                glyph: self.glyph.as_ref().unwrap().clone(),
-            });
+            let undo_contour_data = mem::replace(&mut self.contour_data, contour_data);
+            self.history.push_undo(
+                HistoryEntry {
+                    description: "Redo".to_owned(),
+                    layer_idx: self.layer_idx,
+                    contour_idx: self.contour_idx,
+                    point_idx: self.point_idx,
+                    guidelines: self.guidelines.clone(),
+                    selected: Some(self.selected.clone()),
+                    glyph: self.glyph.as_ref().unwrap().clone(),
+                },
+                undo_contour_data,
+            );
 
             self.glyph = Some(redo_entry.glyph.clone());
             self.layer_idx = redo_entry.layer_idx;
@@ -92,14 +122,14 @@ impl Editor {
 
     /// This function combines entries on the top of the undo stack that share a description.
     pub fn collapse_history_entries(&mut self) {
-        let top_entry = self.history.undo_stack.pop();
+        let top_entry = self.history.pop_undo();
 
-        if let Some(entry) = top_entry {
+        if let Some((entry, contour_data)) = top_entry {
             loop {
-                let next_entry = self.history.undo_stack.pop();
-                if let Some(next_entry) = next_entry {
+                let next_entry = self.history.pop_undo();
+                if let Some((next_entry, next_contour_data)) = next_entry {
                     if next_entry.description != entry.description {
-                        self.history.undo_stack.push(next_entry);
+                        self.history.push_undo(next_entry, next_contour_data);
                         break;
                     }
                 } else {
@@ -107,7 +137,7 @@ impl Editor {
                 }
             }
 
-            self.history.undo_stack.push(entry);
+            self.history.push_undo(entry, contour_data);
         }
     }
 
@@ -1,4 +1,6 @@
-use super::{events::*, Editor};
+use super::{
+    contour_data::ContourDataStore, convert, events::*, rounding::RoundToGrid as _, spiro, Editor,
+};
 
 use glifparser::glif::contour::MFEKContourCommon;
 use MFEKmath::mfek::ResolveCubic;
@@ -62,6 +64,7 @@ impl Editor {
 
     pub fn load_glif_impl<F: AsRef<Path> + Clone>(&mut self, file: F) {
         // TODO: Actually handle errors now that we have them.
+        let mut contour_data = ContourDataStore::default();
         let glif: MFEKGlif<_> = {
             let ext = file.as_ref().extension().map(|e| e.to_ascii_lowercase());
             let ext_or = ext
@@ -70,8 +73,12 @@ impl Editor {
                 .into_owned();
             let mut tempglif: MFEKGlif<_> = match ext_or.as_str() {
                 "glifjson" => {
-                    serde_json::from_str(&fs::read_to_string(&file).expect("Could not open file"))
-                        .expect("Could not deserialize JSON MFEKGlif")
+                    let mut json: serde_json::Value = serde_json::from_str(
+                        &fs::read_to_string(&file).expect("Could not open file"),
+                    )
+                    .expect("Could not deserialize JSON MFEKGlif");
+                    contour_data = ContourDataStore::take_from_json(&mut json);
+                    serde_json::from_value(json).expect("Could not deserialize JSON MFEKGlif")
                 }
                 "glif" => glifparser::read_from_filename(&file)
                     .expect("Invalid glif!")
@@ -96,11 +103,14 @@ impl Editor {
         }
 
         self.set_glyph(glif);
+        self.contour_data = contour_data;
         self.initialize();
     }
 
     pub fn save_glif(&mut self, rename: bool) -> Result<PathBuf, ()> {
         self.begin_modification("Saved glyph", true);
+        self.reconcile_contour_data();
+        let contour_data = self.contour_data.clone();
         let res = self.with_glyph_mut(|glyph| {
             let filename: PathBuf = if rename {
                 match filedialog::save_filename(Some("glifjson"), None) {
@@ -119,7 +129,14 @@ impl Editor {
 
             glyph.filename = None;
             log::info!("Requested save to {:?}", &filename);
-            fs::write(&filename, serde_json::to_vec_pretty(&glyph).unwrap()).expect("Write failed");
+            let json = if contour_data.is_empty() {
+                serde_json::to_vec_pretty(&glyph).unwrap()
+            } else {
+                let mut json = serde_json::to_value(&glyph).unwrap();
+                contour_data.put_into_json(&mut json);
+                serde_json::to_vec_pretty(&json).unwrap()
+            };
+            fs::write(&filename, json).expect("Write failed");
             glyph.filename = Some(filename.clone());
             Ok(filename)
         });
@@ -133,8 +150,9 @@ impl Editor {
         rename: bool,
     ) -> Result<PathBuf, ()> {
         self.mark_preview_dirty();
-        if let Some(i) = interface {
-            self.rebuild(i);
+        match interface {
+            Some(i) => self.rebuild(i),
+            None => self.rebuild_preview(),
         }
 
         let mut export = self.prepare_export();
@@ -143,7 +161,13 @@ impl Editor {
         }
         let layer = &mut export.layers[0];
 
-        let glif_struct = self.glyph.as_ref().unwrap().to_exported(layer);
+        let mut glif_struct = self.glyph.as_ref().unwrap().to_exported(layer);
+        if let Some(tolerance) = self.args.quadratic {
+            convert::quadratic_outline(&mut glif_struct, &[], tolerance);
+        }
+        if self.rounding.on_export {
+            glif_struct.round_to_grid(self.rounding.unit);
+        }
 
         let filename = self.with_glyph(|glyph| {
             let mut filename: PathBuf = if rename {
@@ -175,8 +199,9 @@ impl Editor {
 
     pub fn export_glif(&mut self, interface: Option<&mut Interface>) -> Result<(), ()> {
         self.mark_preview_dirty();
-        if let Some(i) = interface {
-            self.rebuild(i);
+        match interface {
+            Some(i) => self.rebuild(i),
+            None => self.rebuild_preview(),
         }
         let glif_fn = {
             let mut temp = self.filename_or_panic();
@@ -205,6 +230,13 @@ impl Editor {
             return Err(());
         };
 
+        let glyph = self.glyph.as_ref().unwrap();
+        let exported: Vec<_> = export
+            .layers
+            .iter_mut()
+            .map(|layer| layer.visible.then(|| glyph.to_exported(layer)))
+            .collect();
+
         for (i, layer) in export.layers.iter_mut().enumerate() {
             if !layer.visible {
                 continue;
@@ -239,7 +271,20 @@ impl Editor {
             }
             log::info!("Targeting {:?} to write {}", &target, &layer.name);
 
-            let glif_struct = self.glyph.as_ref().unwrap().to_exported(layer);
+            let mut glif_struct = exported[i].clone().unwrap();
+            if let Some(tolerance) = self.args.quadratic {
+                // Layers converted alone could get different numbers of points.
+                let others: Vec<_> = exported
+                    .iter()
+                    .enumerate()
+                    .filter(|(oi, _)| *oi != i)
+                    .filter_map(|(_, other)| other.as_ref())
+                    .collect();
+                convert::quadratic_outline(&mut glif_struct, &others, tolerance);
+            }
+            if self.rounding.on_export {
+                glif_struct.round_to_grid(self.rounding.unit);
+            }
             glifparser::write_to_filename(&glif_struct, &target)
                 .unwrap_or_else(|e| panic!("Failed to write glif: {:?}", e));
 
@@ -374,7 +419,15 @@ impl ExportLayer for MFEKGlif<MFEKPointData> {
         let contours: Vec<_> = layer
             .outline
             .iter_mut()
-            .map(|c| c.to_cubic().cubic_mut().unwrap().clone())
+            .map(|c| {
+                // Spiro contours have no handles of their own, and the glyph may not have been
+                // rebuilt with them resolved (see `Editor::prepare_export`).
+                if spiro::is_spiro_contour(c) {
+                    spiro::resolve(c).cubic().unwrap().clone()
+                } else {
+                    c.to_cubic().cubic().unwrap().clone()
+                }
+            })
             .collect();
         let mut ret = Glif::new();
         ret.outline = Some(contours);
@@ -397,3 +450,34 @@ impl ExportLayer for MFEKGlif<MFEKPointData> {
         ret
     }
 }
+
+#[test]
+fn export_spiro_test() {
+    use glifparser::glif::{Layer, MFEKOutline};
+    use glifparser::{Contour, Handle};
+
+    let points: Contour<MFEKPointData> = [(100., 0.), (0., 100.), (-100., 0.), (0., -100.)]
+        .into_iter()
+        .map(|position| spiro::new_point(position, spiro::SpiroPointType::G2))
+        .collect();
+    let outline: MFEKOutline<MFEKPointData> = vec![points.into()];
+    let mut layer = Layer {
+        name: "0".to_string(),
+        visible: true,
+        color: None,
+        outline,
+        operation: None,
+        images: vec![],
+    };
+    let glif: MFEKGlif<MFEKPointData> = Glif::new().into();
+
+    let exported = glif.to_exported(&mut layer);
+    let contour = &exported.outline.unwrap()[0];
+    // Exported as the circle the points are on, not as the polygon through them.
+    assert!(contour.len() > 4);
+    for point in contour {
+        assert!(point.name.is_none());
+        assert!(matches!(point.a, Handle::At(..)));
+        assert!(matches!(point.b, Handle::At(..)));
+    }
+}
@@ -0,0 +1,341 @@
+use glifparser::glif::{
+    contour::MFEKContourCommon, contour_operations::ContourOperation, MFEKContour,
+};
+use glifparser::{Contour, Handle, MFEKPointData, PointType};
+use MFEKmath::{mfek::ResolveCubic, Bezier, Evaluate, Vector};
+
+use super::{extrema::split_segment, Editor};
+use crate::contour_operations::sub_contour_operation;
+
+// Cuts closer than this to either end of a segment are made at the existing point.
+const KNIFE_EPSILON: f64 = 0.001;
+// Each segment is sampled this many times looking for sign changes before bisecting.
+const KNIFE_SAMPLES: usize = 32;
+const KNIFE_BISECTIONS: usize = 40;
+
+/// A place a contour was cut: the segment, the parameter on it, and how far along the knife it is.
+#[derive(Clone, Copy, Debug)]
+struct Cut {
+    seg_idx: usize,
+    t: f64,
+    along: f64,
+}
+
+fn cross(a: Vector, b: Vector) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+/// Finds where a cubic segment crosses the line segment from `from` to `to`, as pairs of the
+/// segment's parameter and the fraction of the way along the line.
+fn line_crossings(bezier: &Bezier, from: Vector, to: Vector) -> Vec<(f64, f64)> {
+    let dir = to - from;
+    let len2 = dir.x * dir.x + dir.y * dir.y;
+    if len2 < f64::EPSILON {
+        return vec![];
+    }
+
+    let side = |t: f64| cross(dir, bezier.at(t) - from);
+    let mut ret = vec![];
+    let mut prev_t = 0.;
+    let mut prev = side(0.);
+    for step in 1..=KNIFE_SAMPLES {
+        let t = step as f64 / KNIFE_SAMPLES as f64;
+        let cur = side(t);
+        if prev == 0. || prev.signum() != cur.signum() {
+            let (mut lo, mut hi) = (prev_t, t);
+            for _ in 0..KNIFE_BISECTIONS {
+                let mid = (lo + hi) / 2.;
+                if side(lo).signum() == side(mid).signum() {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            let root = (lo + hi) / 2.;
+            let p = bezier.at(root) - from;
+            let along = (p.x * dir.x + p.y * dir.y) / len2;
+            if (0. ..=1.).contains(&along) {
+                ret.push((root, along));
+            }
+        }
+        prev_t = t;
+        prev = cur;
+    }
+    ret
+}
+
+/// Finds every cut the knife makes in a cubic contour, snapping cuts near points onto them.
+fn contour_cuts(points: &Contour<MFEKPointData>, open: bool, from: Vector, to: Vector) -> Vec<Cut> {
+    let len = points.len();
+    let seg_count = if open { len.saturating_sub(1) } else { len };
+
+    let mut cuts: Vec<Cut> = vec![];
+    for seg_idx in 0..seg_count {
+        let next_idx = (seg_idx + 1) % len;
+        let bezier = Bezier::from(&points[seg_idx], &points[next_idx]);
+        for (t, along) in line_crossings(&bezier, from, to) {
+            let cut = if t < KNIFE_EPSILON {
+                Cut {
+                    seg_idx,
+                    t: 0.,
+                    along,
+                }
+            } else if t > 1. - KNIFE_EPSILON {
+                Cut {
+                    seg_idx: next_idx,
+                    t: 0.,
+                    along,
+                }
+            } else {
+                Cut { seg_idx, t, along }
+            };
+            // Cutting an open contour at one of its ends doesn't split anything.
+            if open && cut.t == 0. && (cut.seg_idx == 0 || cut.seg_idx == len - 1) {
+                continue;
+            }
+            cuts.push(cut);
+        }
+    }
+
+    cuts.sort_by(|a, b| (a.seg_idx, a.t).partial_cmp(&(b.seg_idx, b.t)).unwrap());
+    cuts.dedup_by(|a, b| a.seg_idx == b.seg_idx && (a.t - b.t).abs() < KNIFE_EPSILON);
+    cuts
+}
+
+/// Inserts points at the cuts and returns the indices of the points each cut is now at, paired
+/// with how far along the knife they are.
+fn insert_cut_points(contour: &mut MFEKContour<MFEKPointData>, cuts: &[Cut]) -> Vec<(usize, f64)> {
+    // Work backwards so the indices we haven't visited yet stay valid, rescaling t into the part of
+    // the segment which is left as we go, the same as when adding extrema.
+    let mut upper = (usize::MAX, 1.);
+    for cut in cuts.iter().rev().filter(|c| c.t > 0.) {
+        if upper.0 != cut.seg_idx {
+            upper = (cut.seg_idx, 1.);
+        }
+        contour.operation_mut().insert_op(cut.seg_idx + 1);
+        split_segment(contour.cubic_mut().unwrap(), cut.seg_idx, cut.t / upper.1);
+        upper.1 = cut.t;
+    }
+
+    let mut inserted = 0;
+    cuts.iter()
+        .map(|cut| {
+            if cut.t > 0. {
+                inserted += 1;
+            }
+            (cut.seg_idx + inserted, cut.along)
+        })
+        .collect()
+}
+
+/// Builds an open contour from the points at `indices`.
+fn open_piece(
+    contour: &MFEKContour<MFEKPointData>,
+    indices: &[usize],
+) -> MFEKContour<MFEKPointData> {
+    let points = contour.cubic().unwrap();
+    let mut piece: Contour<MFEKPointData> =
+        indices.iter().map(|idx| points[*idx].clone()).collect();
+    piece[0].ptype = PointType::Move;
+    piece[0].b = Handle::Colocated;
+    piece.last_mut().unwrap().a = Handle::Colocated;
+
+    let mut ret: MFEKContour<MFEKPointData> = piece.into();
+    ret.set_operation(sub_contour_operation(contour.operation(), indices));
+    ret
+}
+
+/// Builds a closed contour from the points at `indices`, closed by a straight line from the last
+/// point back to the first.
+fn closed_piece(
+    contour: &MFEKContour<MFEKPointData>,
+    indices: &[usize],
+) -> MFEKContour<MFEKPointData> {
+    let points = contour.cubic().unwrap();
+    let mut piece: Contour<MFEKPointData> =
+        indices.iter().map(|idx| points[*idx].clone()).collect();
+    piece[0].ptype = PointType::Line;
+    piece[0].b = Handle::Colocated;
+    piece.last_mut().unwrap().a = Handle::Colocated;
+
+    let mut ret: MFEKContour<MFEKPointData> = piece.into();
+    ret.set_operation(sub_contour_operation(contour.operation(), indices));
+    ret
+}
+
+/// Splits a contour into open pieces at the cut points.
+fn split_open(
+    contour: &MFEKContour<MFEKPointData>,
+    cut_points: &[usize],
+) -> Vec<MFEKContour<MFEKPointData>> {
+    let len = contour.len();
+    let mut cut_points = cut_points.to_vec();
+    cut_points.sort_unstable();
+    cut_points.dedup();
+
+    if contour.is_open() {
+        let mut bounds = vec![0];
+        bounds.extend(cut_points);
+        bounds.push(len - 1);
+        bounds
+            .windows(2)
+            .map(|w| open_piece(contour, &(w[0]..=w[1]).collect::<Vec<_>>()))
+            .collect()
+    } else {
+        // Each piece runs from one cut to the next, wrapping around. With a single cut the contour
+        // opens into one piece which starts and ends there.
+        (0..cut_points.len())
+            .map(|ci| {
+                let (start, end) = (cut_points[ci], cut_points[(ci + 1) % cut_points.len()]);
+                let count = (end + len - start - 1) % len + 1;
+                let indices: Vec<usize> = (0..=count).map(|k| (start + k) % len).collect();
+                open_piece(contour, &indices)
+            })
+            .collect()
+    }
+}
+
+/// Cuts a closed contour into closed pieces. Cut points are paired up in the order the knife
+/// crosses them, and each pair is joined by a straight line which both pieces share.
+fn split_closed(
+    contour: &MFEKContour<MFEKPointData>,
+    cuts: &[(usize, f64)],
+) -> Vec<MFEKContour<MFEKPointData>> {
+    let mut by_knife = cuts.to_vec();
+    by_knife.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+    let mut pieces: Vec<Vec<usize>> = vec![(0..contour.len()).collect()];
+    for pair in by_knife.chunks_exact(2) {
+        let (a, b) = (pair[0].0, pair[1].0);
+        if a == b {
+            continue;
+        }
+        let found = pieces.iter().enumerate().find_map(|(pi, piece)| {
+            let pa = piece.iter().position(|idx| *idx == a)?;
+            let pb = piece.iter().position(|idx| *idx == b)?;
+            Some((pi, pa.min(pb), pa.max(pb)))
+        });
+        if let Some((pi, lo, hi)) = found {
+            let piece = pieces.remove(pi);
+            let mut other = piece[hi..].to_vec();
+            other.extend_from_slice(&piece[..=lo]);
+            pieces.push(piece[lo..=hi].to_vec());
+            pieces.push(other);
+        }
+    }
+
+    if pieces.len() == 1 {
+        return vec![contour.clone()];
+    }
+    pieces
+        .iter()
+        .map(|indices| closed_piece(contour, indices))
+        .collect()
+}
+
+impl Editor {
+    /// Cuts every contour in the active layer which the line from `from` to `to` crosses. Contours
+    /// are split into open pieces at the crossings, unless `closed` is set, in which case closed
+    /// contours are cut into closed pieces instead. Contours which aren't cubic become cubic if
+    /// they're cut.
+    pub fn knife(&mut self, from: (f32, f32), to: (f32, f32), closed: bool) {
+        let (from, to) = (
+            Vector::from_components(from.0 as f64, from.1 as f64),
+            Vector::from_components(to.0 as f64, to.1 as f64),
+        );
+
+        let mut results: Vec<(usize, Vec<MFEKContour<MFEKPointData>>)> = vec![];
+        for (ci, contour) in self.get_active_layer_ref().outline.iter().enumerate() {
+            let mut contour = contour.to_cubic();
+            let open = contour.is_open();
+            let cuts = contour_cuts(contour.cubic().unwrap(), open, from, to);
+            if cuts.is_empty() {
+                continue;
+            }
+
+            let cut_points = insert_cut_points(&mut contour, &cuts);
+            let pieces = if closed && !open {
+                split_closed(&contour, &cut_points)
+            } else {
+                split_open(
+                    &contour,
+                    &cut_points.iter().map(|(idx, _)| *idx).collect::<Vec<_>>(),
+                )
+            };
+            results.push((ci, pieces));
+        }
+
+        if results.is_empty() {
+            return;
+        }
+
+        self.begin_modification("Knife.", false);
+        let layer = self.get_active_layer_mut();
+        for (ci, pieces) in results.into_iter().rev() {
+            layer.outline.remove(ci);
+            for piece in pieces.into_iter().rev() {
+                layer.outline.insert(ci, piece);
+            }
+        }
+        self.end_modification();
+
+        self.contour_idx = None;
+        self.point_idx = None;
+        self.selected.clear();
+    }
+}
+
+#[test]
+fn knife_rounded_corners_test() {
+    use super::contour_data::{ContourData, ContourDataStore, EditorOperation};
+    use crate::contour_operations::roundedcorners::RoundedCornersContour;
+    use glifparser::glif::{Layer, MFEKGlif};
+    use glifparser::Glif;
+
+    let square: Contour<MFEKPointData> = [(0., 0.), (100., 0.), (100., 100.), (0., 100.)]
+        .into_iter()
+        .map(|position| glifparser::Point::from_x_y_type(position, PointType::Line))
+        .collect();
+    let square: MFEKContour<MFEKPointData> = square.into();
+    let mut glyph: MFEKGlif<MFEKPointData> = Glif::new().into();
+    glyph.layers = vec![Layer {
+        name: "0".to_string(),
+        visible: true,
+        color: None,
+        outline: vec![square.clone()],
+        operation: None,
+        images: vec![],
+    }];
+
+    let mut store = ContourDataStore::default();
+    let data = ContourData {
+        operation: Some(EditorOperation::RoundedCorners(RoundedCornersContour::new(4, 10.))),
+        ..Default::default()
+    };
+    store.set(&glyph, 0, 0, data);
+
+    // Cut the square in half down the middle.
+    let (from, to) = (
+        Vector::from_components(50., -50.),
+        Vector::from_components(50., 150.),
+    );
+    let mut contour = square;
+    let cuts = contour_cuts(contour.cubic().unwrap(), false, from, to);
+    let cut_points = insert_cut_points(&mut contour, &cuts);
+    glyph.layers[0].outline = split_closed(&contour, &cut_points);
+    assert_eq!(glyph.layers[0].outline.len(), 2);
+    store.reconcile(&glyph);
+
+    for (ci, piece) in glyph.layers[0].outline.iter().enumerate() {
+        let handles = match store.get(0, ci).and_then(|data| data.operation.as_ref()) {
+            Some(EditorOperation::RoundedCorners(data)) => &data.handles,
+            _ => panic!("Piece {} lost its rounded corners", ci),
+        };
+        assert_eq!(handles.len(), piece.len());
+        // The square's corners keep their radius, and the points the knife added are sharp.
+        for (point, handle) in piece.cubic().unwrap().iter().zip(handles) {
+            let corner = (point.x - 50.).abs() > 0.01;
+            assert_eq!(handle.radius, if corner { 10. } else { 0. });
+        }
+    }
+}
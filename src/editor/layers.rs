@@ -14,15 +14,18 @@ impl Editor {
             images: vec![],
         };
 
-        self.history.add_undo_entry(HistoryEntry {
-            description: "Added layer.".to_owned(),
-            layer_idx: self.layer_idx,
-            contour_idx: self.contour_idx,
-            point_idx: self.point_idx,
-            guidelines: self.guidelines.clone(),
-            selected: Some(self.selected.clone()),
-            glyph: self.glyph.as_ref().unwrap().clone(),
-        });
+        self.history.add_undo_entry(
+            HistoryEntry {
+                description: "Added layer.".to_owned(),
+                layer_idx: self.layer_idx,
+                contour_idx: self.contour_idx,
+                point_idx: self.point_idx,
+                guidelines: self.guidelines.clone(),
+                selected: Some(self.selected.clone()),
+                glyph: self.glyph.as_ref().unwrap().clone(),
+            },
+            self.contour_data.clone(),
+        );
 
         self.glyph.as_mut().unwrap().layers.push(new_layer);
 
@@ -49,18 +52,22 @@ impl Editor {
             self.end_modification();
         }
 
-        self.history.add_undo_entry(HistoryEntry {
-            description: "Deleted layer.".to_owned(),
-            layer_idx: self.layer_idx,
-            contour_idx: self.contour_idx,
-            point_idx: self.point_idx,
-            guidelines: self.guidelines.clone(),
-            selected: Some(self.selected.clone()),
-            glyph: self.glyph.as_ref().unwrap().clone(),
-        });
+        self.history.add_undo_entry(
+            HistoryEntry {
+                description: "Deleted layer.".to_owned(),
+                layer_idx: self.layer_idx,
+                contour_idx: self.contour_idx,
+                point_idx: self.point_idx,
+                guidelines: self.guidelines.clone(),
+                selected: Some(self.selected.clone()),
+                glyph: self.glyph.as_ref().unwrap().clone(),
+            },
+            self.contour_data.clone(),
+        );
 
         let lidx = self.layer_idx.unwrap();
         self.with_glyph_mut_no_history(|glyph| glyph.layers.remove(lidx));
+        self.contour_data.remove_layer(lidx);
 
         if self.layer_idx != Some(0) {
             self.layer_idx = Some(self.layer_idx.unwrap() - 1);
@@ -94,15 +101,18 @@ impl Editor {
 
     pub fn swap_layers(&mut self, src: usize, dest: usize, add_history: bool) {
         if add_history {
-            self.history.add_undo_entry(HistoryEntry {
-                description: "Layer moved.".to_owned(),
-                layer_idx: self.layer_idx,
-                contour_idx: self.contour_idx,
-                point_idx: self.point_idx,
-                guidelines: self.guidelines.clone(),
-                selected: Some(self.selected.clone()),
-                glyph: self.glyph.as_ref().unwrap().clone(),
-            });
+            self.history.add_undo_entry(
+                HistoryEntry {
+                    description: "Layer moved.".to_owned(),
+                    layer_idx: self.layer_idx,
+                    contour_idx: self.contour_idx,
+                    point_idx: self.point_idx,
+                    guidelines: self.guidelines.clone(),
+                    selected: Some(self.selected.clone()),
+                    glyph: self.glyph.as_ref().unwrap().clone(),
+                },
+                self.contour_data.clone(),
+            );
         }
 
         let src_copy = self.glyph.as_mut().unwrap().layers[src].clone();
@@ -117,6 +127,7 @@ impl Editor {
 
         self.glyph.as_mut().unwrap().layers[dest] = src_copy;
         self.glyph.as_mut().unwrap().layers[src] = dest_copy;
+        self.contour_data.swap_layers(src, dest);
 
         if dest == 0 && self.glyph.as_ref().unwrap().layers[src].operation.is_none() {
             self.glyph.as_mut().unwrap().layers[dest].operation = None;
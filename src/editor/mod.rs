@@ -12,25 +12,41 @@ use glifparser::{
 pub use skia_safe::Contains as _;
 pub use skia_safe::{Canvas, Matrix, Path as SkPath, Point as SkPoint, Rect as SkRect};
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path;
 use std::sync::mpsc::{Receiver, Sender};
 
-use self::{history::History, selection::EditorClipboard};
+use self::{
+    contour_data::ContourDataStore, history::History, operations::CachedBuild,
+    selection::EditorClipboard,
+};
 
+pub mod align;
+pub mod contour_data;
 pub mod contour_handlers;
+pub mod convert;
+pub mod corners;
+pub mod dash;
 pub mod debug;
 pub mod events;
+pub mod extrema;
 pub mod filesystem_watch;
 pub mod headless;
 pub mod history;
 pub mod images;
 pub mod io;
+pub mod knife;
 pub mod layers;
 pub mod operations;
+pub mod patterns;
+pub mod rounding;
 pub mod selection;
+pub mod simplify;
+pub mod spiro;
 pub mod tools;
+pub mod transform;
 pub mod util;
+pub mod vws_caps;
 
 #[macro_use]
 pub mod macros;
@@ -59,12 +75,23 @@ pub struct Editor {
 
     pub(crate) filesystem_watch_tx: Sender<path::PathBuf>,
     pub(crate) filesystem_watch_rx: Receiver<path::PathBuf>,
+    // Other .glif files which changed, whose glyphs' patterns are refreshed once no modification is
+    // in progress.
+    changed_glifs: Vec<path::PathBuf>,
 
     preview_dirty: bool,
     pub preview: Option<MFEKGlif<MFEKPointData>>,
+    // Built contour operations from the last rebuild, keyed by a hash of their source contour.
+    op_cache: HashMap<u64, Vec<CachedBuild>>,
+    // Data kept for contours which the glyph has nowhere to store, saved and undone along with it.
+    contour_data: ContourDataStore,
 
     pub italic_angle: f32,
     pub selected: HashSet<(usize, usize)>,
+    /// Set by right clicking with the Select tool, used as the origin for rotations and transforms.
+    pub pivot_point: Option<(f32, f32)>,
+
+    pub rounding: rounding::RoundingSettings,
 
     pub images: images::EditorImages,
     // These are UFO-global guidelines which won't be picked up by glifparser.
@@ -78,6 +105,10 @@ pub struct Editor {
 impl Editor {
     pub fn new(args: Args) -> Editor {
         let (fstx, fsrx) = std::sync::mpsc::channel();
+        let rounding = rounding::RoundingSettings {
+            unit: args.round.unwrap_or(1.),
+            on_export: args.round.is_some(),
+        };
         let mut self_o = Editor {
             args,
             glyph: None,
@@ -90,12 +121,16 @@ impl Editor {
 
             clipboard: EditorClipboard::default(),
             preview: None,
+            op_cache: HashMap::new(),
+            contour_data: ContourDataStore::default(),
 
             layer_idx: None,
             contour_idx: None,
             point_idx: None,
             italic_angle: 0.,
             selected: HashSet::new(),
+            pivot_point: None,
+            rounding,
 
             images: images::EditorImages::new(),
             guidelines: vec![],
@@ -108,6 +143,7 @@ impl Editor {
 
             filesystem_watch_tx: fstx,
             filesystem_watch_rx: fsrx,
+            changed_glifs: vec![],
         };
         self_o.headless();
         self_o
@@ -131,15 +167,18 @@ impl Editor {
             }
         }
 
-        self.history.add_undo_entry(HistoryEntry {
-            description: description.to_owned(),
-            layer_idx: self.layer_idx,
-            contour_idx: self.contour_idx,
-            point_idx: self.point_idx,
-            guidelines: self.guidelines.clone(),
-            selected: Some(self.selected.clone()),
-            glyph: self.glyph.as_ref().unwrap().clone(),
-        });
+        self.history.add_undo_entry(
+            HistoryEntry {
+                description: description.to_owned(),
+                layer_idx: self.layer_idx,
+                contour_idx: self.contour_idx,
+                point_idx: self.point_idx,
+                guidelines: self.guidelines.clone(),
+                selected: Some(self.selected.clone()),
+                glyph: self.glyph.as_ref().unwrap().clone(),
+            },
+            self.contour_data.clone(),
+        );
     }
 
     /// When calling this family of functions the editor will become inaccessible because of the borrow on one of it's members.
@@ -185,6 +224,7 @@ impl Editor {
 
         // TODO: Events here.
         self.modifying = false;
+        self.reconcile_contour_data();
         self.mark_preview_dirty();
     }
 
@@ -201,7 +241,7 @@ impl Editor {
 
         self.modifying = false;
         self.undo();
-        self.history.redo_stack.pop(); // Removes the "Undo" item added by above call.
+        self.history.pop_redo(); // Removes the "Undo" item added by above call.
     }
 
     pub fn add_width_guidelines(&mut self) {
@@ -1,17 +1,147 @@
+use crate::contour_operations::dashalongpath;
+use crate::contour_operations::variablewidthstroke;
 use crate::contour_operations::ContourOperationBuild;
 use crate::user_interface::Interface;
 use glifparser::glif::contour::MFEKContourCommon;
+use glifparser::glif::contour_operations::ContourOperations;
+use glifparser::glif::point::MFEKPointCommon;
 use glifparser::outline::skia::{FromSkiaPath, ToSkiaPaths};
 use glifparser::{
-    glif::{Layer, LayerOperation},
+    glif::{Layer, LayerOperation, MFEKContour, MFEKOutline},
     MFEKGlif, Outline,
 };
-use glifparser::{FlattenedGlif, MFEKPointData};
-use skia_safe::PathOp;
+use glifparser::{FlattenedGlif, Handle, MFEKPointData, WhichHandle};
+use skia_safe::{Path as SkPath, PathOp};
 use MFEKmath::mfek::ResolveCubic;
 
+use super::contour_data::ContourData;
+use super::spiro;
 use super::Editor;
 
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash as _, Hasher as _};
+use std::{mem, thread};
+
+/// A contour to build, with the settings for its operation the editor keeps outside the glyph.
+type ContourToBuild<'a> = (&'a MFEKContour<MFEKPointData>, Option<&'a ContourData>);
+
+/// Hashes where a contour's points and handles are and which operations it has. It's a quick first
+/// check, and doesn't cover the operations' settings: whatever it matches is compared in full.
+fn contour_hash((contour, data): ContourToBuild) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for point in (0..contour.len()).filter_map(|pi| contour.get_point(pi)) {
+        (point.x().to_bits(), point.y().to_bits()).hash(&mut hasher);
+        for wh in [WhichHandle::A, WhichHandle::B] {
+            if let Some(Handle::At(x, y)) = point.get_handle(wh) {
+                (x.to_bits(), y.to_bits()).hash(&mut hasher);
+            }
+        }
+    }
+    contour
+        .operation()
+        .as_ref()
+        .map(mem::discriminant)
+        .hash(&mut hasher);
+    data.and_then(|data| data.operation.as_ref())
+        .map(mem::discriminant)
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A contour as it was built, with the data the editor kept for it, and what it built.
+#[derive(Debug)]
+pub struct CachedBuild {
+    contour: MFEKContour<MFEKPointData>,
+    data: Option<ContourData>,
+    outline: MFEKOutline<MFEKPointData>,
+}
+
+impl CachedBuild {
+    fn is_for(&self, (contour, data): ContourToBuild) -> bool {
+        self.contour == *contour && self.data.as_ref() == data
+    }
+}
+
+/// Builds a contour's operation, with a variable width stroke's custom caps or a dash's phase and
+/// per-segment patterns if it has any. An operation kept in the contour's data is built instead of
+/// its own, and the chain in its data is built on whatever that made. Spiro contours are resolved
+/// to cubics first.
+fn build_contour((contour, data): ContourToBuild) -> MFEKOutline<MFEKPointData> {
+    let editor_operation = data.and_then(|data| data.operation.as_ref());
+    let caps = data.map(|data| &data.vws_caps);
+    let dash = data
+        .map(|data| &data.dash_extras)
+        .filter(|dash| !dash.is_empty());
+    let resolved;
+    let contour = if spiro::is_spiro_contour(contour) {
+        let per_point = contour.operation().is_some()
+            || data.map_or(false, |data| !data.is_empty());
+        resolved = spiro::resolve_with(contour, per_point);
+        &resolved
+    } else {
+        contour
+    };
+    let outline = match (editor_operation, contour.operation(), caps, dash) {
+        (Some(operation), _, _, _) => operation.build(contour),
+        (None, Some(ContourOperations::VariableWidthStroke { data }), Some(caps), _) => {
+            variablewidthstroke::build_with_caps(data, contour, caps)
+        }
+        (None, Some(ContourOperations::DashAlongPath { data }), _, Some(dash)) => {
+            dashalongpath::build_with_extras(data, contour, dash)
+        }
+        (None, op, _, _) => op.build(contour),
+    };
+    match data {
+        Some(data) if !data.chain.is_empty() => data.chain.build_from(outline),
+        _ => outline,
+    }
+}
+
+fn build_serially(contours: &[ContourToBuild]) -> Vec<MFEKOutline<MFEKPointData>> {
+    contours.iter().map(|contour| build_contour(*contour)).collect()
+}
+
+/// Builds the operations of the given contours, split across as many threads as there are cores
+/// unless `serial` is set. The results are in the same order as the contours whichever thread built
+/// them, and any share of the work a thread can't be started for is built on this one instead.
+fn build_contour_operations(
+    contours: &[ContourToBuild],
+    serial: bool,
+) -> Vec<MFEKOutline<MFEKPointData>> {
+    let threads = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(contours.len());
+    if serial || threads <= 1 {
+        return build_serially(contours);
+    }
+
+    let chunk_size = contours.len().div_ceil(threads);
+    thread::scope(|scope| {
+        let handles: Vec<_> = contours
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let handle = thread::Builder::new()
+                    .name("contour operations".to_string())
+                    .spawn_scoped(scope, move || build_serially(chunk));
+                (chunk, handle)
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|(chunk, handle)| match handle {
+                Ok(handle) => handle.join().expect("Contour operation thread panicked"),
+                Err(e) => {
+                    log::warn!("Building contour operations serially: {:?}", e);
+                    build_serially(chunk)
+                }
+            })
+            .collect()
+    })
+}
+
 impl Editor {
     pub fn mark_preview_dirty(&mut self) {
         self.preview_dirty = true;
@@ -23,7 +153,12 @@ impl Editor {
 
     pub fn rebuild(&mut self, i: &mut Interface) {
         self.handle_filesystem_events(i);
+        self.rebuild_preview();
+    }
 
+    /// Builds the preview from the glyph if it's out of date, without an interface to report
+    /// filesystem events to, as when exporting headless.
+    pub fn rebuild_preview(&mut self) {
         if !self.preview_dirty {
             return;
         };
@@ -33,19 +168,68 @@ impl Editor {
         }
 
         //self.fix_contour_ops();
+        self.reconcile_contour_data();
+
+        // Only contours whose points or operation data changed since the last rebuild are built
+        // again, and those are built in parallel. Each contour is keyed by its hash and where it
+        // is among the contours with that hash.
+        let glyph = self.glyph.as_ref().unwrap();
+        let mut built: HashMap<u64, Vec<CachedBuild>> = HashMap::new();
+        let mut keys: Vec<Vec<(u64, usize)>> = vec![];
+        let mut to_build: Vec<((u64, usize), ContourToBuild)> = vec![];
+        for (layer_idx, layer) in glyph.layers.iter().enumerate() {
+            let mut layer_keys = vec![];
+            for (contour_idx, glif_contour) in layer.outline.iter().enumerate() {
+                let to = (glif_contour, self.contour_data.get(layer_idx, contour_idx));
+                let hash = contour_hash(to);
+                if glif_contour.inner().len() <= 1 {
+                    layer_keys.push((hash, 0));
+                    continue;
+                }
+                let same = built.entry(hash).or_default();
+                let idx = match same.iter().position(|cached| cached.is_for(to)) {
+                    Some(idx) => idx,
+                    None => {
+                        let cached = self.op_cache.get_mut(&hash).and_then(|old| {
+                            let idx = old.iter().position(|cached| cached.is_for(to))?;
+                            Some(old.swap_remove(idx))
+                        });
+                        match cached {
+                            Some(cached) => same.push(cached),
+                            None => {
+                                to_build.push(((hash, same.len()), to));
+                                same.push(CachedBuild {
+                                    contour: glif_contour.clone(),
+                                    data: to.1.cloned(),
+                                    outline: vec![],
+                                });
+                            }
+                        }
+                        same.len() - 1
+                    }
+                };
+                layer_keys.push((hash, idx));
+            }
+            keys.push(layer_keys);
+        }
+
+        let contours: Vec<_> = to_build.iter().map(|(_, contour)| *contour).collect();
+        let outlines = build_contour_operations(&contours, self.args.serial_contour_ops);
+        for (((hash, idx), _), outline) in to_build.iter().zip(outlines) {
+            built.get_mut(hash).unwrap()[*idx].outline = outline;
+        }
+
         let mut preview_layers = Vec::new();
-        for layer in &self.glyph.as_mut().unwrap().layers {
+        for (layer, layer_keys) in glyph.layers.iter().zip(keys) {
             let mut preview_outline = Vec::new();
 
-            for (_idx, glif_contour) in layer.outline.iter().enumerate() {
+            for (glif_contour, key) in layer.outline.iter().zip(layer_keys) {
                 if glif_contour.inner().len() <= 1 {
                     preview_outline.push(glif_contour.to_cubic());
                     continue;
                 }
 
-                let build_result = glif_contour.operation().build(glif_contour);
-
-                for new_contour in build_result {
+                for new_contour in &built[&key.0][key.1].outline {
                     preview_outline.push(new_contour.to_cubic());
                 }
             }
@@ -54,6 +238,7 @@ impl Editor {
             new_layer.outline = preview_outline;
             preview_layers.push(new_layer);
         }
+        self.op_cache = built;
 
         let mut rects = Some(vec![]);
         let flattened = self.glyph.as_mut().unwrap().flattened(&mut rects);
@@ -69,6 +254,15 @@ impl Editor {
         self.preview_dirty = false;
     }
 
+    /// What the operation of a contour on the active layer builds, with the settings the editor
+    /// keeps for it outside the glyph.
+    pub fn build_contour_operation(&self, contour_idx: usize) -> MFEKOutline<MFEKPointData> {
+        build_contour((
+            &self.get_active_layer_ref().outline[contour_idx],
+            self.contour_data(contour_idx),
+        ))
+    }
+
     pub fn prepare_export(&self) -> MFEKGlif<MFEKPointData> {
         let glyph = self
             .glyph
@@ -79,16 +273,27 @@ impl Editor {
                 .outline
                 .iter()
                 .all(|c| c.operation().clone() == None)
+            && self.contour_data.is_empty()
         {
             return glyph.clone();
         }
 
         let glif = self.preview.as_ref().unwrap_or(glyph);
 
+        // Expanded strokes overlap whatever they're drawn over, so their layers are unioned.
+        let union = |layer_idx: usize, path: SkPath| {
+            if self.contour_data.unions_layer(layer_idx) {
+                path.simplify().unwrap_or(path)
+            } else {
+                path
+            }
+        };
+
         // MFEKGlif always has a layer zero so this is safe. (No it isn't, it can be invisible. TODO: Fix this.)
         let mut last_combine_layer: Layer<MFEKPointData> = glif.layers[0].clone();
         let mut exported_layers: Vec<Layer<MFEKPointData>> = vec![];
-        let mut current_layer_group = last_combine_layer.outline.to_skia_paths(None).combined();
+        let mut current_layer_group =
+            union(0, last_combine_layer.outline.to_skia_paths(None).combined());
 
         for (layer_idx, layer) in glif.layers.iter().enumerate() {
             if !layer.visible {
@@ -110,7 +315,7 @@ impl Editor {
                     };
 
                     if let Some(result) = current_layer_group
-                        .op(&(skpaths.combined()), pathop)
+                        .op(&union(layer_idx, skpaths.combined()), pathop)
                         .unwrap()
                         .as_winding()
                     {
@@ -127,7 +332,8 @@ impl Editor {
                     combined_layer.outline = mfek_outline;
                     exported_layers.push(combined_layer);
 
-                    current_layer_group = layer.outline.to_skia_paths(None).combined();
+                    current_layer_group =
+                        union(layer_idx, layer.outline.to_skia_paths(None).combined());
                 }
             }
         }
@@ -143,3 +349,48 @@ impl Editor {
         exported_mfek
     }
 }
+
+#[test]
+fn contour_hash_test() {
+    use super::contour_data::EditorOperation;
+    use glifparser::{Contour, PointType};
+
+    let points: Contour<MFEKPointData> = vec![
+        glifparser::Point::from_x_y_type((0., 0.), PointType::Move),
+        glifparser::Point::from_x_y_type((100., 0.), PointType::Line),
+        glifparser::Point::from_x_y_type((100., 100.), PointType::Line),
+    ];
+    let contour: MFEKContour<MFEKPointData> = points.clone().into();
+    let hash = contour_hash((&contour, None));
+
+    // Equal contours hash the same, so a rebuild can reuse what was built for either.
+    let same: MFEKContour<MFEKPointData> = points.clone().into();
+    assert_eq!(hash, contour_hash((&same, None)));
+
+    // Moving a point, or giving it another operation, means building it again.
+    let mut moved = points;
+    moved[2].x = 50.;
+    let moved: MFEKContour<MFEKPointData> = moved.into();
+    assert_ne!(hash, contour_hash((&moved, None)));
+
+    let stroked = ContourData {
+        operation: Some(EditorOperation::ExpandStroke(Default::default())),
+        ..ContourData::default()
+    };
+    assert_ne!(hash, contour_hash((&contour, Some(&stroked))));
+
+    // Changing an operation's settings doesn't change the hash, but what was built before is only
+    // reused for the same settings.
+    let mut dashed = ContourData::default();
+    dashed.dash_extras.phase = 5.;
+    assert_eq!(hash, contour_hash((&contour, Some(&dashed))));
+
+    let cached = CachedBuild {
+        contour: contour.clone(),
+        data: None,
+        outline: vec![],
+    };
+    assert!(cached.is_for((&same, None)));
+    assert!(!cached.is_for((&contour, Some(&dashed))));
+    assert!(!cached.is_for((&moved, None)));
+}
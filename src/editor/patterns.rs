@@ -0,0 +1,113 @@
+//! Patterns for Pattern Along Path which come from other glyphs in the same UFO. The name of the
+//! glyph is kept in the contour's data, so that when the glyph changes on disk every PAP contour
+//! which took its pattern from it can be given the new one.
+
+use glifparser::glif::contour_operations::ContourOperations;
+use glifparser::glif::{MFEKGlif, MFEKOutline};
+use glifparser::MFEKPointData;
+use plist;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::Editor;
+
+/// Reads the first layer of a .glif file as a pattern.
+pub(super) fn read_pattern(path: &Path) -> Result<MFEKOutline<MFEKPointData>, String> {
+    let glif: MFEKGlif<MFEKPointData> = glifparser::read_from_filename(path)
+        .map_err(|e| format!("{:?}", e))?
+        .into();
+    glif.layers
+        .first()
+        .map(|layer| layer.outline.clone())
+        .ok_or_else(|| format!("{:?} has no layers", path))
+}
+
+impl Editor {
+    /// Finds the .glif file of a glyph in the same glyphs directory as the current glyph, by looking
+    /// its name up in the directory's contents.plist.
+    pub fn glyph_filename(&self, name: &str) -> Option<PathBuf> {
+        let filename = self.with_glyph(|glif| glif.filename.clone())?;
+        let dir = filename.parent()?;
+        let contents: HashMap<String, String> =
+            plist::from_file(dir.join("contents.plist")).ok()?;
+        contents.get(name).map(|glif| dir.join(glif))
+    }
+
+    /// Loads another glyph's outline to use as a pattern.
+    pub fn load_pattern_glyph(&self, name: &str) -> Result<MFEKOutline<MFEKPointData>, String> {
+        let path = self
+            .glyph_filename(name)
+            .ok_or_else(|| format!("No glyph named {} next to this one", name))?;
+        read_pattern(&path)
+    }
+
+    /// Gives every PAP contour whose pattern was taken from a glyph whose .glif file changed its
+    /// new outline. This makes a modification of its own, so it waits until none is in progress.
+    pub(super) fn refresh_linked_patterns(&mut self) {
+        if self.changed_glifs.is_empty() || self.is_modifying() {
+            return;
+        }
+        let changed: Vec<PathBuf> = self
+            .changed_glifs
+            .drain(..)
+            .map(|path| canonical(&path))
+            .collect();
+        let glyph = match self.glyph.as_ref() {
+            Some(glyph) => glyph,
+            None => return,
+        };
+
+        let mut patterns: HashMap<PathBuf, Option<MFEKOutline<MFEKPointData>>> = HashMap::new();
+        let mut stale = vec![];
+        for (li, layer) in glyph.layers.iter().enumerate() {
+            for (ci, contour) in layer.outline.iter().enumerate() {
+                let source = self
+                    .contour_data
+                    .get(li, ci)
+                    .and_then(|data| data.pattern_source.as_ref())
+                    .and_then(|name| self.glyph_filename(name))
+                    .map(|path| canonical(&path))
+                    .filter(|path| changed.contains(path));
+                let path = match source {
+                    Some(path) => path,
+                    None => continue,
+                };
+                let pattern = patterns.entry(path).or_insert_with_key(|path| {
+                    read_pattern(path)
+                        .map_err(|e| log::error!("Couldn't refresh pattern from {:?}: {}", path, e))
+                        .ok()
+                });
+                if let (Some(pattern), Some(ContourOperations::PatternAlongPath { data })) =
+                    (pattern.as_ref(), contour.operation())
+                {
+                    if data.pattern != *pattern {
+                        stale.push((li, ci, pattern.clone()));
+                    }
+                }
+            }
+        }
+        if stale.is_empty() {
+            return;
+        }
+
+        self.begin_modification("Refreshed pattern from another glyph.", false);
+        self.with_glyph_mut(|glif| {
+            for (li, ci, pattern) in stale {
+                if let Some(ContourOperations::PatternAlongPath { data }) =
+                    glif.layers[li].outline[ci].operation_mut()
+                {
+                    data.pattern = pattern;
+                }
+            }
+        });
+        self.end_modification();
+        log::info!("Refreshed patterns taken from {:?}", changed);
+    }
+}
+
+/// The path a file is at with every link followed, so that paths to the same file compare equal
+/// however they were written. Files which are gone are left as they are.
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
@@ -0,0 +1,140 @@
+use glifparser::{
+    glif::{contour::MFEKContourCommon, point::MFEKPointCommon, Layer},
+    Glif, Handle, IntegerOrFloat, MFEKPointData, WhichHandle,
+};
+use kurbo::Affine;
+
+use super::Editor;
+
+/// Rounds `value` to the nearest multiple of `unit`. A unit of 1 rounds to integers.
+pub fn round_to_unit(value: f32, unit: f32) -> f32 {
+    if unit <= 0. {
+        return value;
+    }
+    (value / unit).round() * unit
+}
+
+fn round_handle(handle: Handle, unit: f32) -> Handle {
+    match handle {
+        Handle::At(x, y) => Handle::At(round_to_unit(x, unit), round_to_unit(y, unit)),
+        Handle::Colocated => Handle::Colocated,
+    }
+}
+
+/// Values which round to whole numbers become integers, so they're written without a fraction.
+fn round_iof(value: IntegerOrFloat, unit: f32) -> IntegerOrFloat {
+    let rounded = round_to_unit(f32::from(value), unit);
+    if rounded.fract() == 0. {
+        IntegerOrFloat::Integer(rounded as _)
+    } else {
+        IntegerOrFloat::Float(rounded)
+    }
+}
+
+/// Only the translation of an image's matrix is rounded, its scale and skew are left alone.
+fn round_affine(affine: Affine, unit: f32) -> Affine {
+    let mut coeffs = affine.as_coeffs();
+    coeffs[4] = round_to_unit(coeffs[4] as f32, unit) as f64;
+    coeffs[5] = round_to_unit(coeffs[5] as f32, unit) as f64;
+    Affine::new(coeffs)
+}
+
+/// Rounds every coordinate of a glyph, or part of one, to multiples of a unit.
+pub trait RoundToGrid {
+    fn round_to_grid(&mut self, unit: f32);
+}
+
+impl RoundToGrid for Layer<MFEKPointData> {
+    fn round_to_grid(&mut self, unit: f32) {
+        for contour in self.outline.iter_mut() {
+            for pi in 0..contour.len() {
+                let point = contour.get_point_mut(pi).unwrap();
+                let (x, y) = (point.x(), point.y());
+                point.set_position_no_handles(round_to_unit(x, unit), round_to_unit(y, unit));
+
+                for wh in [WhichHandle::A, WhichHandle::B] {
+                    if let Some(handle @ Handle::At(..)) = point.get_handle(wh) {
+                        point.set_handle(wh, round_handle(handle, unit));
+                    }
+                }
+            }
+        }
+
+        for (_, matrix) in self.images.iter_mut() {
+            *matrix = round_affine(*matrix, unit);
+        }
+    }
+}
+
+/// Used at export time, after contour operations have been applied and the outline flattened.
+impl RoundToGrid for Glif<MFEKPointData> {
+    fn round_to_grid(&mut self, unit: f32) {
+        if let Some(outline) = self.outline.as_mut() {
+            for contour in outline.iter_mut() {
+                for point in contour.iter_mut() {
+                    point.x = round_to_unit(point.x, unit);
+                    point.y = round_to_unit(point.y, unit);
+                    point.a = round_handle(point.a, unit);
+                    point.b = round_handle(point.b, unit);
+                }
+            }
+        }
+
+        for anchor in self.anchors.iter_mut() {
+            anchor.x = round_to_unit(anchor.x, unit);
+            anchor.y = round_to_unit(anchor.y, unit);
+        }
+
+        for component in self.components.vec.iter_mut() {
+            component.xOffset = round_iof(component.xOffset, unit);
+            component.yOffset = round_iof(component.yOffset, unit);
+        }
+
+        for image in self.images.iter_mut() {
+            let matrix: Affine = image.matrix().into();
+            image.set_matrix(round_affine(matrix, unit));
+        }
+    }
+}
+
+/// Settings for the Round to Grid command, also used when rounding on export.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RoundingSettings {
+    pub unit: f32,
+    pub on_export: bool,
+}
+
+impl Default for RoundingSettings {
+    fn default() -> Self {
+        Self {
+            unit: 1.,
+            on_export: false,
+        }
+    }
+}
+
+impl Editor {
+    /// Rounds points, handles, anchors, component offsets and image positions in every layer to
+    /// multiples of `unit`. All layers are rounded together so they stay point compatible.
+    pub fn round_to_grid(&mut self, unit: f32) {
+        self.begin_modification("Round to grid.", false);
+        self.with_glyph_mut(|glyph| {
+            for layer in glyph.layers.iter_mut() {
+                layer.round_to_grid(unit);
+            }
+
+            for anchor in glyph.anchors.iter_mut() {
+                anchor.x = round_to_unit(anchor.x, unit);
+                anchor.y = round_to_unit(anchor.y, unit);
+            }
+
+            for component in glyph.components.vec.iter_mut() {
+                component.xOffset = round_iof(component.xOffset, unit);
+                component.yOffset = round_iof(component.yOffset, unit);
+            }
+        });
+        self.end_modification();
+
+        self.recache_images();
+    }
+}
@@ -1,4 +1,3 @@
-use flo_curves::{bezier::fit_curve_cubic, BezierCurve};
 use glifparser::{
     glif::{
         contour::MFEKContourCommon, contour_operations::ContourOperation, inner::MFEKCommonInner,
@@ -7,12 +6,13 @@ use glifparser::{
     outline::skia::ToSkiaPaths as _,
     Handle, MFEKPointData, WhichHandle,
 };
-use MFEKmath::{Bezier, Evaluate, Rect, Vector};
+use MFEKmath::{Evaluate, Rect, Vector};
 
 use arboard::{self, Clipboard};
 use serde_json;
 use shrinkwraprs;
 
+use super::simplify::fit_beziers;
 use super::Editor;
 use crate::user_interface::gui;
 
@@ -141,10 +141,9 @@ impl Editor {
             .unwrap_or(());
     }
 
-    /// If `position` is provided, it means that the client is requesting that the layer outline be
-    /// moved
-    pub fn paste_selection(&mut self, position: Option<(f32, f32)>) {
-        let mut clipboard: Layer<_> = if let Some(data) = self.clipboard.with(|clipboard: &mut Clipboard| {
+    /// Reads the layer our clipboard data holds, if the OS clipboard has any.
+    pub fn clipboard_layer(&mut self) -> Option<Layer<MFEKPointData>> {
+        self.clipboard.with(|clipboard: &mut Clipboard| {
             let cbtext; // [For borrow checker!]
             let (mimetype, data) = match clipboard.get_text() {
                 Ok(t) => {
@@ -178,15 +177,15 @@ impl Editor {
                     Err(())
                 }
             }
-        }) {
-            match data {
-                Ok(d) => d,
-                Err(()) => {
-                    return;
-                }
-            }
-        } else {
-            return;
+        }).and_then(Result::ok)
+    }
+
+    /// If `position` is provided, it means that the client is requesting that the layer outline be
+    /// moved
+    pub fn paste_selection(&mut self, position: Option<(f32, f32)>) {
+        let mut clipboard = match self.clipboard_layer() {
+            Some(layer) => layer,
+            None => return,
         };
 
         log::debug!("Got layer {} from clipboard", &clipboard.name);
@@ -344,21 +343,9 @@ impl Editor {
             return;
         }
 
-        // we've finally handled all the cases in which we won't simplify we now need to build an array of points that
-        // lie on the two beziers seperated by some small chord length
-        let mut sample_points = Vec::new();
-
-        for point in flo_curves::bezier::walk_curve_evenly(&left_bezier, 0.01, 0.001) {
-            sample_points.push(point.start_point());
-        }
-
-        for point in flo_curves::bezier::walk_curve_evenly(&right_bezier, 0.01, 0.001) {
-            sample_points.push(point.start_point());
-        }
-
+        // we've finally handled all the cases in which we won't simplify, so we fit one curve to both
         let max_error = 10.;
-        let fitted_curve: Vec<Bezier> =
-            fit_curve_cubic(&sample_points, &start_tangent, &-end_tangent, max_error);
+        let fitted_curve = fit_beziers(&[left_bezier, right_bezier], max_error);
 
         if fitted_curve.len() > 1 {
             self.delete_single_point();
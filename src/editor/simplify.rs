@@ -0,0 +1,212 @@
+use flo_curves::bezier::{fit_curve_cubic, walk_curve_evenly};
+use flo_curves::BezierCurve;
+use glifparser::{
+    glif::{contour::MFEKContourCommon, contour_operations::ContourOperation, MFEKContour},
+    Contour, MFEKPointData, Point, PointType,
+};
+use MFEKmath::{Bezier, Evaluate};
+
+use super::Editor;
+
+use std::collections::{HashMap, HashSet};
+
+/// A span of a contour which will be replaced by fitted curves. The first and last indices are
+/// kept, every point between them is removed.
+#[derive(Clone, Debug)]
+struct SimplifyRun {
+    indices: Vec<usize>,
+}
+
+/// Finds the runs of contiguous selected points in a contour. On closed contours a run may wrap
+/// around the start of the contour, and a fully selected closed contour is anchored at its first
+/// point.
+fn selected_runs(selected: &HashSet<usize>, len: usize, open: bool) -> Vec<SimplifyRun> {
+    if selected.len() < 3 {
+        return vec![];
+    }
+
+    if !open && selected.len() == len {
+        return vec![SimplifyRun {
+            indices: (0..len).chain(std::iter::once(0)).collect(),
+        }];
+    }
+
+    // On a closed contour begin scanning after an unselected point so wrapping runs stay whole.
+    let start = if open {
+        0
+    } else {
+        (0..len).find(|i| !selected.contains(i)).unwrap() + 1
+    };
+
+    let mut runs = vec![];
+    let mut current: Vec<usize> = vec![];
+    for offset in 0..len {
+        let idx = (start + offset) % len;
+        if selected.contains(&idx) {
+            current.push(idx);
+        } else {
+            if current.len() > 2 {
+                runs.push(SimplifyRun { indices: current.clone() });
+            }
+            current.clear();
+        }
+    }
+    if current.len() > 2 {
+        runs.push(SimplifyRun { indices: current });
+    }
+
+    runs
+}
+
+/// Fits the fewest cubic curves within `tolerance` of a chain of curves, keeping the tangents at
+/// its ends.
+pub fn fit_beziers(beziers: &[Bezier], tolerance: f64) -> Vec<Bezier> {
+    let mut sample_points = Vec::new();
+    for bez in beziers {
+        for section in walk_curve_evenly(bez, 0.01, 0.001) {
+            sample_points.push(section.start_point());
+        }
+    }
+    sample_points.push(beziers.last().unwrap().end_point());
+
+    let start_tangent = beziers.first().unwrap().tangent_at(0.0);
+    let end_tangent = beziers.last().unwrap().tangent_at(1.0);
+
+    fit_curve_cubic(&sample_points, &start_tangent, &-end_tangent, tolerance)
+}
+
+/// Fits the fewest cubic curves within tolerance to the span, returning the curves if they need
+/// fewer points than the span currently has.
+fn fit_run(points: &Contour<MFEKPointData>, run: &SimplifyRun, tolerance: f64) -> Option<Vec<Bezier>> {
+    let beziers: Vec<Bezier> = run
+        .indices
+        .windows(2)
+        .map(|w| Bezier::from(&points[w[0]], &points[w[1]]))
+        .collect();
+
+    let fitted = fit_beziers(&beziers, tolerance);
+
+    if fitted.is_empty() || fitted.len() >= beziers.len() {
+        None
+    } else {
+        Some(fitted)
+    }
+}
+
+/// Replaces the interior of every run with the fitted curves, keeping the contour operation data
+/// aligned with the points.
+fn simplify_contour(
+    contour: &MFEKContour<MFEKPointData>,
+    selected: &HashSet<usize>,
+    tolerance: f64,
+) -> Option<MFEKContour<MFEKPointData>> {
+    let points = contour.cubic()?;
+    let runs = selected_runs(selected, points.len(), contour.is_open());
+
+    // run start index → (interior indices, fitted curves)
+    let mut replacements: HashMap<usize, (Vec<usize>, Vec<Bezier>)> = HashMap::new();
+    for run in &runs {
+        if let Some(fitted) = fit_run(points, run, tolerance) {
+            let interior = run.indices[1..run.indices.len() - 1].to_vec();
+            replacements.insert(run.indices[0], (interior, fitted));
+        }
+    }
+
+    if replacements.is_empty() {
+        return None;
+    }
+
+    let removed: HashSet<usize> = replacements
+        .values()
+        .flat_map(|(interior, _)| interior.iter().copied())
+        .collect();
+
+    let mut new_points: Contour<MFEKPointData> = points.clone();
+    for (start, (_, fitted)) in &replacements {
+        new_points[*start].a = fitted[0].w2.to_handle();
+        let end = (start + 1..)
+            .map(|i| i % points.len())
+            .find(|i| !removed.contains(i))
+            .unwrap();
+        new_points[end].b = fitted.last().unwrap().w3.to_handle();
+    }
+
+    let mut result = contour.clone();
+    let mut sorted_removed: Vec<usize> = removed.iter().copied().collect();
+    sorted_removed.sort_unstable();
+    for idx in sorted_removed.iter().rev() {
+        result.operation_mut().remove_op(*idx);
+    }
+
+    let mut rebuilt: Contour<MFEKPointData> = vec![];
+    let mut inserted = vec![];
+    for (idx, point) in new_points.into_iter().enumerate() {
+        if removed.contains(&idx) {
+            continue;
+        }
+        rebuilt.push(point);
+
+        if let Some((_, fitted)) = replacements.get(&idx) {
+            for window in fitted.windows(2) {
+                let (prev, next) = (&window[0], &window[1]);
+                inserted.push(rebuilt.len());
+                rebuilt.push(Point::from_x_y_a_b_type(
+                    (next.w1.x as f32, next.w1.y as f32),
+                    (next.w2.to_handle(), prev.w3.to_handle()),
+                    PointType::Curve,
+                ));
+            }
+        }
+    }
+
+    for idx in inserted {
+        result.operation_mut().insert_op(idx);
+    }
+    *result.cubic_mut().unwrap() = rebuilt;
+
+    Some(result)
+}
+
+impl Editor {
+    /// Builds the contours which simplifying the selection with the given tolerance would produce,
+    /// keyed by their index in the active layer. Only cubic contours are simplified.
+    pub fn build_simplified_contours(
+        &self,
+        tolerance: f64,
+    ) -> Vec<(usize, MFEKContour<MFEKPointData>)> {
+        let mut by_contour: HashMap<usize, HashSet<usize>> = HashMap::new();
+        for (ci, pi) in &self.selected {
+            by_contour.entry(*ci).or_default().insert(*pi);
+        }
+
+        let layer = self.get_active_layer_ref();
+        let mut contours: Vec<_> = by_contour
+            .iter()
+            .filter_map(|(ci, selected)| {
+                simplify_contour(&layer.outline[*ci], selected, tolerance).map(|c| (*ci, c))
+            })
+            .collect();
+        contours.sort_by_key(|(ci, _)| *ci);
+        contours
+    }
+
+    /// Replaces each run of contiguous selected points with the fewest cubic curves that stay
+    /// within `tolerance` font units of the original outline. The ends of each run are kept.
+    pub fn simplify_selection(&mut self, tolerance: f64) {
+        let simplified = self.build_simplified_contours(tolerance);
+        if simplified.is_empty() {
+            return;
+        }
+
+        self.begin_modification("Simplify selection.", false);
+        let layer = self.get_active_layer_mut();
+        for (contour_idx, contour) in simplified {
+            layer.outline[contour_idx] = contour;
+        }
+
+        self.contour_idx = None;
+        self.point_idx = None;
+        self.selected.clear();
+        self.end_modification();
+    }
+}
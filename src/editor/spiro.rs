@@ -0,0 +1,679 @@
+//! Spiro contours. These are stored as cubic contours whose on-curve points are named after their
+//! Spiro point type. Their handles are unused: the curve through the points is solved with Raph
+//! Levien's Spiro algorithm, the one libspiro implements. Every segment is a polynomial spiral, a
+//! curve whose curvature is a polynomial in arc length, and Newton's method solves the spirals so
+//! that tangent and curvature are continuous through G2 points, and the first two derivatives of
+//! curvature are too through G4 points.
+//!
+//! Solving never changes the glyph. When it's rebuilt, Spiro contours are resolved to plain cubic
+//! contours for the preview and export, the same way hyperbezier contours are.
+
+use glifparser::{Contour, Handle, MFEKPointData, Point, PointData, PointType};
+use glifparser::glif::{contour::MFEKContourCommon, MFEKContour};
+
+use std::f64::consts::PI;
+
+const SPIRO_NAME_PREFIX: &str = "spiro:";
+// libspiro gives up after as many Newton iterations, and stops early at the same tolerance.
+const NEWTON_ITERATIONS: usize = 10;
+const NEWTON_TOLERANCE: f64 = 1e-12;
+// The step used to take finite differences of a spiral's ends with respect to its parameters.
+const DERIVATIVE_STEP: f64 = 1e-6;
+// A spiral which turns more than this many radians is halved before being drawn as cubics, at
+// most `MAX_SUBDIVISIONS` times.
+const MAX_BEND: f64 = 1.;
+const MAX_SUBDIVISIONS: usize = 5;
+// The positive nodes of eight point Gauss-Legendre quadrature on [-1, 1], with their weights.
+const GAUSS_LEGENDRE: [(f64, f64); 4] = [
+    (0.1834346424956498, 0.3626837833783620),
+    (0.5255324099163290, 0.3137066458778873),
+    (0.7966664774136267, 0.2223810344533745),
+    (0.9602898564975363, 0.1012285362903763),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpiroPointType {
+    G2,
+    G4,
+    Corner,
+    /// Joins a curve coming in to a straight line going out.
+    Left,
+    /// Joins a straight line coming in to a curve going out.
+    Right,
+}
+
+impl SpiroPointType {
+    pub const ALL: [Self; 5] = [Self::G2, Self::G4, Self::Corner, Self::Left, Self::Right];
+
+    pub fn name(self) -> String {
+        let suffix = match self {
+            Self::G2 => "g2",
+            Self::G4 => "g4",
+            Self::Corner => "corner",
+            Self::Left => "left",
+            Self::Right => "right",
+        };
+        format!("{}{}", SPIRO_NAME_PREFIX, suffix)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.name() == name)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::G2 => "G2",
+            Self::G4 => "G4",
+            Self::Corner => "Corner",
+            Self::Left => "Left",
+            Self::Right => "Right",
+        }
+    }
+
+    fn is_smooth(self) -> bool {
+        matches!(self, Self::G2 | Self::G4)
+    }
+}
+
+pub fn point_type<PD: PointData>(point: &Point<PD>) -> Option<SpiroPointType> {
+    point.name.as_deref().and_then(SpiroPointType::from_name)
+}
+
+/// Builds an on-curve point for a Spiro contour. Its handles are never used, see [`resolve`]. As on
+/// any contour, the first point of an open one must be made a move point.
+pub fn new_point<PD: PointData>(position: (f32, f32), ptype: SpiroPointType) -> Point<PD> {
+    let mut point = Point::from_x_y_type(position, PointType::Curve);
+    point.name = Some(ptype.name());
+    point
+}
+
+pub fn is_spiro_contour(contour: &MFEKContour<MFEKPointData>) -> bool {
+    match contour.cubic() {
+        Some(points) => !points.is_empty() && points.iter().all(|p| point_type(p).is_some()),
+        None => false,
+    }
+}
+
+type Vec2 = (f64, f64);
+
+fn sub(a: Vec2, b: Vec2) -> Vec2 {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn length(a: Vec2) -> f64 {
+    f64::hypot(a.0, a.1)
+}
+
+fn angle(a: Vec2) -> f64 {
+    f64::atan2(a.1, a.0)
+}
+
+/// Wraps an angle into [-π, π).
+fn mod_2pi(th: f64) -> f64 {
+    let u = th / (2. * PI);
+    2. * PI * (u - (u + 0.5).floor())
+}
+
+// Is the segment from point idx to the next one a straight line?
+fn is_straight(types: &[SpiroPointType], positions: &[Vec2], idx: usize) -> bool {
+    let next = (idx + 1) % types.len();
+    length(sub(positions[next], positions[idx])) < f64::EPSILON
+        || types[idx] == SpiroPointType::Left
+        || types[next] == SpiroPointType::Right
+        || (types[idx] == SpiroPointType::Corner && types[next] == SpiroPointType::Corner)
+}
+
+/// A polynomial spiral running over arc length s from -1/2 to 1/2. Its tangent angle at s is
+/// k0 s + k1 s²/2 + k2 s³/6 + k3 s⁴/24, so its curvature is k0 + k1 s + k2 s²/2 + k3 s³/6.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Spiral([f64; 4]);
+
+impl Spiral {
+    fn angle(&self, s: f64) -> f64 {
+        let k = &self.0;
+        (((k[3] / 24. * s + k[2] / 6.) * s + k[1] / 2.) * s + k[0]) * s
+    }
+
+    /// The curvature at s, or its first or second derivative.
+    fn curvature(&self, s: f64, derivative: usize) -> f64 {
+        let k = &self.0;
+        match derivative {
+            0 => ((k[3] / 6. * s + k[2] / 2.) * s + k[1]) * s + k[0],
+            1 => (k[3] / 2. * s + k[2]) * s + k[1],
+            _ => k[3] * s + k[2],
+        }
+    }
+
+    /// A bound on how far the spiral turns, as libspiro measures it.
+    fn bend(&self) -> f64 {
+        let k = &self.0;
+        k[0].abs() + (k[1] / 2.).abs() + (k[2] / 8.).abs() + (k[3] / 48.).abs()
+    }
+
+    /// Where the spiral is at `to` relative to where it is at `from`.
+    fn integrate(&self, from: f64, to: f64) -> Vec2 {
+        // Each step of the quadrature turns through at most about a quarter of a radian.
+        let steps = 1 + ((self.bend() * (to - from).abs() * 4.) as usize).min(255);
+        let h = (to - from) / steps as f64;
+        let (mut x, mut y) = (0., 0.);
+        for step in 0..steps {
+            let mid = from + h * (step as f64 + 0.5);
+            for (node, weight) in GAUSS_LEGENDRE {
+                for s in [mid - node * h / 2., mid + node * h / 2.] {
+                    let th = self.angle(s);
+                    x += weight * th.cos();
+                    y += weight * th.sin();
+                }
+            }
+        }
+        (x * h / 2., y * h / 2.)
+    }
+
+    /// The length and angle of the spiral's chord.
+    fn chord(&self) -> (f64, f64) {
+        let chord = self.integrate(-0.5, 0.5);
+        (length(chord), angle(chord))
+    }
+
+    /// The spiral between two places along it, reparameterized to run from -1/2 to 1/2.
+    fn part(&self, from: f64, to: f64) -> Self {
+        let (mid, width) = ((from + to) / 2., to - from);
+        Self([
+            width * self.curvature(mid, 0),
+            width.powi(2) * self.curvature(mid, 1),
+            width.powi(3) * self.curvature(mid, 2),
+            width.powi(4) * self.0[3],
+        ])
+    }
+
+    /// At the start and end of the spiral, scaled so its chord is `seg_ch` long: the angle between
+    /// its tangent and chord, its curvature, and that curvature's first two derivatives. The start
+    /// angle is measured from the tangent to the chord and the end one from the chord to the
+    /// tangent, so they add up to how far the spiral turns.
+    fn ends(&self, seg_ch: f64) -> [[f64; 4]; 2] {
+        let (ch, th) = self.chord();
+        let l = ch / seg_ch;
+        let mut ends = [[0.; 4]; 2];
+        for (end, s) in [(0, -0.5), (1, 0.5)] {
+            ends[end][0] = if end == 0 { th - self.angle(s) } else { self.angle(s) - th };
+            let mut scale = l;
+            for derivative in 0..3 {
+                ends[end][derivative + 1] = self.curvature(s, derivative) * scale;
+                scale *= l;
+            }
+        }
+        ends
+    }
+}
+
+/// What joins a point of a run to the curve on either side of it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Knot {
+    /// Nothing: a corner, or the end of an open contour.
+    Free,
+    G2,
+    G4,
+    /// A straight line at the given angle, which the curve joins smoothly with zero curvature.
+    Line(f64),
+}
+
+/// How many of a spiral's parameters are solved for, given what joins its ends. The rest are
+/// zero, so a spiral between two G2 points is an Euler spiral and one between corners is straight.
+fn unknowns(start: Knot, end: Knot) -> usize {
+    use Knot::*;
+    match (start, end) {
+        (G4, _) | (_, G4) | (Line(_), Line(_)) => 4,
+        (Line(_), G2) | (G2, Line(_)) => 3,
+        (G2, G2) | (Line(_), Free) | (Free, Line(_)) => 2,
+        (Free, G2) | (G2, Free) => 1,
+        (Free, Free) => 0,
+    }
+}
+
+/// One step of Newton's method over the spirals of a run. Each constraint is a row of the
+/// Jacobian of the spirals' ends with respect to their free parameters.
+struct Newton {
+    offsets: Vec<usize>,
+    counts: Vec<usize>,
+    ends: Vec<[[f64; 4]; 2]>,
+    // derivatives[seg][parameter][end][quantity]
+    derivatives: Vec<Vec<[[f64; 4]; 2]>>,
+    matrix: Vec<Vec<f64>>,
+    rhs: Vec<f64>,
+}
+
+impl Newton {
+    fn new(spirals: &[Spiral], chords: &[f64], knots: &[Knot]) -> Self {
+        let n = knots.len();
+        let counts: Vec<usize> = (0..spirals.len())
+            .map(|si| unknowns(knots[si], knots[(si + 1) % n]))
+            .collect();
+        let offsets = counts
+            .iter()
+            .scan(0, |offset, count| {
+                let this = *offset;
+                *offset += count;
+                Some(this)
+            })
+            .collect();
+        let ends: Vec<_> = spirals.iter().zip(chords).map(|(s, ch)| s.ends(*ch)).collect();
+        let derivatives = spirals
+            .iter()
+            .zip(chords)
+            .zip(&counts)
+            .zip(&ends)
+            .map(|(((spiral, ch), count), ends)| {
+                (0..*count)
+                    .map(|k| {
+                        let mut moved = *spiral;
+                        moved.0[k] += DERIVATIVE_STEP;
+                        let mut derivative = moved.ends(*ch);
+                        let before = ends.iter().flatten();
+                        for (d, e) in derivative.iter_mut().flatten().zip(before) {
+                            *d = (*d - e) / DERIVATIVE_STEP;
+                        }
+                        derivative
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            offsets,
+            counts,
+            ends,
+            derivatives,
+            matrix: vec![],
+            rhs: vec![],
+        }
+    }
+
+    fn unknowns(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// Adds a row whose right hand side is `rhs` and returns its index.
+    fn row(&mut self, rhs: f64) -> usize {
+        self.matrix.push(vec![0.; self.unknowns()]);
+        self.rhs.push(rhs);
+        self.rhs.len() - 1
+    }
+
+    /// Adds how quantity `q` at `end` of spiral `seg` changes with its parameters to a row.
+    fn add(&mut self, row: usize, seg: usize, end: usize, q: usize, sign: f64) {
+        for k in 0..self.counts[seg] {
+            self.matrix[row][self.offsets[seg] + k] += sign * self.derivatives[seg][k][end][q];
+        }
+    }
+
+    /// Solves the rows by Gaussian elimination with partial pivoting.
+    fn solve(mut self) -> Option<Vec<f64>> {
+        let n = self.unknowns();
+        if self.rhs.len() != n {
+            log::error!("Spiro run has {} constraints for {} unknowns", self.rhs.len(), n);
+            return None;
+        }
+        for col in 0..n {
+            let pivot = (col..n)
+                .max_by(|a, b| self.matrix[*a][col].abs().total_cmp(&self.matrix[*b][col].abs()))?;
+            let size = self.matrix[pivot][col].abs();
+            if size.is_nan() || size < 1e-14 {
+                return None;
+            }
+            self.matrix.swap(col, pivot);
+            self.rhs.swap(col, pivot);
+            let (pivot_row, pivot_rhs) = (self.matrix[col].clone(), self.rhs[col]);
+            for (row, rhs) in self.matrix.iter_mut().zip(self.rhs.iter_mut()).skip(col + 1) {
+                let f = row[col] / pivot_row[col];
+                for (value, pivot) in row.iter_mut().zip(&pivot_row).skip(col) {
+                    *value -= f * pivot;
+                }
+                *rhs -= f * pivot_rhs;
+            }
+        }
+        let mut x = vec![0.; n];
+        for row in (0..n).rev() {
+            let sum: f64 = (row + 1..n).map(|c| self.matrix[row][c] * x[c]).sum();
+            x[row] = (self.rhs[row] - sum) / self.matrix[row][row];
+        }
+        Some(x)
+    }
+}
+
+/// Solves the spirals of a run of points. An open run has one spiral fewer than points, and its
+/// first and last knots are `Free` or `Line`; a cyclic one has a spiral from every point.
+fn solve_run(positions: &[Vec2], knots: &[Knot], cyclic: bool) -> Vec<Spiral> {
+    let n = positions.len();
+    let seg_count = if cyclic { n } else { n - 1 };
+    let chord = |si: usize| sub(positions[(si + 1) % n], positions[si]);
+    let chords: Vec<f64> = (0..seg_count).map(|si| length(chord(si))).collect();
+    let chord_angles: Vec<f64> = (0..seg_count).map(|si| angle(chord(si))).collect();
+    let mut spirals = vec![Spiral::default(); seg_count];
+
+    for _ in 0..NEWTON_ITERATIONS {
+        let mut newton = Newton::new(&spirals, &chords, knots);
+
+        for pi in 0..n {
+            let (prev, next) = match (pi, cyclic) {
+                (0, false) => (None, Some(0)),
+                (pi, false) if pi == n - 1 => (Some(pi - 1), None),
+                (pi, _) => (Some((pi + seg_count - 1) % seg_count), Some(pi % seg_count)),
+            };
+            match (knots[pi], prev, next) {
+                (Knot::G2 | Knot::G4, Some(prev), Some(next)) => {
+                    // The tangent turns by the angle between the chords on either side.
+                    let bend = mod_2pi(chord_angles[next] - chord_angles[prev]);
+                    let (e0, e1) = (newton.ends[prev], newton.ends[next]);
+                    let row = newton.row(mod_2pi(bend - e0[1][0] - e1[0][0]));
+                    newton.add(row, prev, 1, 0, 1.);
+                    newton.add(row, next, 0, 0, 1.);
+                    let continuous = if knots[pi] == Knot::G4 { 1..4 } else { 1..2 };
+                    for q in continuous {
+                        let row = newton.row(e1[0][q] - e0[1][q]);
+                        newton.add(row, prev, 1, q, 1.);
+                        newton.add(row, next, 0, q, -1.);
+                    }
+                }
+                (Knot::Line(line), None, Some(seg)) => {
+                    let e = newton.ends[seg][0][0];
+                    let row = newton.row(mod_2pi(chord_angles[seg] - line - e));
+                    newton.add(row, seg, 0, 0, 1.);
+                    let row = newton.row(-newton.ends[seg][0][1]);
+                    newton.add(row, seg, 0, 1, 1.);
+                }
+                (Knot::Line(line), Some(seg), None) => {
+                    let e = newton.ends[seg][1][0];
+                    let row = newton.row(mod_2pi(line - chord_angles[seg] - e));
+                    newton.add(row, seg, 1, 0, 1.);
+                    let row = newton.row(-newton.ends[seg][1][1]);
+                    newton.add(row, seg, 1, 1, 1.);
+                }
+                _ => {}
+            }
+        }
+
+        // A spiral with all four parameters free is pinned down at ends which don't fix them all.
+        for si in 0..seg_count {
+            if newton.counts[si] != 4 {
+                continue;
+            }
+            for (end, knot) in [(0, knots[si]), (1, knots[(si + 1) % n])] {
+                let pinned = match knot {
+                    Knot::Free => 2..4,
+                    Knot::G2 => 3..4,
+                    _ => 0..0,
+                };
+                for q in pinned {
+                    let row = newton.row(-newton.ends[si][end][q]);
+                    newton.add(row, si, end, q, 1.);
+                }
+            }
+        }
+
+        let (offsets, counts) = (newton.offsets.clone(), newton.counts.clone());
+        let delta = match newton.solve() {
+            Some(delta) => delta,
+            None => break,
+        };
+        let mut norm: f64 = 0.;
+        for (si, spiral) in spirals.iter_mut().enumerate() {
+            for (k, d) in delta[offsets[si]..offsets[si] + counts[si]].iter().enumerate() {
+                spiral.0[k] += d;
+                norm += d * d;
+            }
+            spiral.0[0] = 2. * mod_2pi(spiral.0[0] / 2.);
+        }
+        if !norm.is_finite() {
+            log::error!("Spiro solver diverged");
+            return vec![Spiral::default(); seg_count];
+        }
+        if norm < NEWTON_TOLERANCE {
+            break;
+        }
+    }
+
+    spirals
+}
+
+/// A cubic from the end of the previous one: its two off-curve points, then its end.
+type Cubic = (Vec2, Vec2, Vec2);
+
+/// Draws a solved spiral from `p0` to `p1` as cubics, halving it wherever it turns too far for one
+/// cubic to follow it closely, but at most `max_subdivisions` times.
+fn spiral_to_cubics(spiral: &Spiral, p0: Vec2, p1: Vec2, max_subdivisions: usize) -> Vec<Cubic> {
+    let (ch, th) = spiral.chord();
+    let seg = sub(p1, p0);
+    let scale = length(seg) / ch;
+    let rot = angle(seg) - th;
+    let at = |s: f64| {
+        let (x, y) = spiral.integrate(-0.5, s);
+        let (sin, cos) = rot.sin_cos();
+        (p0.0 + scale * (x * cos - y * sin), p0.1 + scale * (x * sin + y * cos))
+    };
+
+    fn split(
+        spiral: &Spiral,
+        from: f64,
+        to: f64,
+        depth: usize,
+        max_subdivisions: usize,
+        out: &mut Vec<(f64, f64)>,
+    ) {
+        if depth < max_subdivisions && spiral.part(from, to).bend() > MAX_BEND {
+            let mid = (from + to) / 2.;
+            split(spiral, from, mid, depth + 1, max_subdivisions, out);
+            split(spiral, mid, to, depth + 1, max_subdivisions, out);
+        } else {
+            out.push((from, to));
+        }
+    }
+    let mut parts = vec![];
+    split(spiral, -0.5, 0.5, 0, max_subdivisions, &mut parts);
+
+    parts
+        .into_iter()
+        .map(|(from, to)| {
+            // Handles a third of the arc length long along the tangents, as libspiro draws them.
+            let handle = scale * (to - from) / 3.;
+            let (start, end) = (rot + spiral.angle(from), rot + spiral.angle(to));
+            let (a, b) = (at(from), if to >= 0.5 { p1 } else { at(to) });
+            (
+                (a.0 + handle * start.cos(), a.1 + handle * start.sin()),
+                (b.0 - handle * end.cos(), b.1 - handle * end.sin()),
+                b,
+            )
+        })
+        .collect()
+}
+
+/// Solves the Spiro curve through a contour's points, returning the cubics of each of its segments,
+/// or `None` for straight ones.
+fn solve(
+    points: &[Point<impl PointData>],
+    open: bool,
+    max_subdivisions: usize,
+) -> Vec<Option<Vec<Cubic>>> {
+    let len = points.len();
+    let types: Vec<_> = points
+        .iter()
+        .map(|p| point_type(p).unwrap_or(SpiroPointType::Corner))
+        .collect();
+    let positions: Vec<Vec2> = points.iter().map(|p| (p.x as f64, p.y as f64)).collect();
+    let seg_count = if open { len - 1 } else { len };
+
+    // Every segment starts out straight, and the curved ones are filled in once they're solved.
+    let mut segments: Vec<Option<Vec<Cubic>>> = vec![None; seg_count];
+
+    let straight: Vec<bool> = (0..seg_count)
+        .map(|si| is_straight(&types, &positions, si))
+        .collect();
+    let is_break = |pi: usize| {
+        !types[pi].is_smooth() || (open && (pi == 0 || pi == len - 1))
+    };
+
+    // Collect the runs of curved segments as lists of point indices. A closed contour with no
+    // breaks at all is a single run which wraps around.
+    let mut runs: Vec<(Vec<usize>, bool)> = vec![];
+    match (0..len).find(|pi| is_break(*pi)) {
+        None => runs.push(((0..len).collect(), true)),
+        Some(first_break) => {
+            let mut run = vec![first_break];
+            for step in 0..seg_count {
+                let si = (first_break + step) % len;
+                let next = (si + 1) % len;
+                if straight[si] {
+                    if run.len() > 1 {
+                        runs.push((run, false));
+                    }
+                    run = vec![next];
+                    continue;
+                }
+                run.push(next);
+                if is_break(next) {
+                    runs.push((run, false));
+                    run = vec![next];
+                }
+            }
+        }
+    }
+
+    // The end of a run joins a straight segment smoothly unless it's a corner or open end.
+    let end_knot = |pi: usize, line: Option<usize>| match line {
+        Some(si) if types[pi] != SpiroPointType::Corner && straight[si] => {
+            let chord = sub(positions[(si + 1) % len], positions[si]);
+            if length(chord) < f64::EPSILON {
+                Knot::Free
+            } else {
+                Knot::Line(angle(chord))
+            }
+        }
+        _ => Knot::Free,
+    };
+
+    for (run, cyclic) in runs {
+        let run_positions: Vec<Vec2> = run.iter().map(|pi| positions[*pi]).collect();
+        let mut knots: Vec<Knot> = run
+            .iter()
+            .map(|pi| match types[*pi] {
+                SpiroPointType::G4 => Knot::G4,
+                _ => Knot::G2,
+            })
+            .collect();
+        if !cyclic {
+            let (first, last) = (run[0], *run.last().unwrap());
+            let incoming = (!open || first > 0).then(|| (first + len - 1) % len);
+            let outgoing = (!open || last < len - 1).then_some(last);
+            knots[0] = end_knot(first, incoming);
+            *knots.last_mut().unwrap() = end_knot(last, outgoing);
+        }
+
+        let spirals = solve_run(&run_positions, &knots, cyclic);
+        for (ri, spiral) in spirals.iter().enumerate() {
+            let (p0, p1) = (run_positions[ri], run_positions[(ri + 1) % run.len()]);
+            segments[run[ri]] = Some(spiral_to_cubics(spiral, p0, p1, max_subdivisions));
+        }
+    }
+
+    segments
+}
+
+/// Resolves a Spiro contour to a plain cubic one. Long or tightly turning segments are drawn with
+/// more than one cubic, except on contours with an operation, whose data is kept per point: those
+/// get one cubic per segment.
+pub fn resolve(contour: &MFEKContour<MFEKPointData>) -> MFEKContour<MFEKPointData> {
+    resolve_with(contour, contour.operation().is_some())
+}
+
+/// Resolves a Spiro contour to a plain cubic one, with one cubic per segment if `per_point` is set.
+pub fn resolve_with(
+    contour: &MFEKContour<MFEKPointData>,
+    per_point: bool,
+) -> MFEKContour<MFEKPointData> {
+    let mut resolved = contour.clone();
+    let points = match contour.cubic() {
+        Some(points) if points.len() > 1 => points,
+        _ => return resolved,
+    };
+    let len = points.len();
+    let max_subdivisions = if per_point { 0 } else { MAX_SUBDIVISIONS };
+    let segments = solve(points, contour.is_open(), max_subdivisions);
+
+    let to_handle = |(x, y): Vec2| Handle::At(x as f32, y as f32);
+    let mut out: Contour<MFEKPointData> = points
+        .iter()
+        .map(|point| {
+            let mut point = point.clone();
+            point.name = None;
+            point.a = Handle::Colocated;
+            point.b = Handle::Colocated;
+            point
+        })
+        .collect();
+    // Segments are inserted from the last, so the points before each one haven't moved yet.
+    for (si, cubics) in segments.iter().enumerate().rev() {
+        let cubics = match cubics {
+            Some(cubics) => cubics,
+            None => continue,
+        };
+        out[(si + 1) % len].b = to_handle(cubics.last().unwrap().1);
+        out[si].a = to_handle(cubics[0].0);
+        let between: Vec<_> = cubics
+            .windows(2)
+            .map(|w| {
+                Point::from_x_y_a_b_type(
+                    (w[0].2 .0 as f32, w[0].2 .1 as f32),
+                    (to_handle(w[1].0), to_handle(w[0].1)),
+                    PointType::Curve,
+                )
+            })
+            .collect();
+        out.splice(si + 1..si + 1, between);
+    }
+
+    *resolved.cubic_mut().unwrap() = out;
+    resolved
+}
+
+#[test]
+fn spiro_circle_test() {
+    // Four G2 points on a circle solve to the circle itself, the one curve of constant curvature
+    // through them.
+    let points: Contour<MFEKPointData> = [(100., 0.), (0., 100.), (-100., 0.), (0., -100.)]
+        .into_iter()
+        .map(|position| new_point(position, SpiroPointType::G2))
+        .collect();
+    let resolved = resolve(&points.into());
+    let resolved = resolved.cubic().unwrap();
+    // Each quarter turns too far for a single cubic.
+    assert!(resolved.len() > 4);
+
+    for (pi, point) in resolved.iter().enumerate() {
+        let next = &resolved[(pi + 1) % resolved.len()];
+        let (ax, ay, bx, by) = match (point.a, next.b) {
+            (Handle::At(ax, ay), Handle::At(bx, by)) => (ax, ay, bx, by),
+            _ => panic!("Curved segment without handles"),
+        };
+        let middle = (
+            (point.x + 3. * ax + 3. * bx + next.x) / 8.,
+            (point.y + 3. * ay + 3. * by + next.y) / 8.,
+        );
+        assert!((f32::hypot(point.x, point.y) - 100.).abs() < 0.01);
+        assert!((f32::hypot(middle.0, middle.1) - 100.).abs() < 0.25);
+    }
+}
+
+#[test]
+fn spiro_open_contour_test() {
+    let mut points: Contour<MFEKPointData> = [(0., 0.), (100., 50.), (200., 0.)]
+        .into_iter()
+        .map(|position| new_point(position, SpiroPointType::G2))
+        .collect();
+    points[0].ptype = PointType::Move;
+    let contour: MFEKContour<MFEKPointData> = points.into();
+    assert!(contour.is_open());
+
+    let resolved = resolve(&contour);
+    assert!(resolved.is_open());
+    let resolved = resolved.cubic().unwrap();
+    assert_eq!(resolved[0].ptype, PointType::Move);
+    assert!(resolved.iter().all(|p| p.name.is_none()));
+}
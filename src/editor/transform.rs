@@ -0,0 +1,217 @@
+use glifparser::{
+    glif::{contour::MFEKContourCommon, point::MFEKPointCommon, Layer},
+    Handle, MFEKPointData, PointData, WhichHandle,
+};
+use kurbo::Affine;
+use MFEKmath::{Rect, Vector};
+
+use super::Editor;
+
+use std::collections::HashSet;
+
+/// What a transform is applied to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransformTarget {
+    Selection,
+    Layer,
+    AllLayers,
+}
+
+/// The nine reference points of a bounding box. Transforms use these as their origin, and the
+/// Select tool's bounding box uses all but the center as scale handles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundingBoxAnchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl BoundingBoxAnchor {
+    /// In reading order, so they can be laid out in a 3×3 grid.
+    pub const ALL: [Self; 9] = [
+        Self::TopLeft,
+        Self::Top,
+        Self::TopRight,
+        Self::Left,
+        Self::Center,
+        Self::Right,
+        Self::BottomLeft,
+        Self::Bottom,
+        Self::BottomRight,
+    ];
+
+    /// Horizontal and vertical position within the box, where -1 is left/bottom and 1 is
+    /// right/top.
+    pub fn factors(self) -> (i8, i8) {
+        match self {
+            Self::TopLeft => (-1, 1),
+            Self::Top => (0, 1),
+            Self::TopRight => (1, 1),
+            Self::Left => (-1, 0),
+            Self::Center => (0, 0),
+            Self::Right => (1, 0),
+            Self::BottomLeft => (-1, -1),
+            Self::Bottom => (0, -1),
+            Self::BottomRight => (1, -1),
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        let (fx, fy) = self.factors();
+        Self::ALL
+            .into_iter()
+            .find(|a| a.factors() == (-fx, -fy))
+            .unwrap()
+    }
+
+    pub fn position(self, rect: &Rect) -> (f32, f32) {
+        let (left, right) = (rect.left.min(rect.right), rect.left.max(rect.right));
+        let (bottom, top) = (rect.bottom.min(rect.top), rect.bottom.max(rect.top));
+        let (fx, fy) = self.factors();
+        let x = left + (right - left) * (fx as f64 + 1.) / 2.;
+        let y = bottom + (top - bottom) * (fy as f64 + 1.) / 2.;
+        (x as f32, y as f32)
+    }
+}
+
+/// A numeric transformation. Angles are in degrees, scales are factors (1 is unchanged).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransformOperation {
+    Move { x: f64, y: f64 },
+    Scale { x: f64, y: f64 },
+    Rotate { angle: f64 },
+    Skew { x: f64, y: f64 },
+    /// Mirror across the vertical line through the origin if horizontal, else the horizontal one.
+    Mirror { horizontal: bool },
+}
+
+impl TransformOperation {
+    /// Builds the affine for this operation about `origin`. Moves ignore the origin.
+    pub fn to_affine(&self, origin: (f32, f32)) -> Affine {
+        let linear = match *self {
+            Self::Move { x, y } => return Affine::translate((x, y)),
+            Self::Scale { x, y } => Affine::scale_non_uniform(x, y),
+            Self::Rotate { angle } => Affine::rotate(angle.to_radians()),
+            Self::Skew { x, y } => Affine::skew(x.to_radians().tan(), y.to_radians().tan()),
+            Self::Mirror { horizontal: true } => Affine::scale_non_uniform(-1., 1.),
+            Self::Mirror { horizontal: false } => Affine::scale_non_uniform(1., -1.),
+        };
+
+        let origin = (origin.0 as f64, origin.1 as f64);
+        Affine::translate(origin) * linear * Affine::translate((-origin.0, -origin.1))
+    }
+}
+
+/// Applies an affine transformation to a point and its handles.
+pub fn transform_point<PD: PointData>(point: &mut dyn MFEKPointCommon<PD>, affine: &Affine) {
+    let apply = |x: f32, y: f32| {
+        let p = *affine * kurbo::Point::new(x as f64, y as f64);
+        (p.x as f32, p.y as f32)
+    };
+
+    let (x, y) = apply(point.x(), point.y());
+    point.set_position_no_handles(x, y);
+
+    for wh in [WhichHandle::A, WhichHandle::B] {
+        if let Some(Handle::At(hx, hy)) = point.get_handle(wh) {
+            let (hx, hy) = apply(hx, hy);
+            point.set_handle(wh, Handle::At(hx, hy));
+        }
+    }
+}
+
+fn push_point_and_handles<PD: PointData>(points: &mut Vec<Vector>, point: &dyn MFEKPointCommon<PD>) {
+    points.push(Vector {
+        x: point.x() as f64,
+        y: point.y() as f64,
+    });
+    for wh in [WhichHandle::A, WhichHandle::B] {
+        if let Some(Handle::At(x, y)) = point.get_handle(wh) {
+            points.push(Vector {
+                x: x as f64,
+                y: y as f64,
+            });
+        }
+    }
+}
+
+fn layer_bounding_points(layer: &Layer<MFEKPointData>, points: &mut Vec<Vector>) {
+    for contour in layer.outline.iter() {
+        for pi in 0..contour.len() {
+            push_point_and_handles(points, contour.get_point(pi).unwrap());
+        }
+    }
+}
+
+impl Editor {
+    /// The points a Selection transform applies to: the selection plus the point being edited.
+    pub fn transform_selection(&self) -> HashSet<(usize, usize)> {
+        let mut selected = self.selected.clone();
+        if let (Some(ci), Some(pi)) = (self.contour_idx, self.point_idx) {
+            selected.insert((ci, pi));
+        }
+        selected
+    }
+
+    /// Bounding box of everything the target covers, including handles. None if it's empty.
+    pub fn build_target_bounding_box(&self, target: TransformTarget) -> Option<Rect> {
+        let mut points = vec![];
+        match target {
+            TransformTarget::Selection => {
+                if self.selected.len() > 1 {
+                    return Some(self.build_selection_bounding_box());
+                }
+                let layer = self.get_active_layer_ref();
+                for (ci, pi) in self.transform_selection() {
+                    push_point_and_handles(&mut points, layer.outline[ci].get_point(pi).unwrap());
+                }
+            }
+            TransformTarget::Layer => layer_bounding_points(self.get_active_layer_ref(), &mut points),
+            TransformTarget::AllLayers => self.with_glyph(|glyph| {
+                for layer in glyph.layers.iter() {
+                    layer_bounding_points(layer, &mut points);
+                }
+            }),
+        }
+
+        if points.is_empty() {
+            None
+        } else {
+            Some(Rect::AABB_from_points(points))
+        }
+    }
+
+    /// Applies `affine` to every point covered by `target` as a single history entry.
+    pub fn transform(&mut self, target: TransformTarget, affine: Affine) {
+        let selection = self.transform_selection();
+        if target == TransformTarget::Selection && selection.is_empty() {
+            return;
+        }
+
+        let layers: Vec<usize> = match target {
+            TransformTarget::AllLayers => (0..self.get_layer_count()).collect(),
+            _ => vec![self.layer_idx.unwrap()],
+        };
+
+        self.begin_modification("Transform.", false);
+        self.with_glyph_mut(|glyph| {
+            for li in &layers {
+                for (ci, contour) in glyph.layers[*li].outline.iter_mut().enumerate() {
+                    for pi in 0..contour.len() {
+                        if target == TransformTarget::Selection && !selection.contains(&(ci, pi)) {
+                            continue;
+                        }
+                        transform_point(contour.get_point_mut(pi).unwrap(), &affine);
+                    }
+                }
+            }
+        });
+        self.end_modification();
+    }
+}
@@ -7,13 +7,17 @@ use flo_curves::{
     bezier::{solve_curve_for_t_along_axis, Curve as FloCurve},
     geo::Coord2,
 };
-use glifparser::WhichHandle;
+use glifparser::glif::point::{quad::QPoint, MFEKPointCommon};
+use glifparser::glif::MFEKContour;
+use glifparser::{Handle, MFEKPointData, WhichHandle};
 use glifrenderer::constants::{POINT_RADIUS, POINT_STROKE_THICKNESS};
 use skia_safe::Contains;
 use skia_safe::Point as SkPoint;
 use skia_safe::Rect as SkRect;
-use MFEKmath::{Bezier, Piecewise, Primitive as MathPrimitive};
+use MFEKmath::mfek::ResolveCubic as _;
+use MFEKmath::{Bezier, Piecewise, Primitive as MathPrimitive, Vector};
 
+use super::spiro;
 use super::Editor;
 use glifparser::glif::mfek::contour::MFEKContourCommon;
 
@@ -144,6 +148,7 @@ pub fn get_contour_start_or_end(
 }
 
 pub struct HoveredPointInfo {
+    // for hyperbeziers this is along the resolved cubic segment, not the hyperbezier segment
     pub t: f64,
     pub contour_idx: usize,
     pub seg_idx: usize,
@@ -152,6 +157,61 @@ pub struct HoveredPointInfo {
     pub b: (f32, f32),
 }
 
+fn lerp(a: Vector, b: Vector, t: f64) -> Vector {
+    Vector::from_components(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+}
+
+/// Degree elevates each segment of a quadratic contour to a cubic so it can be hit tested like one.
+/// Segment i runs from point i, using its `a` as the off-curve point, to point i + 1. A colocated
+/// `a` is a straight line. Implied on-curve points are stored as ordinary points in a quadratic
+/// contour, so they're already segment ends here.
+pub fn quad_segments(points: &[QPoint<MFEKPointData>], open: bool) -> Vec<Bezier> {
+    let len = points.len();
+    let seg_count = if open { len.saturating_sub(1) } else { len };
+
+    (0..seg_count)
+        .map(|idx| {
+            let (from, to) = (&points[idx], &points[(idx + 1) % len]);
+            let p0 = Vector::from_components(from.x as f64, from.y as f64);
+            let p1 = Vector::from_components(to.x as f64, to.y as f64);
+            let control = match from.a {
+                Handle::At(x, y) => Vector::from_components(x as f64, y as f64),
+                Handle::Colocated => lerp(p0, p1, 0.5),
+            };
+
+            Bezier::from_points(
+                p0,
+                lerp(p0, control, 2. / 3.),
+                lerp(p1, control, 2. / 3.),
+                p1,
+            )
+        })
+        .collect()
+}
+
+/// For a contour and its cubic resolution, maps each cubic segment to the segment of the original
+/// contour it belongs to. Every on-curve point of the original is also an on-curve point of the
+/// resolution, so we advance whenever we reach the next one.
+pub fn resolved_segment_map(
+    contour: &MFEKContour<MFEKPointData>,
+    resolved: &MFEKContour<MFEKPointData>,
+) -> Vec<usize> {
+    let mut map = Vec::with_capacity(resolved.len());
+    let mut current = 0;
+    for ri in 0..resolved.len() {
+        if let (Some(next), Some(point)) = (contour.get_point(current + 1), resolved.get_point(ri)) {
+            if ri > 0
+                && (next.x() - point.x()).abs() < 0.01
+                && (next.y() - point.y()).abs() < 0.01
+            {
+                current += 1;
+            }
+        }
+        map.push(current);
+    }
+    map
+}
+
 pub fn nearest_point_on_curve(
     v: &Editor,
     i: &Interface,
@@ -168,43 +228,59 @@ pub fn nearest_point_on_curve(
         let mut seg_idx = None;
 
         for (cx, contour) in v.get_active_layer_ref().outline.iter().enumerate() {
-            if let Some(cubic_contour) = contour.cubic() {
-                let pw: Piecewise<Bezier> = Piecewise::from(cubic_contour);
-                for (bx, mbezier) in pw.segs.iter().enumerate() {
-                    use flo_curves::BezierCurveFactory as _;
-                    let bezier = FloCurve::from_points(
-                        Coord2(mbezier.w1.x, mbezier.w1.y),
-                        (
-                            Coord2(mbezier.w2.x, mbezier.w2.y),
-                            Coord2(mbezier.w3.x, mbezier.w3.y),
-                        ),
-                        Coord2(mbezier.w4.x, mbezier.w4.y),
-                    );
-                    let mouse_vec = Coord2(position.0 as f64, position.1 as f32 as f64);
-                    let ct = solve_curve_for_t_along_axis(
-                        &bezier,
-                        &mouse_vec,
-                        3.5 / i.viewport.factor as f64,
-                    );
-
-                    if let Some(ct) = ct {
-                        use flo_curves::BezierCurve as _;
-                        use flo_curves::Coordinate as _;
-                        let new_distance = bezier.point_at_pos(ct).distance_to(&mouse_vec);
-                        if new_distance < distance {
-                            distance = new_distance;
-                            current = Some(bezier.point_at_pos(ct));
-                            t = Some(ct);
-                            contour_idx = Some(cx);
-                            seg_idx = Some(bx);
-
-                            let subdivisions = MathPrimitive::subdivide(mbezier, ct);
-                            if let Some(subdivisions) = subdivisions {
-                                h1 = Some(subdivisions.0.to_control_points()[2]);
-                                h2 = Some(subdivisions.1.to_control_points()[1]);
-                            } else {
-                                return None;
-                            }
+            // Hyperbeziers and Spiros are hit tested on their cubic resolution, so we map each cubic
+            // segment back to the segment it was resolved from.
+            let mut seg_map = None;
+            let segs = if spiro::is_spiro_contour(contour) {
+                let resolved = spiro::resolve(contour);
+                seg_map = Some(resolved_segment_map(contour, &resolved));
+                Piecewise::<Bezier>::from(resolved.cubic().unwrap()).segs
+            } else if let Some(cubic_contour) = contour.cubic() {
+                Piecewise::<Bezier>::from(cubic_contour).segs
+            } else if let Some(quad_contour) = contour.quad() {
+                quad_segments(quad_contour, contour.is_open())
+            } else if contour.hyper().is_some() {
+                let resolved = contour.to_cubic();
+                seg_map = Some(resolved_segment_map(contour, &resolved));
+                Piecewise::<Bezier>::from(resolved.cubic().unwrap()).segs
+            } else {
+                continue;
+            };
+
+            for (bx, mbezier) in segs.iter().enumerate() {
+                use flo_curves::BezierCurveFactory as _;
+                let bezier = FloCurve::from_points(
+                    Coord2(mbezier.w1.x, mbezier.w1.y),
+                    (
+                        Coord2(mbezier.w2.x, mbezier.w2.y),
+                        Coord2(mbezier.w3.x, mbezier.w3.y),
+                    ),
+                    Coord2(mbezier.w4.x, mbezier.w4.y),
+                );
+                let mouse_vec = Coord2(position.0 as f64, position.1 as f32 as f64);
+                let ct = solve_curve_for_t_along_axis(
+                    &bezier,
+                    &mouse_vec,
+                    3.5 / i.viewport.factor as f64,
+                );
+
+                if let Some(ct) = ct {
+                    use flo_curves::BezierCurve as _;
+                    use flo_curves::Coordinate as _;
+                    let new_distance = bezier.point_at_pos(ct).distance_to(&mouse_vec);
+                    if new_distance < distance {
+                        distance = new_distance;
+                        current = Some(bezier.point_at_pos(ct));
+                        t = Some(ct);
+                        contour_idx = Some(cx);
+                        seg_idx = Some(seg_map.as_ref().map(|m| m[bx]).unwrap_or(bx));
+
+                        let subdivisions = MathPrimitive::subdivide(mbezier, ct);
+                        if let Some(subdivisions) = subdivisions {
+                            h1 = Some(subdivisions.0.to_control_points()[2]);
+                            h2 = Some(subdivisions.1.to_control_points()[1]);
+                        } else {
+                            return None;
                         }
                     }
                 }
@@ -0,0 +1,50 @@
+//! Custom caps for variable width strokes. glifparser's `VWSContour` has nowhere to keep them, so
+//! they're kept in the contour's data, and handed to MFEKmath whenever the stroke is built (see
+//! `operations::build_contour`).
+
+use glifparser::glif::MFEKOutline;
+use glifparser::MFEKPointData;
+
+use super::patterns::read_pattern;
+use super::Editor;
+use crate::contour_operations::variablewidthstroke::VWSCaps;
+
+/// Which end of a stroke a cap goes on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CapEnd {
+    Start,
+    End,
+}
+
+impl Editor {
+    /// The custom caps of a contour on the active layer, if it has any.
+    pub fn vws_caps(&self, contour_idx: usize) -> Option<&VWSCaps> {
+        self.contour_data(contour_idx)
+            .map(|data| &data.vws_caps)
+            .filter(|caps| !caps.is_empty())
+    }
+
+    /// Sets or clears one of the custom caps of a contour on the active layer. Must be called
+    /// during a modification.
+    pub fn set_vws_cap(
+        &mut self,
+        contour_idx: usize,
+        end: CapEnd,
+        cap: Option<MFEKOutline<MFEKPointData>>,
+    ) {
+        let mut data = self.contour_data(contour_idx).cloned().unwrap_or_default();
+        match end {
+            CapEnd::Start => data.vws_caps.start = cap,
+            CapEnd::End => data.vws_caps.end = cap,
+        }
+        self.set_contour_data(contour_idx, data);
+    }
+
+    /// Reads the outline of another glyph in the same glyphs directory to use as a cap.
+    pub fn read_cap_glyph(&self, name: &str) -> Result<MFEKOutline<MFEKPointData>, String> {
+        let path = self
+            .glyph_filename(name)
+            .ok_or_else(|| format!("No glyph named {} next to this one", name))?;
+        read_pattern(&path)
+    }
+}
@@ -14,6 +14,7 @@
 
 use crate::command::{Command, CommandInfo, CommandMod};
 use crate::editor::{
+    align::{Alignment, DistributeAxis},
     events::{EditorEvent, IOEventType, MouseEventType},
     Editor,
 };
@@ -184,6 +185,21 @@ fn main() {
                         Command::ToolImages => {
                             editor.set_tool(ToolEnum::Image);
                         }
+                        Command::ToolKnife => {
+                            editor.set_tool(ToolEnum::Knife);
+                        }
+                        Command::ToolPencil => {
+                            editor.set_tool(ToolEnum::Pencil);
+                        }
+                        Command::ToolCorners => {
+                            editor.set_tool(ToolEnum::Corners);
+                        }
+                        Command::ToolNib => {
+                            editor.set_tool(ToolEnum::Nib);
+                        }
+                        Command::ToolExpandStroke => {
+                            editor.set_tool(ToolEnum::ExpandStroke);
+                        }
                         Command::TogglePointLabels => {
                             trigger_toggle_on!(
                                 interface,
@@ -301,6 +317,30 @@ fn main() {
                         Command::ReverseContour => {
                             log::debug!("Tried to reverse contour outside Select tool");
                         }
+                        Command::SimplifySelection => {
+                            log::debug!("Tried to simplify selection outside Select tool");
+                        }
+                        Command::AddExtrema => {
+                            editor.add_extrema();
+                        }
+                        Command::RoundToGrid => {
+                            editor.round_to_grid(editor.rounding.unit);
+                        }
+                        Command::AlignLeft
+                        | Command::AlignRight
+                        | Command::AlignTop
+                        | Command::AlignBottom
+                        | Command::AlignHorizontalCenter
+                        | Command::AlignVerticalCenter => {
+                            if let Some(alignment) = Alignment::from_command(command_info.command) {
+                                editor.align_selection(alignment);
+                            }
+                        }
+                        Command::DistributeHorizontally | Command::DistributeVertically => {
+                            if let Some(axis) = DistributeAxis::from_command(command_info.command) {
+                                editor.distribute_selection(axis);
+                            }
+                        }
                         Command::SkiaDump => {
                             editor.skia_dump();
                         }
@@ -0,0 +1,108 @@
+use super::prelude::*;
+use glifparser::glif::contour_operations::ContourOperations;
+use MFEKmath::Vector;
+
+// The shortest a dash or gap can be dragged to.
+const MIN_INTERVAL: f32 = 0.5;
+
+/// Drags the end of one interval of a dash pattern along the path, changing that interval's
+/// length, or with `phase` moves the whole pattern along the path instead.
+#[derive(Clone, Debug)]
+pub struct MoveDashHandle {
+    contour_idx: usize,
+    first_segment: usize, // the run of segments whose pattern is being edited
+    interval: usize,
+    phase: bool,
+    mouse_info: MouseInfo,
+}
+
+impl MoveDashHandle {
+    pub fn new(
+        contour_idx: usize,
+        first_segment: usize,
+        interval: usize,
+        phase: bool,
+        mouse_info: MouseInfo,
+    ) -> Self {
+        MoveDashHandle {
+            contour_idx,
+            first_segment,
+            interval,
+            phase,
+            mouse_info,
+        }
+    }
+
+    fn mouse_moved(&self, v: &mut Editor, _i: &Interface, mouse_info: MouseInfo) {
+        let ci = self.contour_idx;
+        let run = match v
+            .dash_runs(ci)
+            .into_iter()
+            .find(|run| run.first_segment == self.first_segment)
+        {
+            Some(run) => run,
+            None => return,
+        };
+        let mut extras = v.dash_extras(ci);
+        let from = match run
+            .handles(extras.phase)
+            .into_iter()
+            .find(|(_, idx)| *idx == self.interval)
+        {
+            Some((at, _)) => at,
+            None => return,
+        };
+        let mouse =
+            Vector::from_components(mouse_info.position.0 as f64, mouse_info.position.1 as f64);
+        let delta = (run.distance_to(mouse) - from) as f32;
+        if delta == 0. {
+            return;
+        }
+
+        if !v.is_modifying() {
+            v.begin_modification("Move dash handle.", false)
+        }
+
+        if self.phase {
+            let period: f32 = run.pattern.iter().sum();
+            extras.phase = (extras.phase - delta).rem_euclid(period.max(f32::EPSILON));
+            v.set_dash_extras(ci, extras);
+            return;
+        }
+
+        let mut pattern = run.pattern.clone();
+        pattern[self.interval] = (pattern[self.interval] + delta).max(MIN_INTERVAL);
+        if run.overridden {
+            for segment in run.segments() {
+                extras.segment_dashes.insert(segment, pattern.clone());
+            }
+            v.set_dash_extras(ci, extras);
+        } else if let Some(ContourOperations::DashAlongPath { data }) =
+            v.get_active_layer_mut().outline[ci].operation_mut()
+        {
+            data.dash_desc = pattern;
+        }
+    }
+
+    pub fn mouse_released(&mut self, v: &mut Editor, _i: &mut Interface, mouse_info: MouseInfo) {
+        if mouse_info.button == self.mouse_info.button {
+            if v.is_modifying() {
+                v.end_modification();
+            }
+            v.pop_behavior();
+        }
+    }
+}
+
+#[rustfmt::skip]
+impl ToolBehavior for MoveDashHandle {
+    fn event(&mut self, v: &mut Editor, i: &mut Interface, event: EditorEvent) {
+        if let EditorEvent::MouseEvent { mouse_info, event_type } = event {
+            match event_type {
+                MouseEventType::Released => self.mouse_released(v, i, mouse_info),
+                MouseEventType::Moved => self.mouse_moved(v, i, mouse_info),
+                _ => (),
+            }
+        }
+    }
+}
@@ -1,19 +1,26 @@
 use super::prelude::*;
+use crate::user_interface::snap::{self, SnapQuery, SnapResult};
 
 #[derive(Clone, Debug, Default)]
 pub struct MoveGuideline {
     pub selected_idx: usize,
     pub global: bool,
     pub mouse_info: MouseInfo,
+    pub snap: SnapResult,
 }
 
 impl MoveGuideline {
-    pub fn mouse_moved(&mut self, v: &mut Editor, _i: &mut Interface, mouse_info: MouseInfo) {
+    pub fn mouse_moved(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
         if !v.is_modifying() {
             v.begin_modification("Move guideline.", false);
         }
 
-        let mp = mouse_info.position;
+        let query = SnapQuery {
+            exclude_guideline: Some(self.selected_idx),
+            ..SnapQuery::default()
+        };
+        self.snap = snap::snap(v, i, mouse_info.raw_position, &query);
+        let mp = self.snap.position;
         let selected = self.selected_idx;
 
         if self.global {
@@ -71,4 +78,10 @@ impl ToolBehavior for MoveGuideline {
             _ => {}
         }
     }
+
+    fn draw(&mut self, v: &Editor, i: &Interface, canvas: &mut Canvas) {
+        if v.is_modifying() {
+            snap::draw_snap_indicator(i, canvas, &self.snap);
+        }
+    }
 }
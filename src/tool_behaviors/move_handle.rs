@@ -1,6 +1,7 @@
 use super::prelude::*;
 use crate::get_point_mut;
 pub use crate::user_interface::follow::Follow;
+use crate::user_interface::snap::{self, SnapQuery, SnapResult};
 use glifparser::PointData;
 use glifparser::glif::contour::MFEKContourCommon;
 use glifparser::glif::point::MFEKPointCommon;
@@ -19,6 +20,12 @@ pub struct MoveHandle {
     creating: bool,
 
     warned_force_line: bool,
+
+    // the last snap, kept around so we can draw it
+    snap: SnapResult,
+
+    // where the mouse was last time it moved, used to reposition the point while creating
+    last_position: (f32, f32),
 }
 
 // Event handlers
@@ -29,20 +36,51 @@ impl MoveHandle {
             mouse_info,
             creating,
             warned_force_line: false,
+            snap: SnapResult::default(),
+            last_position: mouse_info.raw_position,
         }
     }
 
-    pub fn mouse_moved(&mut self, v: &mut Editor, _i: &mut Interface, mouse_info: MouseInfo) {
+    pub fn mouse_moved(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
         if !v.is_modifying() {
             v.begin_modification("Move handle.", false);
         }
 
         self.mouse_info.modifiers = mouse_info.modifiers;
 
-        let (x, y) = mouse_info.position;
-
         let (vci, vpi) = (v.contour_idx.unwrap(), v.point_idx.unwrap());
 
+        // While drawing out a new point's handles, Ctrl moves the point being placed instead.
+        let last_position = std::mem::replace(&mut self.last_position, mouse_info.raw_position);
+        if self.creating && mouse_info.modifiers.ctrl {
+            let (dx, dy) = (
+                mouse_info.raw_position.0 - last_position.0,
+                mouse_info.raw_position.1 - last_position.1,
+            );
+            let layer = v.get_active_layer_mut();
+            let point = get_point_mut!(layer, vci, vpi).unwrap();
+            point.set_position(point.x() + dx, point.y() + dy);
+            self.snap = SnapResult::default();
+            return;
+        }
+
+        // Handles line up with their own point rather than its neighbours.
+        let point_position = get_point!(v.get_active_layer_ref(), vci, vpi)
+            .map(|p| (p.x(), p.y()))
+            .unwrap();
+        let query = SnapQuery {
+            exclude_points: [(vci, vpi)].into_iter().collect(),
+            exclude_guideline: None,
+            align_to: vec![point_position],
+        };
+        let (x, y) = if mouse_info.modifiers.shift {
+            self.snap = SnapResult::default();
+            snap::constrain_angle(point_position, mouse_info.raw_position)
+        } else {
+            self.snap = snap::snap(v, i, mouse_info.raw_position, &query);
+            self.snap.position
+        };
+
         {
             let layer = v.get_active_layer_mut();
             let point = get_point_mut!(layer, vci, vpi).unwrap();
@@ -57,7 +95,10 @@ impl MoveHandle {
             // Difference in x, difference in y
             let (dx, dy) = (cx - x, cy - y);
 
-            let follow = if self.creating {
+            // Alt breaks the symmetry of a new point's handles.
+            let follow = if self.creating && self.mouse_info.modifiers.alt {
+                Follow::No
+            } else if self.creating {
                 Follow::Mirror
             } else {
                 Follow::from(self.mouse_info)
@@ -129,4 +170,10 @@ impl ToolBehavior for MoveHandle {
             _ => {}
         }
     }
+
+    fn draw(&mut self, v: &Editor, i: &Interface, canvas: &mut Canvas) {
+        if v.is_modifying() {
+            snap::draw_snap_indicator(i, canvas, &self.snap);
+        }
+    }
 }
@@ -3,12 +3,14 @@ use std::convert::TryInto;
 use kurbo::Affine;
 
 use super::prelude::*;
+use crate::user_interface::snap::{self, SnapQuery, SnapResult};
 
 #[derive(Clone, Debug)]
 pub struct MoveImage {
     selected_idx: usize,
     mouse_info: MouseInfo,
     last_position: (f32, f32),
+    snap: SnapResult,
 }
 
 impl MoveImage {
@@ -16,17 +18,21 @@ impl MoveImage {
         MoveImage {
             selected_idx,
             mouse_info,
-            last_position: mouse_info.position,
+            last_position: mouse_info.raw_position,
+            snap: SnapResult::default(),
         }
     }
 
-    pub fn mouse_moved(&mut self, v: &mut Editor, _i: &mut Interface, mouse_info: MouseInfo) {
+    pub fn mouse_moved(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
         if !v.is_modifying() {
             v.begin_modification("Move image.", false);
         }
 
-        let dx = mouse_info.position.0 - self.last_position.0;
-        let dy = mouse_info.position.1 - self.last_position.1;
+        self.snap = snap::snap(v, i, mouse_info.raw_position, &SnapQuery::default());
+        let position = self.snap.position;
+
+        let dx = position.0 - self.last_position.0;
+        let dy = position.1 - self.last_position.1;
 
         self.mouse_info = mouse_info;
 
@@ -45,7 +51,7 @@ impl MoveImage {
             v.get_active_layer_mut().images[self.selected_idx].1 = Affine::new(tra.try_into().unwrap());
         }
 
-        self.last_position = mouse_info.position;
+        self.last_position = position;
     }
 
     pub fn mouse_released(&mut self, v: &mut Editor, _i: &mut Interface, mouse_info: MouseInfo) {
@@ -67,4 +73,10 @@ impl ToolBehavior for MoveImage {
             }
         }
     }
+
+    fn draw(&mut self, v: &Editor, i: &Interface, canvas: &mut Canvas) {
+        if v.is_modifying() {
+            snap::draw_snap_indicator(i, canvas, &self.snap);
+        }
+    }
 }
@@ -1,6 +1,9 @@
 use crate::get_point_mut;
 
+use std::collections::HashSet;
+
 use super::prelude::*;
+use crate::user_interface::snap::{self, SnapQuery, SnapResult};
 use glifparser::glif::{mfek::contour::MFEKContourCommon};
 #[derive(Clone, Debug)]
 pub struct MovePoint {
@@ -11,6 +14,9 @@ pub struct MovePoint {
 
     // should we move all selected points?
     move_selected: bool,
+
+    // the last snap, kept around so we can draw it
+    snap: SnapResult,
 }
 
 impl MovePoint {
@@ -18,14 +24,22 @@ impl MovePoint {
         Self {
             mouse_info,
             move_selected,
+            snap: SnapResult::default(),
         }
     }
 
-    pub fn mouse_moved(&mut self, v: &mut Editor, _i: &mut Interface, mouse_info: MouseInfo) {
-        let x = mouse_info.position.0 as f32;
-        let y = mouse_info.position.1 as f32;
-
+    pub fn mouse_moved(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
         let (vci, vpi) = v.selected_point().unwrap();
+
+        let mut moving = HashSet::new();
+        moving.insert((vci, vpi));
+        if self.move_selected {
+            moving.extend(v.selected.iter().copied());
+        }
+        let query = SnapQuery::for_point(v, vci, vpi, moving);
+        self.snap = snap::snap(v, i, mouse_info.raw_position, &query);
+        let (x, y) = self.snap.position;
+
         if !v.is_modifying() {
             v.begin_modification("Move point.", false);
         }
@@ -120,6 +134,10 @@ impl ToolBehavior for MovePoint {
 
     // We draw a preview to show if the point will be merged with another when you let go of the mouse button.
     fn draw(&mut self, v: &Editor, i: &Interface, canvas: &mut Canvas) {
+        if v.is_modifying() {
+            snap::draw_snap_indicator(i, canvas, &self.snap);
+        }
+
         // This draws a preview to show if we're overlapping a point we can merge with or not.
         // Note that all tool draw events draw over the glyph view.
         if v.contour_idx.is_none() || v.point_idx.is_none() {
@@ -0,0 +1,145 @@
+use kurbo::Affine;
+
+use crate::editor::transform::{transform_point, BoundingBoxAnchor};
+use crate::get_point_mut;
+
+use super::prelude::*;
+use glifparser::glif::contour::MFEKContourCommon;
+
+// A selected point as it was when the drag started: (contour_idx, point_idx), position, handles.
+type PointSnapshot = (usize, usize, (f32, f32), Option<Handle>, Option<Handle>);
+
+/// Scales the selection by dragging one of the handles of its bounding box. Shift keeps the
+/// selection's proportions and Alt scales from the center instead of the opposite handle.
+#[derive(Clone, Debug)]
+pub struct ScaleSelection {
+    mouse_info: MouseInfo,
+    handle: BoundingBoxAnchor,
+
+    // bounding box anchor positions when the drag started, we always scale from these
+    handle_position: (f32, f32),
+    opposite_position: (f32, f32),
+    center_position: (f32, f32),
+
+    // we scale the original points every time the mouse moves so rounding errors don't accumulate
+    original: Vec<PointSnapshot>,
+}
+
+impl ScaleSelection {
+    pub fn new(v: &Editor, handle: BoundingBoxAnchor, mouse_info: MouseInfo) -> Self {
+        let bounding_box = v.build_selection_bounding_box();
+        let layer = v.get_active_layer_ref();
+        let original = v
+            .selected
+            .iter()
+            .map(|(ci, pi)| {
+                let point = get_point!(layer, *ci, *pi).unwrap();
+                (
+                    *ci,
+                    *pi,
+                    (point.x(), point.y()),
+                    point.get_handle(WhichHandle::A),
+                    point.get_handle(WhichHandle::B),
+                )
+            })
+            .collect();
+
+        Self {
+            mouse_info,
+            handle,
+            handle_position: handle.position(&bounding_box),
+            opposite_position: handle.opposite().position(&bounding_box),
+            center_position: BoundingBoxAnchor::Center.position(&bounding_box),
+            original,
+        }
+    }
+
+    fn scale_factors(&self, origin: (f32, f32), mouse_info: &MouseInfo) -> (f64, f64) {
+        let (fx, fy) = self.handle.factors();
+        let factor = |handle: f32, origin: f32, mouse: f32| {
+            let extent = handle - origin;
+            if extent.abs() < f32::EPSILON {
+                1.
+            } else {
+                ((mouse - origin) / extent) as f64
+            }
+        };
+
+        let mut sx = if fx != 0 {
+            factor(self.handle_position.0, origin.0, mouse_info.position.0)
+        } else {
+            1.
+        };
+        let mut sy = if fy != 0 {
+            factor(self.handle_position.1, origin.1, mouse_info.position.1)
+        } else {
+            1.
+        };
+
+        if mouse_info.modifiers.shift {
+            // Corners follow whichever axis moved further, edges scale the other axis along.
+            let s = match (fx, fy) {
+                (0, _) => sy,
+                (_, 0) => sx,
+                _ if sx.abs() > sy.abs() => sx,
+                _ => sy,
+            };
+            sx = s;
+            sy = s;
+        }
+
+        (sx, sy)
+    }
+
+    pub fn mouse_moved(&mut self, v: &mut Editor, _i: &mut Interface, mouse_info: MouseInfo) {
+        if !v.is_modifying() {
+            v.begin_modification("Scale selection.", false);
+        }
+
+        let origin = if mouse_info.modifiers.alt {
+            self.center_position
+        } else {
+            self.opposite_position
+        };
+        let (sx, sy) = self.scale_factors(origin, &mouse_info);
+        let origin = (origin.0 as f64, origin.1 as f64);
+        let affine = Affine::translate(origin)
+            * Affine::scale_non_uniform(sx, sy)
+            * Affine::translate((-origin.0, -origin.1));
+
+        let layer = v.get_active_layer_mut();
+        for (ci, pi, position, a, b) in &self.original {
+            let point = get_point_mut!(layer, *ci, *pi).unwrap();
+            point.set_position_no_handles(position.0, position.1);
+            if let Some(a) = a {
+                point.set_handle(WhichHandle::A, *a);
+            }
+            if let Some(b) = b {
+                point.set_handle(WhichHandle::B, *b);
+            }
+            transform_point(point, &affine);
+        }
+    }
+
+    pub fn mouse_released(&mut self, v: &mut Editor, _i: &mut Interface, mouse_info: MouseInfo) {
+        if mouse_info.button == self.mouse_info.button {
+            if v.is_modifying() {
+                v.end_modification();
+            }
+            v.pop_behavior();
+        }
+    }
+}
+
+#[rustfmt::skip]
+impl ToolBehavior for ScaleSelection {
+    fn event(&mut self, v: &mut Editor, i: &mut Interface, event: EditorEvent) {
+        if let EditorEvent::MouseEvent { mouse_info, event_type } = event {
+            match event_type {
+                MouseEventType::Released => self.mouse_released(v, i, mouse_info),
+                MouseEventType::Moved => self.mouse_moved(v, i, mouse_info),
+                _ => (),
+            }
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use super::super::prelude::*;
+use super::Corners;
+use crate::editor::corners::CornerStyle;
+use crate::user_interface::Interface;
+use egui::Ui;
+
+impl Corners {
+    pub fn tool_dialog(&mut self, v: &mut Editor, _i: &mut Interface, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            for style in CornerStyle::ALL {
+                ui.selectable_value(&mut self.params.style, style, style.label());
+            }
+        });
+
+        match self.params.style {
+            CornerStyle::Round => {
+                ui.label("Radius");
+                ui.add(egui::Slider::new(&mut self.params.radius, 1.0..=200.));
+                ui.checkbox(&mut self.live, "Keep as Contour Operation");
+            }
+            CornerStyle::Chamfer => {
+                ui.label("Size");
+                ui.add(egui::Slider::new(&mut self.params.radius, 1.0..=200.));
+            }
+            CornerStyle::InkTrap => {
+                ui.label("Depth");
+                ui.add(egui::Slider::new(&mut self.params.depth, 1.0..=100.));
+                ui.label("Width");
+                ui.add(egui::Slider::new(&mut self.params.width, 1.0..=100.));
+            }
+        }
+
+        ui.checkbox(&mut self.preview, "Preview");
+
+        if ui.button("Apply to selection").clicked() {
+            let targets: Vec<_> = v.selected.iter().copied().collect();
+            self.apply(v, &targets);
+        }
+    }
+}
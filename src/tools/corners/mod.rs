@@ -0,0 +1,119 @@
+mod dialog;
+
+use glifparser::glif::contour::MFEKContourCommon;
+use glifparser::outline::skia::ToSkiaPaths as _;
+
+use crate::editor::corners::{is_corner, CornerParams, CornerStyle};
+use crate::tool_behaviors::zoom_scroll::ZoomScroll;
+use crate::user_interface::Interface;
+
+use super::prelude::*;
+
+/// Rounds, chamfers or cuts ink traps into corners. Clicking a corner point treats it, and the
+/// dialog can treat every selected corner at once. The result is previewed before it's applied.
+/// Rounds can be kept live, as a rounded corners operation whose radii can be changed later.
+#[derive(Clone, Debug)]
+pub struct Corners {
+    params: CornerParams,
+    // the corner under the mouse, as (contour, point)
+    hovered: Option<(usize, usize)>,
+    preview: bool,
+    // whether rounds are stored as a contour operation rather than made out of the points
+    live: bool,
+}
+
+impl Tool for Corners {
+    #[rustfmt::skip]
+    fn event(&mut self, v: &mut Editor, i: &mut Interface, event: EditorEvent) {
+        match event {
+            EditorEvent::MouseEvent { mouse_info, event_type } => match event_type {
+                MouseEventType::Moved => self.mouse_moved(v, i, mouse_info),
+                MouseEventType::Pressed => self.mouse_pressed(v, mouse_info),
+                _ => (),
+            }
+            EditorEvent::ScrollEvent { .. } => ZoomScroll::default().event(v, i, event),
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, v: &Editor, i: &Interface, canvas: &mut Canvas) {
+        self.draw_preview(v, i, canvas);
+    }
+
+    fn dialog(&mut self, v: &mut Editor, i: &mut Interface, ui: &mut Ui) -> bool {
+        self.tool_dialog(v, i, ui);
+        true
+    }
+}
+
+impl Corners {
+    pub fn new() -> Self {
+        Self {
+            params: CornerParams::default(),
+            hovered: None,
+            preview: true,
+            live: false,
+        }
+    }
+
+    fn mouse_moved(&mut self, v: &Editor, i: &Interface, mouse_info: MouseInfo) {
+        self.hovered = match clicked_point_or_handle(v, i, mouse_info.raw_position, None) {
+            Some((ci, pi, WhichHandle::Neither)) => {
+                let contour = &v.get_active_layer_ref().outline[ci];
+                contour
+                    .cubic()
+                    .filter(|points| is_corner(points, pi, contour.is_open()))
+                    .map(|_| (ci, pi))
+            }
+            _ => None,
+        };
+    }
+
+    fn mouse_pressed(&mut self, v: &mut Editor, mouse_info: MouseInfo) {
+        if mouse_info.button != MouseButton::Left {
+            return;
+        }
+        if let Some(corner) = self.hovered.take() {
+            self.apply(v, &[corner]);
+        }
+    }
+
+    fn apply(&self, v: &mut Editor, targets: &[(usize, usize)]) {
+        if self.live && self.params.style == CornerStyle::Round {
+            v.set_corner_radii(targets, self.params.radius);
+        } else {
+            v.treat_corners(targets, &self.params);
+        }
+    }
+
+    /// The corners which would be treated right now: the hovered one, or else the selection.
+    fn targets(&self, v: &Editor) -> Vec<(usize, usize)> {
+        match self.hovered {
+            Some(corner) => vec![corner],
+            None => v.selected.iter().copied().collect(),
+        }
+    }
+
+    fn draw_preview(&self, v: &Editor, i: &Interface, canvas: &mut Canvas) {
+        if !self.preview || v.is_modifying() {
+            return;
+        }
+
+        let contours: Vec<_> = v
+            .build_corner_contours(&self.targets(v), &self.params)
+            .into_iter()
+            .map(|(_, contour)| contour)
+            .collect();
+        if contours.is_empty() {
+            return;
+        }
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_color(SELECTED_STROKE);
+        paint.set_stroke_width(OUTLINE_STROKE_THICKNESS * (1. / i.viewport.factor));
+
+        canvas.draw_path(&contours.to_skia_paths(None).combined(), &paint);
+    }
+}
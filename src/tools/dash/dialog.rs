@@ -1,6 +1,7 @@
 use super::super::prelude::*;
 use super::Dash;
 use crate::user_interface::Interface;
+use glifparser::glif::contour::MFEKContourCommon;
 use glifparser::glif::contour_operations::{ContourOperations, dash::DashCull};
 use egui::Ui;
 use skia_safe::{PaintCap, PaintJoin};
@@ -31,20 +32,47 @@ impl Dash {
                 .join(" ");
 
             ui.label("Dashes");
-            if ui.text_edit_singleline(&mut dashes).changed() && !dashes.as_str().is_empty() {
-                let new = dashes
-                    .as_str()
-                    .split(" ")
-                    .map(|s| s.parse::<f32>())
-                    .filter(|f| f.is_ok())
-                    .map(|o| o.unwrap())
-                    .collect::<Vec<_>>();
-
-                if new.len() > 1 && new.len() % 2 == 0 {
+            if ui.text_edit_singleline(&mut dashes).changed() {
+                if let Some(new) = parse_dashes(&dashes) {
                     data.dash_desc = new;
                 }
             }
-            
+
+            let original_extras = v.dash_extras(contour_idx);
+            let mut extras = original_extras.clone();
+
+            let period: f32 = data.dash_desc.iter().sum();
+            ui.label("Offset");
+            ui.add(egui::Slider::new(&mut extras.phase, 0.0..=period));
+
+            let segment = v.point_idx.filter(|pi| {
+                let contour = &v.get_active_layer_ref().outline[contour_idx];
+                contour.is_closed() || pi + 1 < contour.len()
+            });
+            if let Some(segment) = segment {
+                let mut overridden = extras.segment_dashes.contains_key(&segment);
+                ui.checkbox(&mut overridden, "Override Dashes on Segment");
+                if overridden {
+                    let pattern = extras
+                        .segment_dashes
+                        .entry(segment)
+                        .or_insert_with(|| data.dash_desc.clone());
+                    let mut dashes = pattern
+                        .iter()
+                        .map(|f| f.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    if ui.text_edit_singleline(&mut dashes).changed() {
+                        if let Some(new) = parse_dashes(&dashes) {
+                            *pattern = new;
+                        }
+                    }
+                } else {
+                    extras.segment_dashes.remove(&segment);
+                }
+            }
+            ui.label("Drag the handles on the path to change lengths, or with shift the offset.");
+
             let mut cull = data.cull.is_some();
             ui.checkbox(&mut cull, "Cull");
 
@@ -101,11 +129,28 @@ impl Dash {
                 data.cull = None;
             }
 
-            if data != original_data {
+            if data != original_data || extras != original_extras {
                 v.begin_modification("Dash dialog modification.", true);
-                v.get_active_layer_mut().outline[contour_idx].set_operation(Some(ContourOperations::DashAlongPath { data }));
+                v.set_dash_extras(contour_idx, extras);
+                v.set_contour_operation(contour_idx, Some(ContourOperations::DashAlongPath { data }));
                 v.end_modification();
             }
         }
     }
 }
+
+/// Reads a dash pattern typed as lengths separated by spaces. Patterns need a gap for every dash.
+fn parse_dashes(dashes: &str) -> Option<Vec<f32>> {
+    let new = dashes
+        .split(" ")
+        .map(|s| s.parse::<f32>())
+        .filter(|f| f.is_ok())
+        .map(|o| o.unwrap())
+        .collect::<Vec<_>>();
+
+    if new.len() > 1 && new.len() % 2 == 0 {
+        Some(new)
+    } else {
+        None
+    }
+}
@@ -6,7 +6,8 @@ use skia_safe::{PaintCap, PaintJoin};
 
 use super::prelude::*;
 use crate::editor::Editor;
-use crate::tool_behaviors::zoom_scroll::ZoomScroll;
+use crate::tool_behaviors::{move_dash_handle::MoveDashHandle, zoom_scroll::ZoomScroll};
+use MFEKmath::Vector;
 
 #[derive(Clone, Debug, Default)]
 pub struct Dash {}
@@ -24,6 +25,10 @@ impl Tool for Dash {
         }
     }
 
+    fn draw(&mut self, v: &Editor, i: &Interface, canvas: &mut Canvas) {
+        self.draw_boundaries(v, i, canvas);
+    }
+
     fn dialog(&mut self, v: &mut Editor, i: &mut Interface, ui: &mut Ui) -> bool {
         let show_dialog = match v.contour_idx {
             Some(ci) => match v.get_active_layer_ref().outline[ci].operation() {
@@ -47,7 +52,41 @@ impl Dash {
         Self::default()
     }
 
+    /// The dash handle under the mouse on the selected contour, as the first segment of its run
+    /// and the index of its interval in the run's pattern.
+    fn clicked_dash_handle(
+        v: &Editor,
+        i: &Interface,
+        mouse_info: MouseInfo,
+    ) -> Option<(usize, usize)> {
+        let ci = v.contour_idx?;
+        let phase = v.dash_extras(ci).phase;
+        let factor = i.viewport.factor;
+        let size = ((POINT_RADIUS * 2.5) + (POINT_STROKE_THICKNESS * 2.)) * (1. / factor);
+        let (x, y) = mouse_info.raw_position;
+        let mouse = Vector::from_components(x as f64, y as f64);
+
+        v.dash_runs(ci).into_iter().find_map(|run| {
+            run.handles(phase)
+                .into_iter()
+                .find(|(at, _)| (run.point_at(*at) - mouse).magnitude() <= size as f64 / 2.)
+                .map(|(_, interval)| (run.first_segment, interval))
+        })
+    }
+
     fn mouse_pressed(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
+        // Dragging a dash handle changes that dash or gap's length, or with shift held the phase.
+        if let Some((first_segment, interval)) = Self::clicked_dash_handle(v, i, mouse_info) {
+            v.set_behavior(Box::new(MoveDashHandle::new(
+                v.contour_idx.unwrap(),
+                first_segment,
+                interval,
+                mouse_info.modifiers.shift,
+                mouse_info,
+            )));
+            return;
+        }
+
         if let Some((ci, pi, _wh)) = clicked_point_or_handle(v, i, mouse_info.raw_position, None) {
             let layer_op = v.get_active_layer_ref().outline[ci].operation().clone();
             v.contour_idx = Some(ci);
@@ -57,19 +96,63 @@ impl Dash {
                 Some(ContourOperations::DashAlongPath { .. }) => (),
                 None | Some(_) => {
                     v.begin_modification("Added dash contour.", false);
-                    v.get_active_layer_mut().outline[ci].set_operation(Some(ContourOperations::DashAlongPath {
-                        data: DashContour {
-                            stroke_width: 10.,
-                            cull: None,
-                            dash_desc: vec![10., 10.],
-                            include_last_path: false,
-                            paint_cap: PaintCap::Butt as u8,
-                            paint_join: PaintJoin::Miter as u8,
-                        },
+                    v.set_contour_operation(ci, Some(ContourOperations::DashAlongPath {
+                        data: new_dash_contour(),
                     }));
                     v.end_modification();
                 }
             }
         }
     }
+
+    /// Marks where every dash starts and ends across the selected contour, and draws the handles
+    /// which change the dash pattern's lengths.
+    fn draw_boundaries(&self, v: &Editor, i: &Interface, canvas: &mut Canvas) {
+        let ci = match v.contour_idx {
+            Some(ci) => ci,
+            None => return,
+        };
+        let width = match v.get_active_layer_ref().outline[ci].operation() {
+            Some(ContourOperations::DashAlongPath { data }) => data.stroke_width as f64,
+            _ => return,
+        };
+        let factor = i.viewport.factor;
+        let phase = v.dash_extras(ci).phase;
+        let half = width / 2. + (POINT_RADIUS / factor) as f64;
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_stroke_width(HANDLEBAR_THICKNESS * (1. / factor));
+
+        for run in v.dash_runs(ci) {
+            paint.set_color(RIB_STROKE);
+            let mut path = SkPath::new();
+            for (at, _) in run.boundaries(phase) {
+                let (point, tangent) = (run.point_at(at), run.tangent_at(at));
+                let normal = Vector::from_components(-tangent.y * half, tangent.x * half);
+                path.move_to(((point.x + normal.x) as f32, (point.y + normal.y) as f32));
+                path.line_to(((point.x - normal.x) as f32, (point.y - normal.y) as f32));
+            }
+            canvas.draw_path(&path, &paint);
+
+            paint.set_color(SELECTED_STROKE);
+            for (at, _) in run.handles(phase) {
+                let point = run.point_at(at);
+                canvas.draw_circle((point.x as f32, point.y as f32), POINT_RADIUS / factor, &paint);
+            }
+        }
+    }
+}
+
+/// The dash a contour is given when it's first dashed.
+pub fn new_dash_contour() -> DashContour {
+    DashContour {
+        stroke_width: 10.,
+        cull: None,
+        dash_desc: vec![10., 10.],
+        include_last_path: false,
+        paint_cap: PaintCap::Butt as u8,
+        paint_join: PaintJoin::Miter as u8,
+    }
 }
@@ -0,0 +1,56 @@
+use super::super::prelude::*;
+use super::ExpandStroke;
+use crate::user_interface::Interface;
+use egui::Ui;
+use skia_safe::{PaintCap, PaintJoin};
+
+impl ExpandStroke {
+    pub fn tool_dialog(&mut self, v: &mut Editor, _i: &mut Interface, ui: &mut Ui) {
+        let contour_idx = match v.contour_idx {
+            Some(ci) => ci,
+            None => {
+                ui.label("No selection!");
+                return;
+            }
+        };
+        let original = match Self::stroke_of(v, contour_idx) {
+            Some(stroke) => stroke,
+            None => return,
+        };
+        let mut stroke = original.clone();
+
+        ui.label("Stroke Width");
+        ui.add(egui::Slider::new(&mut stroke.stroke_width, 1.0..=200.));
+
+        egui::ComboBox::new("expand_stroke_joins", "Joins")
+            .selected_text(format!("{:?}", stroke.paint_join))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut stroke.paint_join, PaintJoin::Miter, "Miter");
+                ui.selectable_value(&mut stroke.paint_join, PaintJoin::Bevel, "Bevel");
+                ui.selectable_value(&mut stroke.paint_join, PaintJoin::Round, "Round");
+            });
+
+        if stroke.paint_join == PaintJoin::Miter {
+            ui.label("Miter Limit");
+            ui.add(egui::Slider::new(&mut stroke.miter_limit, 1.0..=20.));
+        }
+
+        egui::ComboBox::new("expand_stroke_caps", "Caps")
+            .selected_text(format!("{:?}", stroke.paint_cap))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut stroke.paint_cap, PaintCap::Butt, "Butt");
+                ui.selectable_value(&mut stroke.paint_cap, PaintCap::Square, "Square");
+                ui.selectable_value(&mut stroke.paint_cap, PaintCap::Round, "Round");
+            });
+
+        if stroke != original {
+            Self::set_stroke(
+                v,
+                contour_idx,
+                stroke,
+                "Expand stroke dialog modification.",
+                true,
+            );
+        }
+    }
+}
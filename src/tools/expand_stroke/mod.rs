@@ -0,0 +1,87 @@
+mod dialog;
+
+use crate::contour_operations::expandstroke::ExpandStrokeContour;
+use crate::editor::contour_data::EditorOperation;
+use crate::tool_behaviors::zoom_scroll::ZoomScroll;
+
+use super::prelude::*;
+
+/// Strokes contours with a constant width. Clicking a contour gives it an expand stroke operation,
+/// whose width, joins and caps the dialog changes.
+#[derive(Clone, Debug, Default)]
+pub struct ExpandStroke {}
+
+impl Tool for ExpandStroke {
+    #[rustfmt::skip]
+    fn event(&mut self, v: &mut Editor, i: &mut Interface, event: EditorEvent) {
+        match event {
+            EditorEvent::MouseEvent { mouse_info, event_type } => match event_type {
+                MouseEventType::Pressed => self.mouse_pressed(v, i, mouse_info),
+                _ => (),
+            }
+            EditorEvent::ScrollEvent { .. } => ZoomScroll::default().event(v, i, event),
+            _ => {}
+        }
+    }
+
+    fn dialog(&mut self, v: &mut Editor, i: &mut Interface, ui: &mut Ui) -> bool {
+        if v.contour_idx.and_then(|ci| Self::stroke_of(v, ci)).is_some() {
+            self.tool_dialog(v, i, ui);
+            return true;
+        }
+
+        false
+    }
+}
+
+impl ExpandStroke {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The expand stroke operation of a contour on the active layer, if it has one.
+    fn stroke_of(v: &Editor, contour_idx: usize) -> Option<ExpandStrokeContour> {
+        match v.contour_data(contour_idx)?.operation.as_ref()? {
+            EditorOperation::ExpandStroke(data) => Some(data.clone()),
+            _ => None,
+        }
+    }
+
+    /// Gives a contour on the active layer an expand stroke operation, replacing any other it had.
+    fn set_stroke(
+        v: &mut Editor,
+        contour_idx: usize,
+        stroke: ExpandStrokeContour,
+        description: &str,
+        fold: bool,
+    ) {
+        let mut data = v.contour_data(contour_idx).cloned().unwrap_or_default();
+        data.operation = Some(EditorOperation::ExpandStroke(stroke));
+
+        v.begin_modification(description, fold);
+        v.set_contour_data(contour_idx, data);
+        v.get_active_layer_mut().outline[contour_idx].set_operation(None);
+        v.end_modification();
+    }
+
+    fn mouse_pressed(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
+        if mouse_info.button != MouseButton::Left {
+            return;
+        }
+
+        if let Some((ci, pi, _wh)) = clicked_point_or_handle(v, i, mouse_info.raw_position, None) {
+            v.contour_idx = Some(ci);
+            v.point_idx = Some(pi);
+
+            if Self::stroke_of(v, ci).is_none() {
+                Self::set_stroke(
+                    v,
+                    ci,
+                    ExpandStrokeContour::default(),
+                    "Added expand stroke contour.",
+                    false,
+                );
+            }
+        }
+    }
+}
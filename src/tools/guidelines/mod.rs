@@ -196,6 +196,7 @@ impl Guidelines {
                     selected_idx,
                     global: guidelines[selected_idx].1,
                     mouse_info,
+                    ..MoveGuideline::default()
                 });
                 v.set_behavior(behavior);
             }
@@ -0,0 +1,75 @@
+use glifrenderer::constants::OUTLINE_STROKE_THICKNESS;
+use skia_safe::{Canvas, Paint, Path};
+
+use crate::constants::KNIFE_STROKE;
+use crate::editor::Editor;
+use crate::user_interface::{snap, Interface};
+
+use super::prelude::*;
+
+/// Drag a line across contours to cut them where it crosses. Holding Alt when releasing cuts closed
+/// contours into closed pieces instead of opening them, and Shift constrains the line to 45°.
+#[derive(Clone, Debug)]
+pub struct Knife {
+    cut_from: Option<(f32, f32)>,
+}
+
+impl Tool for Knife {
+    #[rustfmt::skip]
+    fn event(&mut self, v: &mut Editor, _i: &mut Interface, event: EditorEvent) {
+        if let EditorEvent::MouseEvent { mouse_info, event_type } = event {
+            match event_type {
+                MouseEventType::Pressed => self.mouse_pressed(v, mouse_info),
+                MouseEventType::Released => self.mouse_released(v, mouse_info),
+                _ => (),
+            }
+        }
+    }
+
+    fn draw(&mut self, _v: &Editor, i: &Interface, canvas: &mut Canvas) {
+        self.draw_line(i, canvas);
+    }
+}
+
+impl Knife {
+    pub fn new() -> Self {
+        Self { cut_from: None }
+    }
+
+    fn cut_to(from: (f32, f32), mouse_info: &MouseInfo) -> (f32, f32) {
+        if mouse_info.modifiers.shift {
+            snap::constrain_angle(from, mouse_info.position)
+        } else {
+            mouse_info.position
+        }
+    }
+
+    fn mouse_pressed(&mut self, _v: &Editor, mouse_info: MouseInfo) {
+        if mouse_info.button == MouseButton::Left {
+            self.cut_from = Some(mouse_info.position);
+        }
+    }
+
+    fn mouse_released(&mut self, v: &mut Editor, mouse_info: MouseInfo) {
+        if let Some(from) = self.cut_from.take() {
+            let to = Self::cut_to(from, &mouse_info);
+            v.knife(from, to, mouse_info.modifiers.alt);
+        }
+    }
+
+    fn draw_line(&self, i: &Interface, canvas: &mut Canvas) {
+        if let Some(from) = self.cut_from {
+            let to = Self::cut_to(from, &i.mouse_info);
+            let mut path = Path::new();
+            path.move_to(from);
+            path.line_to(to);
+
+            let mut paint = Paint::default();
+            paint.set_anti_alias(true);
+            paint.set_color(KNIFE_STROKE);
+            paint.set_style(skia_safe::PaintStyle::Stroke);
+            paint.set_stroke_width(OUTLINE_STROKE_THICKNESS * (1. / i.viewport.factor));
+            canvas.draw_path(&path, &paint);
+        }
+    }
+}
@@ -3,8 +3,9 @@ pub_mod!("src/tools");
 
 use self::prelude::*;
 use self::{
-    anchors::Anchors, dash::Dash, guidelines::Guidelines, image::Image, measure::Measure, pan::Pan,
-    pap::PAP, pen::Pen, select::Select, shapes::Shapes, vws::VWS, zoom::Zoom,
+    anchors::Anchors, corners::Corners, dash::Dash, expand_stroke::ExpandStroke,
+    guidelines::Guidelines, image::Image, knife::Knife, measure::Measure, nib::Nib, pan::Pan,
+    pap::PAP, pen::Pen, pencil::Pencil, select::Select, shapes::Shapes, vws::VWS, zoom::Zoom,
 };
 
 use dyn_clone::DynClone;
@@ -52,6 +53,11 @@ pub enum ToolEnum {
     Shapes,
     Image,
     Guidelines,
+    Knife,
+    Pencil,
+    Corners,
+    Nib,
+    ExpandStroke,
 }
 
 impl Display for ToolEnum {
@@ -80,5 +86,10 @@ pub fn tool_enum_to_tool(tool: ToolEnum) -> Box<dyn Tool> {
         ToolEnum::Image => Box::new(Image::new()),
         ToolEnum::PAP => Box::new(PAP::new()),
         ToolEnum::Guidelines => Box::new(Guidelines::new()),
+        ToolEnum::Knife => Box::new(Knife::new()),
+        ToolEnum::Pencil => Box::new(Pencil::new()),
+        ToolEnum::Corners => Box::new(Corners::new()),
+        ToolEnum::Nib => Box::new(Nib::new()),
+        ToolEnum::ExpandStroke => Box::new(ExpandStroke::new()),
     }
 }
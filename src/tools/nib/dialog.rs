@@ -0,0 +1,95 @@
+use super::super::prelude::*;
+use super::Nib;
+use crate::contour_operations::nib::{shape_from_contour, NibShape};
+use crate::user_interface::Interface;
+use egui::Ui;
+
+impl Nib {
+    pub fn tool_dialog(&mut self, v: &mut Editor, _i: &mut Interface, ui: &mut Ui) {
+        let contour_idx = match v.contour_idx {
+            Some(ci) => ci,
+            None => {
+                ui.label("No selection!");
+                return;
+            }
+        };
+        let original = match Self::nib_of(v, contour_idx) {
+            Some(nib) => nib,
+            None => return,
+        };
+        let mut nib = original.clone();
+
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut nib.shape, NibShape::Ellipse, "Ellipse");
+            ui.selectable_value(&mut nib.shape, NibShape::Rectangle, "Rectangle");
+            if let NibShape::Contour(_) = nib.shape {
+                let _ = ui.selectable_label(true, "Contour");
+            }
+        });
+
+        self.source_layer = self.source_layer.min(v.get_layer_count() - 1);
+        ui.horizontal(|ui| {
+            let name = v.with_glyph(|glif| glif.layers[self.source_layer].name.clone());
+            egui::ComboBox::new("nib_source_layer", "")
+                .selected_text(name)
+                .show_ui(ui, |ui| {
+                    for layer in 0..v.get_layer_count() {
+                        let name = v.with_glyph(|glif| glif.layers[layer].name.clone());
+                        ui.selectable_value(&mut self.source_layer, layer, name);
+                    }
+                });
+            if ui.button("Use Layer's Contour").clicked() {
+                // The first contour that makes a nib, or else why the last one didn't.
+                let shape = v.with_glyph(|glif| {
+                    let mut shape = Err("The layer has no contours.".to_string());
+                    for contour in &glif.layers[self.source_layer].outline {
+                        shape = shape_from_contour(contour);
+                        if shape.is_ok() {
+                            break;
+                        }
+                    }
+                    shape
+                });
+                match shape {
+                    Ok(shape) => nib.shape = shape,
+                    Err(e) => gui::error!("Couldn't use the layer's contour as a nib: {}", e),
+                }
+            }
+        });
+
+        ui.label("Width");
+        ui.add(egui::Slider::new(&mut nib.width, 1.0..=200.));
+        ui.label("Height");
+        ui.add(egui::Slider::new(&mut nib.height, 1.0..=200.));
+        ui.label("Angle");
+        ui.add(egui::Slider::new(&mut nib.angle, -180.0..=180.));
+
+        if let Some(handle) = v.point_idx.and_then(|pi| nib.handles.get_mut(pi)) {
+            ui.separator();
+            ui.label("Selected Point");
+
+            let mut angle = handle.angle.is_some();
+            ui.checkbox(&mut angle, "Override Angle");
+            if angle {
+                let value = handle.angle.get_or_insert(nib.angle);
+                ui.add(egui::Slider::new(value, -180.0..=180.));
+            } else {
+                handle.angle = None;
+            }
+
+            let mut scale = handle.scale.is_some();
+            ui.checkbox(&mut scale, "Override Size");
+            if scale {
+                let value = handle.scale.get_or_insert(1.);
+                ui.add(egui::Slider::new(value, 0.0..=4.));
+            } else {
+                handle.scale = None;
+            }
+        }
+
+        if nib != original {
+            self.nib = nib.clone();
+            Self::set_nib(v, contour_idx, nib, "Nib dialog modification.", true);
+        }
+    }
+}
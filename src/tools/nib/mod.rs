@@ -0,0 +1,147 @@
+mod dialog;
+
+use glifparser::glif::{contour::MFEKContourCommon, point::MFEKPointCommon};
+
+use crate::contour_operations::nib::NibContour;
+use crate::editor::contour_data::EditorOperation;
+use crate::tool_behaviors::zoom_scroll::ZoomScroll;
+use crate::user_interface::Interface;
+
+use super::prelude::*;
+
+/// Sweeps a calligraphic nib along contours. Clicking a point gives its contour a nib operation,
+/// and picks the point whose nib angle and size can be overridden in the dialog. The nib is shown
+/// under the mouse and on the picked point; the sweep itself is in the preview.
+#[derive(Clone, Debug)]
+pub struct Nib {
+    // the nib given to contours which don't have one yet, as last edited
+    nib: NibContour,
+    // the layer a contour nib is taken from
+    source_layer: usize,
+}
+
+impl Tool for Nib {
+    #[rustfmt::skip]
+    fn event(&mut self, v: &mut Editor, i: &mut Interface, event: EditorEvent) {
+        match event {
+            EditorEvent::MouseEvent { mouse_info, event_type } => match event_type {
+                MouseEventType::Pressed => self.mouse_pressed(v, i, mouse_info),
+                _ => (),
+            }
+            EditorEvent::ScrollEvent { .. } => ZoomScroll::default().event(v, i, event),
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, v: &Editor, i: &Interface, canvas: &mut Canvas) {
+        self.draw_nib(v, i, canvas);
+    }
+
+    fn dialog(&mut self, v: &mut Editor, i: &mut Interface, ui: &mut Ui) -> bool {
+        if v.contour_idx.and_then(|ci| Self::nib_of(v, ci)).is_some() {
+            self.tool_dialog(v, i, ui);
+            return true;
+        }
+
+        false
+    }
+}
+
+impl Nib {
+    pub fn new() -> Self {
+        Self {
+            nib: NibContour::default(),
+            source_layer: 0,
+        }
+    }
+
+    /// The nib operation of a contour on the active layer, if it has one.
+    fn nib_of(v: &Editor, contour_idx: usize) -> Option<NibContour> {
+        match v.contour_data(contour_idx)?.operation.as_ref()? {
+            EditorOperation::Nib(data) => Some(data.clone()),
+            _ => None,
+        }
+    }
+
+    /// Gives a contour on the active layer a nib operation, replacing any other it had, with room
+    /// for an override on each of its points.
+    fn set_nib(
+        v: &mut Editor,
+        contour_idx: usize,
+        mut nib: NibContour,
+        description: &str,
+        fold: bool,
+    ) {
+        let len = v.get_active_layer_ref().outline[contour_idx].len();
+        nib.handles.resize(len, Default::default());
+        let mut data = v.contour_data(contour_idx).cloned().unwrap_or_default();
+        data.operation = Some(EditorOperation::Nib(nib));
+
+        v.begin_modification(description, fold);
+        v.set_contour_data(contour_idx, data);
+        v.get_active_layer_mut().outline[contour_idx].set_operation(None);
+        v.end_modification();
+    }
+
+    fn mouse_pressed(&mut self, v: &mut Editor, i: &Interface, mouse_info: MouseInfo) {
+        if mouse_info.button != MouseButton::Left {
+            return;
+        }
+
+        if let Some((ci, pi, _)) = clicked_point_or_handle(v, i, mouse_info.raw_position, None) {
+            v.contour_idx = Some(ci);
+            v.point_idx = Some(pi);
+            v.selected.clear();
+            v.selected.insert((ci, pi));
+
+            if Self::nib_of(v, ci).is_none() {
+                let mut nib = self.nib.clone();
+                nib.handles.clear();
+                Self::set_nib(v, ci, nib, "Added nib contour.", false);
+            }
+        }
+    }
+
+    fn draw_nib(&self, v: &Editor, i: &Interface, canvas: &mut Canvas) {
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_stroke_width(OUTLINE_STROKE_THICKNESS * (1. / i.viewport.factor));
+
+        let draw_polygon = |canvas: &mut Canvas, polygon: Vec<(f64, f64)>, paint: &Paint| {
+            let points: Vec<SkPoint> = polygon
+                .into_iter()
+                .map(|(x, y)| SkPoint::new(x as f32, y as f32))
+                .collect();
+            let mut path = Path::new();
+            path.add_poly(&points, true);
+            canvas.draw_path(&path, paint);
+        };
+
+        let selected = v
+            .contour_idx
+            .filter(|_| !v.is_modifying())
+            .and_then(|ci| Some((ci, Self::nib_of(v, ci)?)));
+
+        paint.set_color(OUTLINE_STROKE);
+        let nib = selected.as_ref().map(|(_, nib)| nib).unwrap_or(&self.nib);
+        let mouse = i.mouse_info.position;
+        let polygon = nib.polygon_at((mouse.0 as f64, mouse.1 as f64), nib.angle as f64, 1.);
+        draw_polygon(canvas, polygon, &paint);
+
+        let (ci, nib) = match selected {
+            Some(selected) => selected,
+            None => return,
+        };
+        let contour = &v.get_active_layer_ref().outline[ci];
+
+        paint.set_color(SELECTED_STROKE);
+        if let Some((pi, point)) = v
+            .point_idx
+            .and_then(|pi| Some((pi, contour.get_point(pi)?)))
+        {
+            let polygon = nib.polygon_at_point((point.x() as f64, point.y() as f64), pi);
+            draw_polygon(canvas, polygon, &paint);
+        }
+    }
+}
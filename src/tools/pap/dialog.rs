@@ -1,7 +1,9 @@
 use super::super::prelude::*;
-use super::PAP;
-use crate::user_interface::{Interface, gui::windows::egui_parsed_textfield};
-use glifparser::glif::contour_operations::{pap::{PatternStretch, PatternCopies, PatternSubdivide, PAPContour}, ContourOperations};
+use super::{new_pap_contour, PatternSource, PAP};
+use crate::user_interface::{Interface, gui, gui::windows::egui_parsed_textfield};
+use glifparser::glif::MFEKOutline;
+use glifparser::MFEKPointData;
+use glifparser::glif::contour_operations::{pap::{PatternStretch, PatternCopies, PatternSubdivide}, ContourOperations};
 use egui::Ui;
 
 impl PAP {
@@ -78,60 +80,41 @@ impl PAP {
                 .text("Prevent Overdraw")
             );
 
-            if data != original_data {
+            let mut replaced_source = None;
+            ui.collapsing("Replace Pattern", |ui| {
+                self.pattern_source_ui(v, ui);
+                if ui.add_enabled(self.pattern_ready(), egui::Button::new("Replace")).clicked() {
+                    if let Some((pattern, source)) = self.take_pattern(v) {
+                        data.pattern = pattern;
+                        replaced_source = Some(source);
+                    }
+                }
+            });
+
+            if data != original_data || replaced_source.is_some() {
                 v.begin_modification("Modified PAP.", true);
-                *v.get_active_layer_mut().outline[contour_idx].operation_mut() = Some(ContourOperations::PatternAlongPath { data: data });
+                if let Some(source) = replaced_source {
+                    Self::set_pattern_source(v, contour_idx, source);
+                }
+                v.set_contour_operation(contour_idx, Some(ContourOperations::PatternAlongPath { data: data }));
                 v.end_modification();
             }
         } else if v.selected_point().is_some() {
-            let none_string = "None".to_string();
-            let selected_string = if let Some(layer) = self.pattern_layer {
-                if let Some(l) = v.with_glyph(|glif| glif.layers.get(layer).cloned()) {
-                    l.name.clone()
-                } else if layer == v.get_active_layer() {
-                    self.pattern_layer = None;
-                    none_string
-                } else {
-                    self.pattern_layer = None;
-                    none_string
-                }
-            } else { none_string };
-
-            egui::ComboBox::new("pattern", "Pattern Layer")
-                .selected_text(selected_string.as_str())
-                .show_ui(ui, |ui| {
-                    for i in 1..v.get_layer_count() {
-                        let layer_name = v.with_glyph(|glif| glif.layers[i].name.clone());
-                        ui.selectable_value(&mut self.pattern_layer, Some(i), layer_name.as_str());
-                    }
-                });
-
+            self.pattern_source_ui(v, ui);
 
             let contour_idx = v.contour_idx.expect("Checked in if statement above.");
-            let enabled = self.pattern_layer.is_some();
+            let enabled = self.pattern_ready();
             if ui.add_enabled(enabled, egui::Button::new("Create Pattern Along Path.")).clicked() {
-                let pattern = v.with_glyph(|glif| glif.layers[self.pattern_layer.unwrap()].clone()).outline;
+                let (pattern, source) = match self.take_pattern(v) {
+                    Some(taken) => taken,
+                    None => return,
+                };
                 v.begin_modification("Added PAP contour.", true);
-                v.get_active_layer_mut().outline[contour_idx].set_operation(
+                Self::set_pattern_source(v, contour_idx, source);
+                v.set_contour_operation(
+                    contour_idx,
                     Some(ContourOperations::PatternAlongPath {
-                        // TODO: Default() implementation for many of our structs.
-                        data: PAPContour {
-                            pattern: pattern,
-                            copies: PatternCopies::Repeated,
-                            subdivide: PatternSubdivide::Off,
-                            is_vertical: false,
-                            stretch: PatternStretch::On,
-                            spacing: 4.,
-                            simplify: false,
-                            normal_offset: 0.,
-                            tangent_offset: 0.,
-                            pattern_scale: (1., 1.),
-                            center_pattern: true,
-                            prevent_overdraw: 0.,
-                            two_pass_culling: false,
-                            reverse_path: false,
-                            reverse_culling: false,
-                        },
+                        data: new_pap_contour(pattern),
                     })
                 );
                 v.end_modification();
@@ -140,4 +123,90 @@ impl PAP {
             ui.label("No selection!");
         }
     }
+
+    fn pattern_source_ui(&mut self, v: &mut Editor, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.pattern_source, PatternSource::Layer, "Layer");
+            ui.radio_value(&mut self.pattern_source, PatternSource::Glyph, "Glyph");
+            ui.radio_value(&mut self.pattern_source, PatternSource::Clipboard, "Clipboard");
+        });
+
+        match self.pattern_source {
+            PatternSource::Layer => {
+                let none_string = "None".to_string();
+                let selected_string = if let Some(layer) = self.pattern_layer {
+                    if let Some(l) = v.with_glyph(|glif| glif.layers.get(layer).cloned()) {
+                        l.name.clone()
+                    } else if layer == v.get_active_layer() {
+                        self.pattern_layer = None;
+                        none_string
+                    } else {
+                        self.pattern_layer = None;
+                        none_string
+                    }
+                } else { none_string };
+
+                egui::ComboBox::new("pattern", "Pattern Layer")
+                    .selected_text(selected_string.as_str())
+                    .show_ui(ui, |ui| {
+                        for i in 1..v.get_layer_count() {
+                            let layer_name = v.with_glyph(|glif| glif.layers[i].name.clone());
+                            ui.selectable_value(&mut self.pattern_layer, Some(i), layer_name.as_str());
+                        }
+                    });
+            }
+            PatternSource::Glyph => {
+                ui.horizontal(|ui| {
+                    ui.label("Glyph name:");
+                    ui.text_edit_singleline(&mut self.pattern_glyph);
+                });
+            }
+            PatternSource::Clipboard => {
+                ui.label("Uses the layer on the clipboard.");
+            }
+        }
+    }
+
+    /// Remembers which glyph, if any, a contour's pattern was taken from. Must be called during a
+    /// modification.
+    fn set_pattern_source(v: &mut Editor, contour_idx: usize, source: Option<String>) {
+        let mut data = v.contour_data(contour_idx).cloned().unwrap_or_default();
+        data.pattern_source = source;
+        v.set_contour_data(contour_idx, data);
+    }
+
+    fn pattern_ready(&self) -> bool {
+        match self.pattern_source {
+            PatternSource::Layer => self.pattern_layer.is_some(),
+            PatternSource::Glyph => !self.pattern_glyph.is_empty(),
+            PatternSource::Clipboard => true,
+        }
+    }
+
+    /// Takes the pattern from the chosen source, with the name of the glyph it came from if it's
+    /// another glyph's, so it can be refreshed when that glyph changes.
+    fn take_pattern(
+        &mut self,
+        v: &mut Editor,
+    ) -> Option<(MFEKOutline<MFEKPointData>, Option<String>)> {
+        match self.pattern_source {
+            PatternSource::Layer => self
+                .pattern_layer
+                .map(|layer| (v.with_glyph(|glif| glif.layers[layer].outline.clone()), None)),
+            PatternSource::Glyph => match v.load_pattern_glyph(&self.pattern_glyph) {
+                Ok(pattern) => Some((pattern, Some(self.pattern_glyph.clone()))),
+                Err(e) => {
+                    gui::error!("Couldn't use glyph {} as a pattern: {}", self.pattern_glyph, e);
+                    None
+                }
+            },
+            PatternSource::Clipboard => {
+                let pattern = v.clipboard_layer().map(|layer| (layer.outline, None));
+                if pattern.is_none() {
+                    gui::error!("The clipboard doesn't hold a layer to use as a pattern.");
+                }
+                pattern
+            }
+        }
+    }
 }
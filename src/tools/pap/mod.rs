@@ -2,13 +2,29 @@ mod dialog;
 
 use std::collections::HashMap;
 
+use glifparser::glif::contour_operations::pap::{
+    PAPContour, PatternCopies, PatternStretch, PatternSubdivide,
+};
+use glifparser::glif::MFEKOutline;
+use glifparser::MFEKPointData;
+
 use super::prelude::*;
 use crate::tool_behaviors::zoom_scroll::ZoomScroll;
 use crate::editor::Editor;
 
+/// Where a new pattern is taken from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PatternSource {
+    Layer,
+    Glyph,
+    Clipboard,
+}
+
 #[derive(Clone, Debug)]
 pub struct PAP {
+    pattern_source: PatternSource,
     pattern_layer: Option<usize>,
+    pattern_glyph: String,
     edit_buf: HashMap<String, String>
 }
 
@@ -39,7 +55,31 @@ impl PAP {
     pub fn new() -> Self {
         Self {
             edit_buf: HashMap::new(),
+            pattern_source: PatternSource::Layer,
             pattern_layer: None,
+            pattern_glyph: String::new(),
         }
     }
 }
+
+/// The pattern along path a contour is given when it's first made one, repeating `pattern`.
+pub fn new_pap_contour(pattern: MFEKOutline<MFEKPointData>) -> PAPContour {
+    // TODO: Default() implementation for many of our structs.
+    PAPContour {
+        pattern,
+        copies: PatternCopies::Repeated,
+        subdivide: PatternSubdivide::Off,
+        is_vertical: false,
+        stretch: PatternStretch::On,
+        spacing: 4.,
+        simplify: false,
+        normal_offset: 0.,
+        tangent_offset: 0.,
+        pattern_scale: (1., 1.),
+        center_pattern: true,
+        prevent_overdraw: 0.,
+        two_pass_culling: false,
+        reverse_path: false,
+        reverse_culling: false,
+    }
+}
@@ -1,30 +1,33 @@
 mod modes;
 
-use self::modes::PenMode;
+use self::modes::{PenMode, PenModeType};
 use self::modes::cubic::CubicMode;
 use self::modes::hyper::HyperMode;
 use self::modes::quad::QuadMode;
+use self::modes::spiro::SpiroMode;
 
 use super::prelude::*;
 
 use crate::constants::FONT_SCALE_FACTOR;
 use crate::is_contour_open;
 use crate::tool_behaviors::{move_handle::MoveHandle, pan::PanBehavior, zoom_scroll::ZoomScroll};
-use crate::user_interface::{Interface, gui::build_icon_button as build_button, gui::icons};
+use crate::user_interface::{Interface, gui::build_icon_button as build_button, gui::icons, snap};
 use egui::{Align2, Color32};
-use glifparser::glif::inner::MFEKContourInnerType;
+use crate::editor::spiro::{self, SpiroPointType};
 use glifrenderer::points::draw_point;
 use glifparser::glif::mfek::contour::MFEKContourCommon;
+use glifparser::glif::point::MFEKPointCommon;
 
 
 use editor::util::get_contour_start_or_end;
 
 #[derive(Clone, Debug)]
 pub struct Pen {
-    mode: MFEKContourInnerType,
+    mode: PenModeType,
     cubic: CubicMode,
     quad: QuadMode,
     hyper: HyperMode,
+    spiro: SpiroMode,
 }
 
 
@@ -50,40 +53,60 @@ impl Tool for Pen {
                 ui.horizontal(|ui| {
                     let cubic_button = build_button::<"icons">(v, ui, icons::PEN, "Cubic Bézier");
 
-                    let cubic_button = if self.mode == MFEKContourInnerType::Cubic {
+                    let cubic_button = if self.mode == PenModeType::Cubic {
                         cubic_button.button.unwrap().stroke(egui::Stroke::new(2., Color32::from_rgb(9, 82, 128)))
                     } else {
                         cubic_button.button.unwrap()
                     };
 
                     if ui.add(cubic_button).clicked() {
-                        self.mode = MFEKContourInnerType::Cubic
+                        self.mode = PenModeType::Cubic
                     }
 
                     let quad_button = build_button::<"icons">(v, ui, icons::QUADRATIC, "Quadratic Bézier");
 
-                    let quad_button = if self.mode == MFEKContourInnerType::Quad {
+                    let quad_button = if self.mode == PenModeType::Quad {
                         quad_button.button.unwrap().stroke(egui::Stroke::new(2., Color32::from_rgb(9, 82, 128)))
                     } else {
                         quad_button.button.unwrap()
                     };
 
                     if ui.add(quad_button).clicked() {
-                        self.mode = MFEKContourInnerType::Quad
+                        self.mode = PenModeType::Quad
                     }
 
                     let hyper_button = build_button::<"icons">(v, ui, icons::HYPERBEZIER, "Hyperbezier");
 
-                    let hyper_button = if self.mode == MFEKContourInnerType::Hyper {
+                    let hyper_button = if self.mode == PenModeType::Hyper {
                         hyper_button.button.unwrap().stroke(egui::Stroke::new(2., Color32::from_rgb(9, 82, 128)))
                     } else {
                         hyper_button.button.unwrap()
                     };
         
                     if ui.add(hyper_button).clicked() {
-                        self.mode = MFEKContourInnerType::Hyper
+                        self.mode = PenModeType::Hyper
+                    }
+
+                    let spiro_button = build_button::<"icons">(v, ui, icons::SPIRO, "Spiro");
+
+                    let spiro_button = if self.mode == PenModeType::Spiro {
+                        spiro_button.button.unwrap().stroke(egui::Stroke::new(2., Color32::from_rgb(9, 82, 128)))
+                    } else {
+                        spiro_button.button.unwrap()
+                    };
+
+                    if ui.add(spiro_button).clicked() {
+                        self.mode = PenModeType::Spiro
                     }
                 });
+
+                if self.mode == PenModeType::Spiro {
+                    ui.horizontal(|ui| {
+                        for ptype in SpiroPointType::ALL {
+                            ui.selectable_value(&mut self.spiro.point_type, ptype, ptype.label());
+                        }
+                    });
+                }
             });
     }
 
@@ -96,10 +119,11 @@ impl Tool for Pen {
 impl Pen {
     pub fn new() -> Self {
         Self {
-            mode: MFEKContourInnerType::Cubic,
+            mode: PenModeType::Cubic,
             cubic: CubicMode {  },
             quad: QuadMode {  },
             hyper: HyperMode { },
+            spiro: SpiroMode { point_type: SpiroPointType::G2 },
         }
     }
 
@@ -149,20 +173,33 @@ impl Pen {
         }
 
         // Next we check if our mouse is over an existing curve. If so we add a point to the curve.
-        if let Some(info) = nearest_point_on_curve(v, i, mouse_info.position) {
-            self.get_mode(v).subdivide_curve(v, info)
+        let behavior = if let Some(info) = nearest_point_on_curve(v, i, mouse_info.position) {
+            let mode = self.get_mode_for_contour(v, info.contour_idx);
+            mode.subdivide_curve(v, info);
+            mode.drag_behavior(mouse_info)
         }
         // If we've got the end of a contour selected we'll continue drawing that contour.
         else if can_add_point(v) {
-            self.get_mode(v).add_point(v, mouse_info);
+            // Shift constrains the new point to 45° increments from the one we're continuing from.
+            let mut mouse_info = mouse_info;
+            if mouse_info.modifiers.shift {
+                let (ci, pi) = v.selected_point().unwrap();
+                let previous = get_point!(v.get_active_layer_ref(), ci, pi).unwrap();
+                mouse_info.position = snap::constrain_angle((previous.x(), previous.y()), mouse_info.raw_position);
+            }
+            let mode = self.get_mode(v);
+            mode.add_point(v, mouse_info);
+            mode.drag_behavior(mouse_info)
         } else {
             // Lastly if we get here we create a new contour.
-            self.get_mode(v).new_contour(v, mouse_info)
-        }
+            let mode = self.get_mode(v);
+            mode.new_contour(v, mouse_info);
+            mode.drag_behavior(mouse_info)
+        };
 
-        // No matter how you move the point we want you to be able to manipulate it so we push the MoveHandle
-        // vehavior onto the editor's behavior stack.
-        v.push_behavior(Box::new(MoveHandle::new(WhichHandle::A, mouse_info, true)));
+        // No matter how you move the point we want you to be able to manipulate it so we push the mode's
+        // drag behavior onto the editor's behavior stack.
+        v.push_behavior(behavior);
     }
 
     fn draw_nearest_point(&mut self, v: &Editor, i: &Interface, canvas: &mut Canvas) {
@@ -172,7 +209,7 @@ impl Pen {
         let info = nearest_point_on_curve(v, i, i.mouse_info.position);
 
         if let Some(info) = info {
-            self.get_mode(v).draw_nearest_point(i, canvas, info);
+            self.get_mode_for_contour(v, info.contour_idx).draw_nearest_point(i, canvas, info);
         }
     }
 
@@ -217,15 +254,19 @@ impl Pen {
 
     fn get_mode_for_contour(&mut self, v: &Editor, cidx: usize) -> &mut dyn PenMode {
         let contour = &get_contour!(v.get_active_layer_ref(), cidx);
-        
-        return self.get_mode_by_type(contour.get_type());
+
+        if spiro::is_spiro_contour(contour) {
+            return self.get_mode_by_type(PenModeType::Spiro);
+        }
+        return self.get_mode_by_type(contour.get_type().into());
     }
 
-    fn get_mode_by_type(&mut self, kind: MFEKContourInnerType) -> &mut dyn PenMode {
+    fn get_mode_by_type(&mut self, kind: PenModeType) -> &mut dyn PenMode {
         match kind {
-            MFEKContourInnerType::Cubic => &mut self.cubic,
-            MFEKContourInnerType::Quad => &mut self.quad,
-            MFEKContourInnerType::Hyper => &mut self.hyper,
+            PenModeType::Cubic => &mut self.cubic,
+            PenModeType::Quad => &mut self.quad,
+            PenModeType::Hyper => &mut self.hyper,
+            PenModeType::Spiro => &mut self.spiro,
         }
     }
 }
@@ -1,6 +1,10 @@
-use glifparser::{glif::{point::hyper::{HyperPoint, HyperPointType}, inner::{hyper::MFEKHyperInner, MFEKContourInner}, MFEKContour, contour_operations::ContourOperation, contour::MFEKContourCommon}, MFEKPointData, WhichHandle};
+use glifparser::{glif::{point::hyper::{HyperPoint, HyperPointType}, inner::{hyper::MFEKHyperInner, MFEKContourInner}, MFEKContour, contour_operations::ContourOperation, contour::MFEKContourCommon}, MFEKPointData, Point, PointType, WhichHandle};
+use glifrenderer::points::draw_point;
 
-use crate::{editor::{Editor, util::HoveredPointInfo}, user_interface::MouseInfo, get_contour_len, tool_behaviors::move_handle::MoveHandle};
+use crate::{editor::{Editor, simplify::fit_beziers, util::{HoveredPointInfo, resolved_segment_map}}, user_interface::MouseInfo, get_contour_len, tool_behaviors::move_handle::MoveHandle};
+use glifparser::glif::point::MFEKPointCommon as _;
+use MFEKmath::{Bezier, Evaluate, Piecewise, Primitive as _, Vector};
+use MFEKmath::mfek::ResolveCubic as _;
 
 use super::PenMode;
 
@@ -67,9 +71,132 @@ impl PenMode for HyperMode {
         v.push_behavior(Box::new(MoveHandle::new(WhichHandle::A, mouse_info, true)));
     }
 
-    fn draw_nearest_point(&self, _i: &crate::user_interface::Interface, _canvas: &mut MFEKmath::skia_safe::Canvas, _info: HoveredPointInfo) {
+    fn draw_nearest_point(&self, i: &crate::user_interface::Interface, canvas: &mut MFEKmath::skia_safe::Canvas, info: HoveredPointInfo) {
+        draw_point::<()>(
+            &i.viewport,
+            &Point::from_x_y_type(info.point, PointType::Curve),
+            None,
+            true,
+            canvas
+        )
     }
-    
-    fn subdivide_curve(&self, _v: &mut Editor, _info: HoveredPointInfo) {
+
+    // A smooth point with no handles lets the solver choose its tangents, which bends the curve on
+    // either side of it. So we also try pinning the new point and its neighbours with handles fitted
+    // to each half of the curve as it was, and keep whichever solves closer to the original.
+    fn subdivide_curve(&self, v: &mut Editor, info: HoveredPointInfo) {
+        let (original, halves) = {
+            let contour = &v.get_active_layer_ref().outline[info.contour_idx];
+            let original = resolved_beziers(contour);
+            let halves = split_segment(contour, &info);
+            (original, halves)
+        };
+
+        let layer = v.get_active_layer_mut();
+        let outer = &mut layer.outline[info.contour_idx];
+        outer.operation_mut().insert_op(info.seg_idx + 1);
+        outer.hyper_mut().unwrap().get_points_mut().insert(
+            info.seg_idx + 1,
+            HyperPoint::new(info.point.0, info.point.1, HyperPointType::Curve, true),
+        );
+
+        let (before, after) = match halves {
+            Some(halves) => halves,
+            None => return,
+        };
+        let unpinned = outer.clone();
+        let unpinned_deviation = deviation(&original, &resolved_beziers(&unpinned));
+
+        let len = outer.len();
+        let (prev, new, next) = (info.seg_idx, info.seg_idx + 1, (info.seg_idx + 2) % len);
+        for (idx, wh, handle) in [
+            (prev, WhichHandle::A, before[1]),
+            (new, WhichHandle::B, before[2]),
+            (new, WhichHandle::A, after[1]),
+            (next, WhichHandle::B, after[2]),
+        ] {
+            outer
+                .get_point_mut(idx)
+                .unwrap()
+                .set_handle(wh, handle.to_handle());
+        }
+
+        let pinned_deviation = deviation(&original, &resolved_beziers(outer));
+        log::debug!(
+            "Hyperbezier subdivision moved the curve {} units unpinned, {} pinned",
+            unpinned_deviation,
+            pinned_deviation
+        );
+        if unpinned_deviation <= pinned_deviation {
+            *outer = unpinned;
+        }
     }
-}
\ No newline at end of file
+}
+
+// How many places along each cubic are compared when measuring how far a curve moved.
+const DEVIATION_SAMPLES: usize = 16;
+
+fn resolved_beziers(contour: &MFEKContour<MFEKPointData>) -> Vec<Bezier> {
+    Piecewise::<Bezier>::from(contour.to_cubic().cubic().unwrap()).segs
+}
+
+/// The control points of single cubics fitted to the hovered hyperbezier segment on either side of
+/// the hovered place, if each side can be fitted by one.
+fn split_segment(
+    contour: &MFEKContour<MFEKPointData>,
+    info: &HoveredPointInfo,
+) -> Option<([Vector; 4], [Vector; 4])> {
+    let resolved = contour.to_cubic();
+    let seg_map = resolved_segment_map(contour, &resolved);
+    let beziers = Piecewise::<Bezier>::from(resolved.cubic().unwrap()).segs;
+    let in_segment: Vec<&Bezier> = beziers
+        .iter()
+        .zip(&seg_map)
+        .filter(|(_, seg)| **seg == info.seg_idx)
+        .map(|(bezier, _)| bezier)
+        .collect();
+
+    // The hovered cubic is the one of the segment passing through the hovered place at `t`.
+    let hovered = Vector::from_components(info.point.0 as f64, info.point.1 as f64);
+    let (hi, _) = in_segment.iter().enumerate().min_by(|(_, a), (_, b)| {
+        let (a, b) = (a.at(info.t) - hovered, b.at(info.t) - hovered);
+        a.magnitude().partial_cmp(&b.magnitude()).unwrap()
+    })?;
+    let (left, right) = in_segment[hi].subdivide(info.t)?;
+
+    let mut before: Vec<Bezier> = in_segment[..hi].iter().map(|b| (*b).clone()).collect();
+    before.push(left);
+    let mut after = vec![right];
+    after.extend(in_segment[hi + 1..].iter().map(|b| (*b).clone()));
+
+    let fit = |beziers: &[Bezier]| match fit_beziers(beziers, FIT_TOLERANCE).as_slice() {
+        [bezier] => {
+            let points = bezier.to_control_points();
+            Some([points[0], points[1], points[2], points[3]])
+        }
+        _ => None,
+    };
+    Some((fit(&before)?, fit(&after)?))
+}
+
+// How far, in font units, the cubics pinning a subdivided segment may stray from it.
+const FIT_TOLERANCE: f64 = 0.1;
+
+/// The furthest any sampled place on one curve is from the other, either way round.
+fn deviation(a: &[Bezier], b: &[Bezier]) -> f64 {
+    let sample = |beziers: &[Bezier]| -> Vec<Vector> {
+        beziers
+            .iter()
+            .flat_map(|bezier| {
+                (0..=DEVIATION_SAMPLES).map(move |i| bezier.at(i as f64 / DEVIATION_SAMPLES as f64))
+            })
+            .collect()
+    };
+    let (a, b) = (sample(a), sample(b));
+    let furthest = |from: &[Vector], to: &[Vector]| {
+        from.iter()
+            .map(|p| to.iter().map(|q| (*p - *q).magnitude()).fold(f64::INFINITY, f64::min))
+            .fold(0., f64::max)
+    };
+    furthest(&a, &b).max(furthest(&b, &a))
+}
@@ -1,10 +1,33 @@
 pub mod cubic;
 pub mod quad;
 pub mod hyper;
+pub mod spiro;
 
 use MFEKmath::skia_safe::Canvas;
 use dyn_clone::DynClone;
-use crate::{editor::{Editor, util::HoveredPointInfo}, user_interface::{Interface, MouseInfo}};
+use glifparser::WhichHandle;
+use glifparser::glif::inner::MFEKContourInnerType;
+use crate::{editor::{Editor, util::HoveredPointInfo}, tool_behaviors::{ToolBehavior, move_handle::MoveHandle}, user_interface::{Interface, MouseInfo}};
+
+/// The kinds of contour the pen can draw: the contour inner types, plus Spiro which is stored
+/// as a cubic contour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PenModeType {
+    Cubic,
+    Quad,
+    Hyper,
+    Spiro,
+}
+
+impl From<MFEKContourInnerType> for PenModeType {
+    fn from(kind: MFEKContourInnerType) -> Self {
+        match kind {
+            MFEKContourInnerType::Cubic => Self::Cubic,
+            MFEKContourInnerType::Quad => Self::Quad,
+            MFEKContourInnerType::Hyper => Self::Hyper,
+        }
+    }
+}
 
 pub trait PenMode: DynClone + std::fmt::Debug {
     // No selection starting to draw a new contour.
@@ -17,4 +40,9 @@ pub trait PenMode: DynClone + std::fmt::Debug {
     // stub these functions out.
     fn subdivide_curve(&self, v: &mut Editor, info: HoveredPointInfo);
     fn draw_nearest_point(&self, i: &Interface, canvas: &mut Canvas, info: HoveredPointInfo);
-}
\ No newline at end of file
+
+    // The behavior pushed after a click so the user can keep dragging. Most modes drag out a handle.
+    fn drag_behavior(&self, mouse_info: MouseInfo) -> Box<dyn ToolBehavior> {
+        Box::new(MoveHandle::new(WhichHandle::A, mouse_info, true))
+    }
+}
@@ -1,4 +1,5 @@
-use glifparser::{glif::{contour::MFEKContourCommon, contour_operations::ContourOperation, point::quad::QPoint, MFEKContour, inner::MFEKContourInner},PointType, WhichHandle, MFEKPointData, Handle};
+use glifparser::{glif::{contour::MFEKContourCommon, contour_operations::ContourOperation, point::quad::QPoint, MFEKContour, inner::MFEKContourInner},Point, PointType, WhichHandle, MFEKPointData, Handle};
+use glifrenderer::points::draw_point;
 
 use crate::{editor::{Editor, util::{HoveredPointInfo}}, user_interface::MouseInfo, get_contour_len, tool_behaviors::move_handle::MoveHandle};
 use super::PenMode;
@@ -82,8 +83,48 @@ impl PenMode for QuadMode {
         v.push_behavior(Box::new(MoveHandle::new(WhichHandle::A, mouse_info, true)));
     }
 
-    // TODO: Implement these for quadratic! Would take a bit more work in math.rlib
-    // These functions are safe to be stubbed for now
-    fn draw_nearest_point(&self, _i: &crate::user_interface::Interface, _canvas: &mut MFEKmath::skia_safe::Canvas, _info: HoveredPointInfo) {}
-    fn subdivide_curve(&self, _v: &mut Editor, _info: HoveredPointInfo) {}
+    fn draw_nearest_point(&self, i: &crate::user_interface::Interface, canvas: &mut MFEKmath::skia_safe::Canvas, info: HoveredPointInfo) {
+        draw_point::<()>(
+            &i.viewport,
+            &Point::from_x_y_type(info.point, PointType::Curve),
+            None,
+            true,
+            canvas
+        )
+    }
+
+    // Splits the quadratic segment starting at info.seg_idx with de Casteljau's algorithm. The new
+    // on-curve point is always stored explicitly, taking the right half's off-curve point as its own.
+    fn subdivide_curve(&self, v: &mut Editor, info: HoveredPointInfo) {
+        let layer = v.get_active_layer_mut();
+        let contour = layer.outline[info.contour_idx].quad_mut().unwrap();
+        let next_idx = (info.seg_idx + 1) % contour.len();
+
+        let lerp = |a: (f32, f32), b: (f32, f32), t: f32| (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t);
+        let t = info.t as f32;
+        let p0 = (contour[info.seg_idx].x, contour[info.seg_idx].y);
+        let p1 = (contour[next_idx].x, contour[next_idx].y);
+
+        let (a, new_a, split) = match contour[info.seg_idx].a {
+            Handle::At(cx, cy) => {
+                let left = lerp(p0, (cx, cy), t);
+                let right = lerp((cx, cy), p1, t);
+                (Handle::At(left.0, left.1), Handle::At(right.0, right.1), lerp(left, right, t))
+            }
+            Handle::Colocated => (Handle::Colocated, Handle::Colocated, lerp(p0, p1, t)),
+        };
+
+        contour[info.seg_idx].a = a;
+        contour.insert(info.seg_idx + 1, QPoint {
+            x: split.0,
+            y: split.1,
+            a: new_a,
+            name: None,
+            ptype: PointType::Curve,
+            smooth: matches!(new_a, Handle::At(..)),
+            data: None,
+        });
+
+        layer.outline[info.contour_idx].operation_mut().insert_op(info.seg_idx + 1);
+    }
 }
\ No newline at end of file
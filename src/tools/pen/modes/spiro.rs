@@ -0,0 +1,90 @@
+use glifparser::{glif::{contour::MFEKContourCommon, contour_operations::ContourOperation}, Point, PointType, Contour, MFEKPointData};
+use glifrenderer::points::draw_point;
+
+use crate::{editor::{Editor, spiro::{self, SpiroPointType}, util::HoveredPointInfo}, user_interface::MouseInfo, get_contour_len, tool_behaviors::{ToolBehavior, move_point::MovePoint}};
+use super::PenMode;
+
+/// Draws Spiro contours. Unlike the other modes this has UI state: the type of point the next click
+/// places, chosen in the Pen's mode select window. Spiro points have no handles, so dragging after a
+/// click moves the point instead.
+#[derive(Clone, Debug)]
+pub struct SpiroMode {
+    pub point_type: SpiroPointType,
+}
+
+impl PenMode for SpiroMode {
+    fn new_contour(&self, v: &mut Editor, mouse_info: MouseInfo) {
+        let mouse_pos = mouse_info.position;
+        v.contour_idx = {
+            let layer = v.get_active_layer_mut();
+            let mut new_contour: Contour<MFEKPointData> = Vec::new();
+            let mut point = spiro::new_point(mouse_pos, self.point_type);
+            if !mouse_info.modifiers.shift {
+                point.ptype = PointType::Move;
+            }
+            new_contour.push(point);
+
+            layer.outline.push(new_contour.into());
+            Some(layer.outline.len() - 1)
+        };
+        v.point_idx = Some(0);
+    }
+
+    fn add_point(&self, v: &mut Editor, mouse_info: MouseInfo) {
+        let mouse_pos = mouse_info.position;
+        let contour_idx = v.contour_idx.unwrap();
+        let contour_len = get_contour_len!(v.get_active_layer_ref(), contour_idx);
+
+        if v.point_idx.unwrap() == contour_len - 1 {
+            v.point_idx = {
+                let layer = v.get_active_layer_mut();
+                layer.outline[contour_idx].operation_mut().insert_op(contour_len);
+                let contour = layer.outline[contour_idx].cubic_mut().unwrap();
+                contour.push(spiro::new_point(mouse_pos, self.point_type));
+
+                Some(get_contour_len!(layer, contour_idx) - 1)
+            };
+        } else if v.point_idx.unwrap() == 0 {
+            {
+                let layer = v.get_active_layer_mut();
+                let contour = layer.outline[contour_idx].cubic_mut().unwrap();
+                let mut point = spiro::new_point(mouse_pos, self.point_type);
+                point.ptype = contour[0].ptype;
+
+                if contour.is_open() {
+                    contour[0].ptype = PointType::Curve;
+                }
+
+                contour.insert(0, point);
+
+                layer.outline[contour_idx].operation_mut().insert_op(0);
+            };
+            v.point_idx = Some(0);
+        }
+    }
+
+    fn draw_nearest_point(&self, i: &crate::user_interface::Interface, canvas: &mut MFEKmath::skia_safe::Canvas, info: HoveredPointInfo) {
+        draw_point::<()>(
+            &i.viewport,
+            &Point::from_x_y_type(info.point, PointType::Curve),
+            None,
+            true,
+            canvas
+        )
+    }
+
+    // The new point is smooth, so the re-solved curve still passes through where the user clicked.
+    fn subdivide_curve(&self, v: &mut Editor, info: HoveredPointInfo) {
+        {
+            let layer = v.get_active_layer_mut();
+            layer.outline[info.contour_idx].operation_mut().insert_op(info.seg_idx + 1);
+            let contour = layer.outline[info.contour_idx].cubic_mut().unwrap();
+            contour.insert(info.seg_idx + 1, spiro::new_point(info.point, SpiroPointType::G2));
+        }
+        v.set_selected(info.contour_idx, info.seg_idx + 1);
+    }
+
+    fn drag_behavior(&self, mouse_info: MouseInfo) -> Box<dyn ToolBehavior> {
+        Box::new(MovePoint::new(false, mouse_info))
+    }
+}
@@ -0,0 +1,17 @@
+use super::super::prelude::*;
+use super::Pencil;
+use crate::user_interface::Interface;
+use egui::Ui;
+
+impl Pencil {
+    pub fn tool_dialog(&mut self, _i: &mut Interface, ui: &mut Ui) {
+        ui.label("Smoothing");
+        ui.add(egui::Slider::new(&mut self.tolerance, 0.5..=50.));
+
+        ui.checkbox(&mut self.vws, "Variable width stroke");
+        if self.vws {
+            ui.label("Width");
+            ui.add(egui::Slider::new(&mut self.vws_width, 1.0..=200.));
+        }
+    }
+}
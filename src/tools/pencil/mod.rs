@@ -0,0 +1,193 @@
+mod dialog;
+
+use flo_curves::bezier::fit_curve_cubic;
+use glifparser::glif::contour_operations::ContourOperations;
+use glifparser::MFEKPointData;
+use glifrenderer::constants::OUTLINE_STROKE_THICKNESS;
+use skia_safe::{Canvas, Paint, PaintStyle, Path};
+use MFEKmath::{Bezier, Vector};
+
+use crate::tool_behaviors::zoom_scroll::ZoomScroll;
+use crate::tools::vws::util::vws_contour_for_len;
+use crate::user_interface::Interface;
+
+use super::prelude::*;
+
+// Samples closer than this many screen pixels to the previous one are dropped.
+const PENCIL_MIN_DISTANCE: f32 = 2.;
+// If the stroke ends within this many screen pixels of where it started, the contour is closed.
+const PENCIL_CLOSE_DISTANCE: f32 = 10.;
+// How many samples in from each end the start and end tangents are measured over.
+const PENCIL_TANGENT_SAMPLES: usize = 4;
+
+/// Draws freehand. The path the mouse takes while the button is down is fitted with smooth cubic
+/// curves when it's released.
+#[derive(Clone, Debug)]
+pub struct Pencil {
+    // the stroke being drawn, in glyph space
+    samples: Vec<(f32, f32)>,
+    drawing: bool,
+
+    // how far, in units, the fitted curves may stray from the stroke
+    tolerance: f32,
+    // attach a variable width stroke to new contours, with this total width
+    vws: bool,
+    vws_width: f32,
+}
+
+impl Tool for Pencil {
+    #[rustfmt::skip]
+    fn event(&mut self, v: &mut Editor, i: &mut Interface, event: EditorEvent) {
+        match event {
+            EditorEvent::MouseEvent { mouse_info, event_type } => match event_type {
+                MouseEventType::Pressed => self.mouse_pressed(mouse_info),
+                MouseEventType::Moved => self.mouse_moved(i, mouse_info),
+                MouseEventType::Released => self.mouse_released(v, i),
+                _ => (),
+            }
+            EditorEvent::ScrollEvent { .. } => ZoomScroll::default().event(v, i, event),
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, _v: &Editor, i: &Interface, canvas: &mut Canvas) {
+        self.draw_stroke(i, canvas);
+    }
+
+    fn dialog(&mut self, _v: &mut Editor, i: &mut Interface, ui: &mut Ui) -> bool {
+        self.tool_dialog(i, ui);
+        true
+    }
+}
+
+impl Pencil {
+    pub fn new() -> Self {
+        Self {
+            samples: vec![],
+            drawing: false,
+            tolerance: 4.,
+            vws: false,
+            vws_width: 20.,
+        }
+    }
+
+    fn mouse_pressed(&mut self, mouse_info: MouseInfo) {
+        if mouse_info.button == MouseButton::Left {
+            self.drawing = true;
+            self.samples = vec![mouse_info.raw_position];
+        }
+    }
+
+    fn mouse_moved(&mut self, i: &Interface, mouse_info: MouseInfo) {
+        if !self.drawing {
+            return;
+        }
+
+        let (x, y) = mouse_info.raw_position;
+        let (lx, ly) = *self.samples.last().unwrap();
+        if f32::hypot(x - lx, y - ly) >= PENCIL_MIN_DISTANCE / i.viewport.factor {
+            self.samples.push((x, y));
+        }
+    }
+
+    fn mouse_released(&mut self, v: &mut Editor, i: &Interface) {
+        if !self.drawing {
+            return;
+        }
+        self.drawing = false;
+
+        let samples = std::mem::take(&mut self.samples);
+        let closed = samples.len() > PENCIL_TANGENT_SAMPLES * 2 && {
+            let (first, last) = (samples[0], *samples.last().unwrap());
+            f32::hypot(first.0 - last.0, first.1 - last.1) < PENCIL_CLOSE_DISTANCE / i.viewport.factor
+        };
+
+        if let Some(contour) = fit_stroke(&samples, self.tolerance as f64, closed) {
+            let len = contour.len();
+            let mut contour: glifparser::glif::MFEKContour<MFEKPointData> = contour.into();
+            if self.vws {
+                contour.set_operation(Some(ContourOperations::VariableWidthStroke {
+                    data: vws_contour_for_len(len, self.vws_width as f64 / 2.),
+                }));
+            }
+
+            v.begin_modification("Draw with pencil.", false);
+            v.get_active_layer_mut().outline.push(contour);
+            v.end_modification();
+        }
+    }
+
+    fn draw_stroke(&self, i: &Interface, canvas: &mut Canvas) {
+        if !self.drawing || self.samples.len() < 2 {
+            return;
+        }
+
+        let mut path = Path::new();
+        path.move_to(self.samples[0]);
+        for sample in &self.samples[1..] {
+            path.line_to(*sample);
+        }
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_color(OUTLINE_STROKE);
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_stroke_width(OUTLINE_STROKE_THICKNESS * (1. / i.viewport.factor));
+        canvas.draw_path(&path, &paint);
+    }
+}
+
+/// Fits cubic curves to a freehand stroke and builds a contour from them.
+fn fit_stroke(samples: &[(f32, f32)], tolerance: f64, closed: bool) -> Option<Contour<MFEKPointData>> {
+    let mut samples: Vec<Vector> = samples
+        .iter()
+        .map(|(x, y)| Vector::from_components(*x as f64, *y as f64))
+        .collect();
+    if closed {
+        // end exactly where we started so the contour closes smoothly
+        *samples.last_mut().unwrap() = samples[0];
+    }
+    if samples.len() < 2 {
+        return None;
+    }
+
+    let last = samples.len() - 1;
+    let k = PENCIL_TANGENT_SAMPLES.min(last);
+    let (start_tangent, end_tangent) = if closed {
+        // both ends share the tangent across the join
+        let across = (samples[k] - samples[last - k]).normalize();
+        (across, -across)
+    } else {
+        (
+            (samples[k] - samples[0]).normalize(),
+            (samples[last - k] - samples[last]).normalize(),
+        )
+    };
+
+    let fitted: Vec<Bezier> = fit_curve_cubic(&samples, &start_tangent, &end_tangent, tolerance);
+    if fitted.is_empty() {
+        return None;
+    }
+
+    let mut points: Contour<MFEKPointData> = vec![Point::from_x_y_a_b_type(
+        (fitted[0].w1.x as f32, fitted[0].w1.y as f32),
+        (fitted[0].w2.to_handle(), Handle::Colocated),
+        if closed { PointType::Curve } else { PointType::Move },
+    )];
+    for (idx, bez) in fitted.iter().enumerate() {
+        let next_a = fitted.get(idx + 1).map(|n| n.w2.to_handle()).unwrap_or(Handle::Colocated);
+        points.push(Point::from_x_y_a_b_type(
+            (bez.w4.x as f32, bez.w4.y as f32),
+            (next_a, bez.w3.to_handle()),
+            PointType::Curve,
+        ));
+    }
+
+    if closed {
+        // the last point is the first one again
+        let end = points.pop().unwrap();
+        points[0].b = end.b;
+    }
+
+    Some(points)
+}
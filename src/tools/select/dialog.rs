@@ -0,0 +1,18 @@
+use super::super::prelude::*;
+use super::Select;
+use crate::user_interface::Interface;
+use egui::Ui;
+
+impl Select {
+    pub fn tool_dialog(&mut self, v: &mut Editor, _i: &mut Interface, ui: &mut Ui) {
+        ui.collapsing("Simplify", |ui| {
+            ui.label("Tolerance");
+            ui.add(egui::Slider::new(&mut self.simplify_tolerance, 0.1..=50.));
+            ui.checkbox(&mut self.simplify_preview, "Preview");
+
+            if ui.button("Apply").clicked() {
+                v.simplify_selection(self.simplify_tolerance as f64);
+            }
+        });
+    }
+}
@@ -1,12 +1,25 @@
+mod dialog;
+
+// Sizes of the selection bounding box's scale handles and the rotation zone outside its corners,
+// in screen pixels.
+const BOUNDING_BOX_HANDLE_SIZE: f32 = 4.;
+const ROTATION_ZONE_SIZE: f32 = 16.;
+
 use std::collections::HashSet;
 
 // Select
 use super::{prelude::*, EditorEvent, MouseEventType, Tool};
 use crate::command::{Command, CommandType};
 use crate::get_point_mut;
+use crate::editor::transform::BoundingBoxAnchor;
 use crate::tool_behaviors::rotate_selection::RotateSelection;
+use crate::tool_behaviors::scale_selection::ScaleSelection;
 use glifparser::glif::mfek::contour::MFEKContourCommon;
+use glifparser::glif::MFEKContour;
+use glifparser::outline::skia::ToSkiaPaths as _;
+use glifparser::MFEKPointData;
 
+use itertools::Itertools as _;
 use MFEKmath::Vector;
 
 use crate::tool_behaviors::{
@@ -17,10 +30,34 @@ use crate::tool_behaviors::{
 // Select is a good example of a more complicated tool that keeps lots of state.
 // It has state for which handle it's selected, follow rules, selection box, and to track if it's currently
 // moving a point.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct Select {
-    pivot_point: Option<(f32, f32)>,
     draw_pivot: DrawPivot,
+    simplify_tolerance: f32,
+    simplify_preview: bool,
+    // The simplified contours last previewed, with what they were fitted to so they're only fitted
+    // again when that changes.
+    simplify_cache: Option<(SimplifyInput, Vec<MFEKContour<MFEKPointData>>)>,
+}
+
+/// The selection, tolerance and contours a simplify preview was fitted to.
+#[derive(Clone, Debug, PartialEq)]
+struct SimplifyInput {
+    layer: usize,
+    selected: Vec<(usize, usize)>,
+    tolerance: f32,
+    contours: Vec<MFEKContour<MFEKPointData>>,
+}
+
+impl Default for Select {
+    fn default() -> Self {
+        Self {
+            draw_pivot: DrawPivot::default(),
+            simplify_tolerance: 5.,
+            simplify_preview: true,
+            simplify_cache: None,
+        }
+    }
 }
 
 impl Tool for Select {
@@ -51,6 +88,14 @@ impl Tool for Select {
                 *stop_after.borrow_mut() = true;
                 self.reverse_selected(v);
             }
+            EditorEvent::ToolCommand {
+                command: Command::SimplifySelection,
+                stop_after,
+                ..
+            } => {
+                *stop_after.borrow_mut() = true;
+                v.simplify_selection(self.simplify_tolerance as f64);
+            }
             EditorEvent::ToolCommand {
                 command,
                 stop_after,
@@ -69,6 +114,17 @@ impl Tool for Select {
 
     fn draw(&mut self, v: &Editor, i: &Interface, canvas: &mut Canvas) {
         self.draw_pivot.draw(v, i, canvas);
+        self.draw_bounding_box(v, i, canvas);
+        self.draw_simplify_preview(v, i, canvas);
+    }
+
+    fn dialog(&mut self, v: &mut Editor, i: &mut Interface, ui: &mut Ui) -> bool {
+        if v.selected.len() < 3 {
+            return false;
+        }
+
+        self.tool_dialog(v, i, ui);
+        true
     }
 }
 
@@ -147,26 +203,22 @@ impl Select {
             return;
         }
 
+        // the bounding box's handles sit on the selection's extreme points, so they're looked for first or
+        // those points would always win the click
+        if mouse_info.button == MouseButton::Left && v.selected.len() > 1 {
+            if let Some(handle) = self.clicked_bounding_box_handle(v, i, mouse_info.raw_position) {
+                let behavior = ScaleSelection::new(v, handle, mouse_info);
+                v.set_behavior(Box::new(behavior));
+                return;
+            }
+        }
+
         // if the user holds control we initiate a rotation of the current selection, either around the pivot point
         // or around the selection's bounding box's center
         if mouse_info.modifiers.ctrl && !v.selected.is_empty() {
-            let pivot = self
-                .pivot_point
-                .unwrap_or_else(|| v.get_selection_bounding_box_center());
-            let pivot_calc = (pivot.0, pivot.1);
-            let pivot_vector = Vector::from_components(pivot_calc.0 as f64, pivot_calc.1 as f64);
-            let mouse_vector =
-                Vector::from_components(mouse_info.position.0 as f64, mouse_info.position.1 as f64);
-            let normal_from_pivot = (pivot_vector - mouse_vector).normalize();
-
-            v.set_behavior(Box::new(RotateSelection::new(
-                pivot,
-                normal_from_pivot.into(),
-                mouse_info,
-            )));
+            self.start_rotation(v, mouse_info);
             return;
         }
-        
 
         // if we found a point or handle we're going to start a drag operation
         match clicked_point_or_handle(v, i, mouse_info.raw_position, None) {
@@ -197,6 +249,15 @@ impl Select {
                 }
             }
             None => {
+                // the rotation zone lies outside the bounding box's corners, where points may sit, so it only
+                // counts if no point was clicked
+                if mouse_info.button == MouseButton::Left && v.selected.len() > 1 {
+                    if self.clicked_rotation_zone(v, i, mouse_info.raw_position) {
+                        self.start_rotation(v, mouse_info);
+                        return;
+                    }
+                }
+
                 // if the user isn't holding shift we clear the current selection and the currently selected
                 // point
                 if !mouse_info.modifiers.shift {
@@ -207,7 +268,7 @@ impl Select {
 
                 // if they clicked right mouse we set the pivot point that will be used by rotate_points behavior.
                 if mouse_info.button == MouseButton::Right {
-                    self.pivot_point = Some((mouse_info.position.0, mouse_info.position.1));
+                    v.pivot_point = Some((mouse_info.position.0, mouse_info.position.1));
                 } else if mouse_info.button == MouseButton::Left {
                     v.set_behavior(Box::new(SelectionBox::new(mouse_info)));
                 }
@@ -215,6 +276,136 @@ impl Select {
         };
     }
 
+    fn start_rotation(&mut self, v: &mut Editor, mouse_info: MouseInfo) {
+        let pivot = v
+            .pivot_point
+            .unwrap_or_else(|| v.get_selection_bounding_box_center());
+        let pivot_vector = Vector::from_components(pivot.0 as f64, pivot.1 as f64);
+        let mouse_vector =
+            Vector::from_components(mouse_info.position.0 as f64, mouse_info.position.1 as f64);
+        let normal_from_pivot = (pivot_vector - mouse_vector).normalize();
+
+        v.set_behavior(Box::new(RotateSelection::new(
+            pivot,
+            normal_from_pivot.into(),
+            mouse_info,
+        )));
+    }
+
+    fn bounding_box_handles(v: &Editor) -> Vec<(BoundingBoxAnchor, (f32, f32))> {
+        let bounding_box = v.build_selection_bounding_box();
+        BoundingBoxAnchor::ALL
+            .into_iter()
+            .filter(|anchor| *anchor != BoundingBoxAnchor::Center)
+            .map(|anchor| (anchor, anchor.position(&bounding_box)))
+            .collect()
+    }
+
+    fn clicked_bounding_box_handle(
+        &self,
+        v: &Editor,
+        i: &Interface,
+        position: (f32, f32),
+    ) -> Option<BoundingBoxAnchor> {
+        let size = BOUNDING_BOX_HANDLE_SIZE * 1.5 / i.viewport.factor;
+        Self::bounding_box_handles(v)
+            .into_iter()
+            .find(|(_, (x, y))| (position.0 - x).abs() <= size && (position.1 - y).abs() <= size)
+            .map(|(anchor, _)| anchor)
+    }
+
+    // The rotation zone is the area just outside each corner of the bounding box.
+    fn clicked_rotation_zone(&self, v: &Editor, i: &Interface, position: (f32, f32)) -> bool {
+        let zone = ROTATION_ZONE_SIZE / i.viewport.factor;
+        let bounding_box = v.build_selection_bounding_box();
+        let (left, bottom) = BoundingBoxAnchor::BottomLeft.position(&bounding_box);
+        let (right, top) = BoundingBoxAnchor::TopRight.position(&bounding_box);
+
+        let outside_x = position.0 < left || position.0 > right;
+        let outside_y = position.1 < bottom || position.1 > top;
+        let near_x = position.0 > left - zone && position.0 < right + zone;
+        let near_y = position.1 > bottom - zone && position.1 < top + zone;
+        let near_corner = [(left, bottom), (left, top), (right, bottom), (right, top)]
+            .iter()
+            .any(|(x, y)| f32::hypot(position.0 - x, position.1 - y) < zone);
+
+        (outside_x || outside_y) && near_x && near_y && near_corner
+    }
+
+    fn draw_bounding_box(&self, v: &Editor, i: &Interface, canvas: &mut Canvas) {
+        if v.selected.len() < 2 || v.is_modifying() {
+            return;
+        }
+
+        let factor = i.viewport.factor;
+        let bounding_box = v.build_selection_bounding_box();
+        let (left, bottom) = BoundingBoxAnchor::BottomLeft.position(&bounding_box);
+        let (right, top) = BoundingBoxAnchor::TopRight.position(&bounding_box);
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_color(OUTLINE_STROKE);
+        paint.set_stroke_width(OUTLINE_STROKE_THICKNESS * (1. / factor));
+
+        let mut path = Path::new();
+        path.add_rect(Rect::from_ltrb(left, bottom, right, top), None);
+        for (_, (x, y)) in Self::bounding_box_handles(v) {
+            let size = BOUNDING_BOX_HANDLE_SIZE / factor;
+            path.add_rect(Rect::from_ltrb(x - size, y - size, x + size, y + size), None);
+        }
+        canvas.draw_path(&path, &paint);
+    }
+
+    fn draw_simplify_preview(&mut self, v: &Editor, i: &Interface, canvas: &mut Canvas) {
+        if !self.simplify_preview || v.selected.len() < 3 || v.is_modifying() {
+            return;
+        }
+
+        let mut selected: Vec<_> = v.selected.iter().copied().collect();
+        selected.sort_unstable();
+        let layer = v.get_active_layer_ref();
+        let contours: Vec<_> = selected
+            .iter()
+            .map(|(ci, _)| *ci)
+            .dedup()
+            .map(|ci| &layer.outline[ci])
+            .collect();
+        let fitted = self.simplify_cache.as_ref().map_or(false, |(input, _)| {
+            input.layer == v.get_active_layer()
+                && input.selected == selected
+                && input.tolerance == self.simplify_tolerance
+                && input.contours.iter().eq(contours.iter().copied())
+        });
+
+        if !fitted {
+            let simplified = v
+                .build_simplified_contours(self.simplify_tolerance as f64)
+                .into_iter()
+                .map(|(_, contour)| contour)
+                .collect();
+            let input = SimplifyInput {
+                layer: v.get_active_layer(),
+                selected,
+                tolerance: self.simplify_tolerance,
+                contours: contours.into_iter().cloned().collect(),
+            };
+            self.simplify_cache = Some((input, simplified));
+        }
+        let contours = &self.simplify_cache.as_ref().unwrap().1;
+        if contours.is_empty() {
+            return;
+        }
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_color(SELECTED_STROKE);
+        paint.set_stroke_width(OUTLINE_STROKE_THICKNESS * (1. / i.viewport.factor));
+
+        canvas.draw_path(&contours.to_skia_paths(None).combined(), &paint);
+    }
+
     fn mouse_double_pressed(&mut self, v: &mut Editor, i: &Interface, mouse_info: MouseInfo) {
         let ci = if let Some((ci, _pi, _wh)) =
             clicked_point_or_handle(v, i, mouse_info.raw_position, None)
@@ -3,10 +3,14 @@ use glifparser::JoinType;
 use glifparser::glif::contour_operations::ContourOperations;
 
 use super::super::prelude::*;
+use super::profile::{self, WidthProfile};
 use super::util::*;
 use super::VWS;
-use crate::user_interface::Interface;
+use crate::editor::vws_caps::CapEnd;
+use crate::user_interface::{Interface, gui};
 use crate::user_interface::gui::windows::egui_parsed_textfield;
+use glifparser::MFEKPointData;
+use glifparser::glif::MFEKOutline;
 
 impl VWS {
     pub fn tool_dialog(&mut self, v: &mut Editor, _i: &Interface, ui: &mut egui::Ui) {
@@ -64,13 +68,131 @@ impl VWS {
                 }
             });
 
+            let mut cap_changes = vec![];
+            ui.collapsing("Custom Caps", |ui| {
+                let caps = v.vws_caps(cidx).cloned().unwrap_or_default();
+                ui.label(format!(
+                    "Start: {}, End: {}",
+                    if caps.start.is_some() { "set" } else { "none" },
+                    if caps.end.is_some() { "set" } else { "none" },
+                ));
+
+                let mut cap = None;
+                self.cap_layer = self.cap_layer.min(v.get_layer_count() - 1);
+                ui.horizontal(|ui| {
+                    let name = v.with_glyph(|glif| glif.layers[self.cap_layer].name.clone());
+                    egui::ComboBox::new("vws_cap_layer", "")
+                        .selected_text(name)
+                        .show_ui(ui, |ui| {
+                            for layer in 0..v.get_layer_count() {
+                                let name = v.with_glyph(|glif| glif.layers[layer].name.clone());
+                                ui.selectable_value(&mut self.cap_layer, layer, name);
+                            }
+                        });
+                    if ui.button("Use as Start").clicked() {
+                        cap = Some((CapEnd::Start, Ok(self.layer_outline(v))));
+                    }
+                    if ui.button("Use as End").clicked() {
+                        cap = Some((CapEnd::End, Ok(self.layer_outline(v))));
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Glyph:");
+                    ui.text_edit_singleline(&mut self.cap_glyph);
+                    let enabled = !self.cap_glyph.is_empty();
+                    if ui.add_enabled(enabled, egui::Button::new("Use as Start")).clicked() {
+                        cap = Some((CapEnd::Start, v.read_cap_glyph(&self.cap_glyph)));
+                    }
+                    if ui.add_enabled(enabled, egui::Button::new("Use as End")).clicked() {
+                        cap = Some((CapEnd::End, v.read_cap_glyph(&self.cap_glyph)));
+                    }
+                });
+
+                if ui.button("Clear Custom Caps").clicked() {
+                    cap_changes.push((CapEnd::Start, None));
+                    cap_changes.push((CapEnd::End, None));
+                }
+
+                match cap {
+                    Some((end, Ok(outline))) => {
+                        cap_changes.push((end, Some(outline)));
+                        // A custom cap only shows if the end's cap type says to use it.
+                        match end {
+                            CapEnd::Start => data.cap_start_type = CapType::Custom,
+                            CapEnd::End => data.cap_end_type = CapType::Custom,
+                        }
+                    }
+                    Some((_, Err(e))) => {
+                        gui::error!("Couldn't use glyph {} as a cap: {}", self.cap_glyph, e)
+                    }
+                    None => (),
+                }
+            });
+
+            ui.collapsing("Width Profile", |ui| {
+                let contour = v.get_active_layer_ref().outline[cidx].clone();
+                let current = WidthProfile::from_vws(&data, &contour);
+                ui.horizontal(|ui| {
+                    if ui.button("Copy").clicked() {
+                        self.profile_clipboard = Some(current.clone());
+                    }
+                    if let Some(copied) = &self.profile_clipboard {
+                        if ui.button("Paste").clicked() {
+                            copied.apply_to(&mut data, &contour);
+                        }
+                    }
+                    if ui.button("Mirror").clicked() {
+                        current.mirrored().apply_to(&mut data, &contour);
+                    }
+                    if ui.button("Reverse").clicked() {
+                        current.reversed().apply_to(&mut data, &contour);
+                    }
+                });
+
+                ui.separator();
+                let mut presets_changed = false;
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.preset_name);
+                    let enabled = !self.preset_name.is_empty();
+                    if ui.add_enabled(enabled, egui::Button::new("Save Preset")).clicked() {
+                        self.presets.insert(self.preset_name.clone(), current.clone());
+                        presets_changed = true;
+                    }
+                });
+                let mut remove = None;
+                for (name, preset) in &self.presets {
+                    ui.horizontal(|ui| {
+                        ui.label(name);
+                        if ui.button("Apply").clicked() {
+                            preset.apply_to(&mut data, &contour);
+                        }
+                        if ui.button("Delete").clicked() {
+                            remove = Some(name.clone());
+                        }
+                    });
+                }
+                if let Some(name) = remove {
+                    self.presets.remove(&name);
+                    presets_changed = true;
+                }
+                if presets_changed {
+                    if let Err(e) = profile::save_presets(&self.presets) {
+                        gui::error!("Failed to save VWS width profiles: {}", e);
+                    }
+                }
+            });
+
             ui.collapsing("Point", |ui| {
                 data.handles[pidx].left_offset = egui_parsed_textfield(ui, "lo", data.handles[pidx].left_offset, &mut self.edit_buf);
                 data.handles[pidx].right_offset = egui_parsed_textfield(ui, "ro", data.handles[pidx].right_offset, &mut self.edit_buf);
             });
 
-            if data != original_data && !v.is_modifying() {
+            if (data != original_data || !cap_changes.is_empty()) && !v.is_modifying() {
                 v.begin_modification("VWS dialog modification.", true);
+                for (end, cap) in cap_changes {
+                    v.set_vws_cap(cidx, end, cap);
+                }
                 set_vws_contour(v, cidx, data);
                 v.end_modification();
             }
@@ -78,4 +200,8 @@ impl VWS {
             ui.label("Non-VWS contour selected!");
         }
     }
+
+    fn layer_outline(&self, v: &Editor) -> MFEKOutline<MFEKPointData> {
+        v.with_glyph(|glif| glif.layers[self.cap_layer].outline.clone())
+    }
 }
@@ -1,7 +1,8 @@
 mod dialog;
+mod profile;
 pub mod util;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::tool_behaviors::{move_vws_handle::MoveVWSHandle, zoom_scroll::ZoomScroll};
 use crate::user_interface::Interface;
@@ -11,6 +12,7 @@ use glifparser::glif::contour_operations::ContourOperations;
 use sdl2::mouse::MouseButton;
 use skia_safe::{Canvas, Paint, PaintStyle, Path as SkiaPath};
 
+use self::profile::WidthProfile;
 use self::util::{clicked_handle, get_vws_handle_pos};
 
 use super::prelude::*;
@@ -19,6 +21,13 @@ use super::prelude::*;
 #[derive(Clone, Debug)]
 pub struct VWS {
     edit_buf: HashMap<String, String>,
+    // where custom caps are taken from
+    cap_layer: usize,
+    cap_glyph: String,
+    // width profiles copied from a contour, and saved under a name
+    profile_clipboard: Option<WidthProfile>,
+    presets: BTreeMap<String, WidthProfile>,
+    preset_name: String,
 }
 
 impl Tool for VWS {
@@ -59,7 +68,15 @@ impl Tool for VWS {
 impl VWS {
     pub fn new() -> Self {
         VWS {
-            edit_buf: HashMap::new()
+            edit_buf: HashMap::new(),
+            cap_layer: 0,
+            cap_glyph: String::new(),
+            profile_clipboard: None,
+            presets: profile::load_presets().unwrap_or_else(|e| {
+                log::error!("Failed to load VWS width profiles: {}", e);
+                BTreeMap::new()
+            }),
+            preset_name: String::new(),
         }
     }
 
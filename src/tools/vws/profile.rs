@@ -0,0 +1,380 @@
+//! Width profiles: the offsets of every handle of a variable width stroke, which can be copied from
+//! one contour to another whatever their point counts, and saved as named presets.
+
+use glifparser::glif::contour::MFEKContourCommon;
+use glifparser::glif::MFEKContour;
+use glifparser::{Handle, MFEKPointData, Point, VWSContour};
+use serde::{Deserialize, Serialize};
+use MFEKmath::mfek::ResolveCubic;
+
+use crate::settings::CONFIG_PATH;
+use lazy_static::lazy_static;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+lazy_static! {
+    pub static ref CONFIG_VWS_PROFILES_PATH: PathBuf = {
+        let mut pb = CONFIG_PATH.clone();
+
+        pb.push("vws_profiles");
+        pb.set_extension("json");
+        pb
+    };
+}
+
+/// Straight lines each segment's length is measured along.
+const LENGTH_STEPS: usize = 16;
+
+/// The left, right and tangent offsets of each handle, in order along the contour, and where each
+/// handle's point is along it as a fraction of its length. A closed contour's profile wraps around
+/// from its last point to its first.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WidthProfile {
+    pub offsets: Vec<(f64, f64, f64)>,
+    pub positions: Vec<f64>,
+    pub closed: bool,
+}
+
+/// A preset as saved by older versions, with only the offsets, taken to be evenly spaced.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedProfile {
+    Profile(WidthProfile),
+    Offsets(Vec<(f64, f64, f64)>),
+}
+
+fn handle_or_point(handle: Handle, point: (f64, f64)) -> (f64, f64) {
+    match handle {
+        Handle::At(x, y) => (x as f64, y as f64),
+        Handle::Colocated => point,
+    }
+}
+
+/// The length of a cubic Bézier segment, measured along straight lines between points on it.
+fn segment_length(p0: (f64, f64), c1: (f64, f64), c2: (f64, f64), p3: (f64, f64)) -> f64 {
+    let at = |t: f64| {
+        let mt = 1. - t;
+        let (a, b, c, d) = (mt * mt * mt, 3. * mt * mt * t, 3. * mt * t * t, t * t * t);
+        (
+            a * p0.0 + b * c1.0 + c * c2.0 + d * p3.0,
+            a * p0.1 + b * c1.1 + c * c2.1 + d * p3.1,
+        )
+    };
+    (0..LENGTH_STEPS)
+        .map(|i| {
+            let (from, to) = (
+                at(i as f64 / LENGTH_STEPS as f64),
+                at((i + 1) as f64 / LENGTH_STEPS as f64),
+            );
+            (to.0 - from.0).hypot(to.1 - from.1)
+        })
+        .sum()
+}
+
+/// Where each point of a contour is along it, as a fraction of its length. A closed contour's
+/// length includes the segment back to its first point.
+fn point_positions(points: &[Point<MFEKPointData>], closed: bool) -> Vec<f64> {
+    let position = |p: &Point<MFEKPointData>| (p.x as f64, p.y as f64);
+    let segments = if closed {
+        points.len()
+    } else {
+        points.len().saturating_sub(1)
+    };
+    let mut lengths = vec![0.];
+    for i in 0..segments {
+        let (from, to) = (&points[i], &points[(i + 1) % points.len()]);
+        let (p0, p3) = (position(from), position(to));
+        let length = segment_length(
+            p0,
+            handle_or_point(from.a, p0),
+            handle_or_point(to.b, p3),
+            p3,
+        );
+        lengths.push(lengths[i] + length);
+    }
+
+    let total = *lengths.last().unwrap();
+    lengths.truncate(points.len());
+    if total <= 0. {
+        return evenly_spaced(points.len(), closed);
+    }
+    lengths.into_iter().map(|length| length / total).collect()
+}
+
+/// `len` positions spaced evenly along a contour.
+fn evenly_spaced(len: usize, closed: bool) -> Vec<f64> {
+    let segments = if closed { len } else { len.saturating_sub(1) }.max(1);
+    (0..len).map(|i| i as f64 / segments as f64).collect()
+}
+
+/// The positions of a stroked contour's handles, or if the points can't be had one per handle,
+/// evenly spaced ones. A stroke can have a handle after its last point's, which is at the end of
+/// the contour: on a closed one, back at its first point.
+fn handle_positions(data: &VWSContour, contour: &MFEKContour<MFEKPointData>) -> Vec<f64> {
+    let cubic = contour.to_cubic();
+    let closed = !contour.is_open();
+    match cubic.cubic() {
+        Some(points) if points.len() == data.handles.len() => point_positions(points, closed),
+        Some(points) if !points.is_empty() && points.len() + 1 == data.handles.len() => {
+            let mut positions = point_positions(points, closed);
+            positions.push(1.);
+            positions
+        }
+        _ => evenly_spaced(data.handles.len(), closed),
+    }
+}
+
+impl WidthProfile {
+    pub fn from_vws(data: &VWSContour, contour: &MFEKContour<MFEKPointData>) -> Self {
+        Self {
+            offsets: data
+                .handles
+                .iter()
+                .map(|h| (h.left_offset, h.right_offset, h.tangent_offset))
+                .collect(),
+            positions: handle_positions(data, contour),
+            closed: !contour.is_open(),
+        }
+    }
+
+    /// The profile's offsets at a position along it, interpolated linearly between the handles
+    /// either side of it, which on a closed profile may be its last and first.
+    fn at(&self, position: f64) -> (f64, f64, f64) {
+        let (offsets, positions) = (&self.offsets, &self.positions);
+        let last = offsets.len() - 1;
+        let lerp = |a: (f64, f64, f64), b: (f64, f64, f64), from: f64, to: f64, at: f64| {
+            let t = if to > from {
+                ((at - from) / (to - from)).clamp(0., 1.)
+            } else {
+                0.
+            };
+            (
+                a.0 + (b.0 - a.0) * t,
+                a.1 + (b.1 - a.1) * t,
+                a.2 + (b.2 - a.2) * t,
+            )
+        };
+
+        if position < positions[0] || position >= positions[last] {
+            if !self.closed {
+                return offsets[if position < positions[0] { 0 } else { last }];
+            }
+            let at = if position < positions[last] {
+                position + 1.
+            } else {
+                position
+            };
+            return lerp(
+                offsets[last],
+                offsets[0],
+                positions[last],
+                positions[0] + 1.,
+                at,
+            );
+        }
+        let i = positions
+            .iter()
+            .rposition(|p| *p <= position)
+            .unwrap_or(0)
+            .min(last - 1);
+        lerp(
+            offsets[i],
+            offsets[i + 1],
+            positions[i],
+            positions[i + 1],
+            position,
+        )
+    }
+
+    /// The profile's offsets at each of the given positions along it.
+    fn resampled(&self, positions: &[f64]) -> Vec<(f64, f64, f64)> {
+        match self.offsets.len() {
+            0 => vec![],
+            1 => vec![self.offsets[0]; positions.len()],
+            _ => positions.iter().map(|p| self.at(*p)).collect(),
+        }
+    }
+
+    /// Sets the offsets of every handle of a stroke, resampling the profile to where its handles
+    /// are along the contour.
+    pub fn apply_to(&self, data: &mut VWSContour, contour: &MFEKContour<MFEKPointData>) {
+        let offsets = self.resampled(&handle_positions(data, contour));
+        for (handle, (left, right, tangent)) in data.handles.iter_mut().zip(offsets) {
+            handle.left_offset = left;
+            handle.right_offset = right;
+            handle.tangent_offset = tangent;
+        }
+    }
+
+    /// Swaps the left and right sides.
+    pub fn mirrored(&self) -> Self {
+        Self {
+            offsets: self.offsets.iter().map(|(l, r, t)| (*r, *l, *t)).collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Runs the profile from the other end of the contour. A closed profile still starts at its
+    /// first point.
+    pub fn reversed(&self) -> Self {
+        let mut reversed: Vec<_> = self
+            .positions
+            .iter()
+            .zip(&self.offsets)
+            .map(|(p, (l, r, t))| {
+                let p = if self.closed { (1. - p) % 1. } else { 1. - p };
+                (p, (*l, *r, -t))
+            })
+            .collect();
+        reversed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self {
+            positions: reversed.iter().map(|(p, _)| *p).collect(),
+            offsets: reversed.into_iter().map(|(_, offsets)| offsets).collect(),
+            closed: self.closed,
+        }
+    }
+}
+
+/// Reads the saved presets. There being no presets file yet isn't an error.
+pub fn load_presets() -> Result<BTreeMap<String, WidthProfile>, String> {
+    let json = match fs::read_to_string(&*CONFIG_VWS_PROFILES_PATH) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(e.to_string()),
+    };
+    let presets: BTreeMap<String, SavedProfile> =
+        serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    Ok(presets
+        .into_iter()
+        .map(|(name, saved)| {
+            let profile = match saved {
+                SavedProfile::Profile(profile) => profile,
+                SavedProfile::Offsets(offsets) => WidthProfile {
+                    positions: evenly_spaced(offsets.len(), false),
+                    offsets,
+                    closed: false,
+                },
+            };
+            (name, profile)
+        })
+        .collect())
+}
+
+pub fn save_presets(presets: &BTreeMap<String, WidthProfile>) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(presets).map_err(|e| e.to_string())?;
+    fs::write(&*CONFIG_VWS_PROFILES_PATH, json).map_err(|e| e.to_string())
+}
+
+#[test]
+fn point_positions_test() {
+    use glifparser::PointType;
+
+    let square: Vec<Point<MFEKPointData>> = [(0., 0.), (100., 0.), (100., 300.), (0., 300.)]
+        .into_iter()
+        .map(|xy| Point::from_x_y_type(xy, PointType::Line))
+        .collect();
+    // a closed contour's length includes its closing side
+    assert_eq!(point_positions(&square, true), vec![0., 0.125, 0.5, 0.625]);
+    assert_eq!(point_positions(&square, false), vec![0., 0.2, 0.8, 1.]);
+}
+
+#[test]
+fn vws_profile_test() {
+    use super::util::vws_contour_for_len;
+    use glifparser::PointType;
+
+    let points = |xys: &[(f32, f32)], ptype: PointType| -> MFEKContour<MFEKPointData> {
+        let mut points: Vec<Point<MFEKPointData>> = xys
+            .iter()
+            .map(|xy| Point::from_x_y_type(*xy, PointType::Line))
+            .collect();
+        points[0].ptype = ptype;
+        points.into()
+    };
+
+    // a stroke with a handle after its last point's
+    let line = points(&[(0., 0.), (100., 0.), (300., 0.)], PointType::Move);
+    let mut data = vws_contour_for_len(line.len(), 0.);
+    assert_eq!(data.handles.len(), line.len() + 1);
+    for (handle, offset) in data.handles.iter_mut().zip([0., 10., 30., 30.]) {
+        handle.left_offset = offset;
+    }
+    let profile = WidthProfile::from_vws(&data, &line);
+    assert_eq!(profile.positions, vec![0., 1. / 3., 1., 1.]);
+
+    let shorter = points(&[(0., 0.), (300., 0.)], PointType::Move);
+    let mut shorter_data = vws_contour_for_len(shorter.len(), 0.);
+    profile.apply_to(&mut shorter_data, &shorter);
+    let lefts: Vec<_> = shorter_data.handles.iter().map(|h| h.left_offset).collect();
+    assert_eq!(lefts, vec![0., 30., 30.]);
+
+    // on a closed contour the last handle is back where the first point is
+    let square = points(
+        &[(0., 0.), (100., 0.), (100., 100.), (0., 100.)],
+        PointType::Line,
+    );
+    let data = vws_contour_for_len(square.len(), 0.);
+    let profile = WidthProfile::from_vws(&data, &square);
+    assert_eq!(profile.positions, vec![0., 0.25, 0.5, 0.75, 1.]);
+}
+
+#[test]
+fn resampled_test() {
+    // handles bunched towards the end of an open contour
+    let open = WidthProfile {
+        offsets: vec![(0., 0., 0.), (8., 16., 0.), (10., 20., 0.)],
+        positions: vec![0., 0.8, 1.],
+        closed: false,
+    };
+    assert_eq!(
+        open.resampled(&[0., 0.4, 0.9, 1.]),
+        vec![(0., 0., 0.), (4., 8., 0.), (9., 18., 0.), (10., 20., 0.)]
+    );
+
+    // a closed contour's profile runs from its last handle back round to its first
+    let closed = WidthProfile {
+        offsets: vec![(0., 0., 0.), (10., 10., 0.)],
+        positions: vec![0., 0.5],
+        closed: true,
+    };
+    assert_eq!(
+        closed.resampled(&[0.25, 0.75]),
+        vec![(5., 5., 0.), (5., 5., 0.)]
+    );
+}
+
+#[test]
+fn mirrored_reversed_test() {
+    let profile = WidthProfile {
+        offsets: vec![(1., 2., 3.), (4., 5., 6.), (7., 8., 9.)],
+        positions: vec![0., 0.25, 1.],
+        closed: false,
+    };
+    assert_eq!(
+        profile.mirrored().offsets,
+        vec![(2., 1., 3.), (5., 4., 6.), (8., 7., 9.)]
+    );
+
+    let reversed = profile.reversed();
+    assert_eq!(reversed.positions, vec![0., 0.75, 1.]);
+    assert_eq!(
+        reversed.offsets,
+        vec![(7., 8., -9.), (4., 5., -6.), (1., 2., -3.)]
+    );
+    assert_eq!(reversed.reversed(), profile);
+
+    // a closed profile keeps its first handle first
+    let closed = WidthProfile {
+        closed: true,
+        positions: vec![0., 0.25, 0.5],
+        ..profile
+    };
+    let reversed = closed.reversed();
+    assert_eq!(reversed.positions, vec![0., 0.5, 0.75]);
+    assert_eq!(
+        reversed.offsets,
+        vec![(1., 2., -3.), (7., 8., -9.), (4., 5., -6.)]
+    );
+}
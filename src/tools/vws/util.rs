@@ -21,7 +21,7 @@ pub fn get_vws_contour(v: &Editor, contour_idx: usize) -> Option<VWSContour> {
 }
 
 pub fn set_vws_contour(v: &mut Editor, contour_idx: usize, contour: VWSContour) {
-    v.get_active_layer_mut().outline[contour_idx].set_operation(Some(ContourOperations::VariableWidthStroke {
+    v.set_contour_operation(contour_idx, Some(ContourOperations::VariableWidthStroke {
         data: contour.clone(),
     }));
 }
@@ -241,6 +241,12 @@ pub fn get_vws_handle_pos(
 }
 
 fn generate_vws_contour(v: &Editor, contour_idx: usize) -> VWSContour {
+    vws_contour_for_len(get_contour_len!(v.get_active_layer_ref(), contour_idx), 10.)
+}
+
+/// A round capped and joined VWS operation for a contour of `len` points, with the given offset on
+/// both sides of every handle.
+pub fn vws_contour_for_len(len: usize, offset: f64) -> VWSContour {
     let mut new_vws_contour = VWSContour {
         handles: Vec::new(),
         cap_start_type: CapType::Round,
@@ -250,10 +256,10 @@ fn generate_vws_contour(v: &Editor, contour_idx: usize) -> VWSContour {
         remove_external: false,
     };
 
-    for _i in 0..get_contour_len!(v.get_active_layer_ref(), contour_idx) + 1 {
+    for _i in 0..len + 1 {
         new_vws_contour.handles.push(VWSHandle {
-            left_offset: 10.,
-            right_offset: 10.,
+            left_offset: offset,
+            right_offset: offset,
             interpolation: InterpolationType::Linear,
             tangent_offset: 0.,
         })
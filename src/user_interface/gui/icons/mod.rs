@@ -3,13 +3,17 @@ pub use self::button::build as build_button;
 pub use self::button::build_and_add as build_and_add_button;
 pub use self::button::IntoResponse as IntoButtonResponse;
 
-pub const _KNIFE: &str = "\u{F000}";
+pub const KNIFE: &str = "\u{F000}";
 pub const MEASURE: &str = "\u{F001}";
 pub const PAN: &str = "\u{F002}";
 pub const PEN: &str = "\u{F003}";
-pub const _PENCIL: &str = "\u{F004}";
+pub const PENCIL: &str = "\u{F004}";
 pub const SELECT: &str = "\u{F005}";
 pub const SHAPES: &str = "\u{F006}";
+// There are no glyphs for corners, nibs or expanded strokes in the icon font yet.
+pub const CORNERS: &str = SHAPES;
+pub const NIB: &str = PEN;
+pub const EXPAND_STROKE: &str = VWS;
 pub const _TEXT: &str = "\u{F007}";
 pub const ZOOM: &str = "\u{F008}";
 pub const VWS: &str = "\u{F009}";
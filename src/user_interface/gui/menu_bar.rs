@@ -39,6 +39,17 @@ pub fn menu_bar(ctx: &Context, v: &mut Editor, i: &mut Interface, wm: &mut Windo
                 if ui.button("Redo").clicked() {
                     v.redo();
                 }
+                ui.separator();
+                if ui.button("Add Extrema").clicked() {
+                    v.add_extrema();
+                }
+                if ui.button("Round to Grid").clicked() {
+                    v.round_to_grid(v.rounding.unit);
+                }
+                ui.menu_button("Rounding", |ui| {
+                    ui.add(egui::Slider::new(&mut v.rounding.unit, 0.01..=100.).logarithmic(true).text("Unit"));
+                    ui.checkbox(&mut v.rounding.on_export, "Round on export");
+                });
             });
 
             //
@@ -69,6 +80,16 @@ pub fn menu_bar(ctx: &Context, v: &mut Editor, i: &mut Interface, wm: &mut Windo
                 });
 
                 ui.checkbox(&mut i.grid.show, "Grid");
+
+                ui.menu_button("Snapping", |ui| {
+                    ui.checkbox(&mut i.snap.enabled, "Enabled");
+                    ui.add_enabled_ui(i.snap.enabled, |ui| {
+                        ui.checkbox(&mut i.snap.grid, "Grid");
+                        ui.checkbox(&mut i.snap.guidelines, "Guidelines and metrics");
+                        ui.checkbox(&mut i.snap.points, "Points");
+                        ui.checkbox(&mut i.snap.alignment, "Alignment with neighbours");
+                    });
+                });
             });
 
             //
@@ -82,6 +103,10 @@ pub fn menu_bar(ctx: &Context, v: &mut Editor, i: &mut Interface, wm: &mut Windo
                 let mut grid_open = wm.grid.open();
                 ui.checkbox(&mut grid_open, "Grid");
                 wm.grid.set_open(grid_open);
+
+                let mut transform_open = wm.transform.open();
+                ui.checkbox(&mut transform_open, "Transform");
+                wm.transform.set_open(transform_open);
             })
         })
     });
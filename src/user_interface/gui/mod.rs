@@ -36,6 +36,7 @@ pub fn build_ui(
         wm.inspector.build(ctx, v, i);
         wm.grid.build(ctx, v, i);
         wm.tool.build(ctx, v, i);
+        wm.transform.build(ctx, v, i);
 
         if i.active_prompts() {
             prompts::build_and_check_prompts(v, i, ctx);
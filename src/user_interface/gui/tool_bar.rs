@@ -50,16 +50,21 @@ pub fn tool_bar(ctx: &Context, v: &mut Editor, _i: &mut Interface) {
                 build_button(v, ui, icons::PAN, ToolEnum::Pan);
                 build_button(v, ui, icons::SELECT, ToolEnum::Select);
                 build_button(v, ui, icons::PEN, ToolEnum::Pen);
+                build_button(v, ui, icons::PENCIL, ToolEnum::Pencil);
+                build_button(v, ui, icons::KNIFE, ToolEnum::Knife);
                 ui.separator();
                 build_button(v, ui, icons::ZOOM, ToolEnum::Zoom);
                 build_button(v, ui, icons::MEASURE, ToolEnum::Measure);
                 ui.separator();
                 build_button(v, ui, icons::VWS, ToolEnum::VWS);
+                build_button(v, ui, icons::NIB, ToolEnum::Nib);
+                build_button(v, ui, icons::EXPAND_STROKE, ToolEnum::ExpandStroke);
                 build_button(v, ui, icons::PAP, ToolEnum::PAP);
                 build_button(v, ui, icons::DASH, ToolEnum::Dash);
                 ui.separator();
                 build_button(v, ui, icons::ANCHOR, ToolEnum::Anchors);
                 build_button(v, ui, icons::SHAPES, ToolEnum::Shapes);
+                build_button(v, ui, icons::CORNERS, ToolEnum::Corners);
                 build_button(v, ui, icons::IMAGES, ToolEnum::Image);
                 build_button(v, ui, icons::GUIDELINES, ToolEnum::Guidelines);
             })
@@ -6,11 +6,13 @@ use crate::{editor::Editor, user_interface::Interface};
 use super::windows::grid_window::GridWindow;
 use super::windows::layer_list::LayerList;
 use super::windows::tool_window::ToolWindow;
+use super::windows::transform_window::TransformWindow;
 pub struct WindowManager {
     pub inspector: InspectionWindow,
     pub grid: GridWindow,
     pub tool: ToolWindow,
     pub layer_list: LayerList,
+    pub transform: TransformWindow,
 }
 
 impl WindowManager {
@@ -20,6 +22,7 @@ impl WindowManager {
             grid: GridWindow::new(),
             tool: ToolWindow::new(),
             layer_list: LayerList::new(),
+            transform: TransformWindow::new(),
         }
     }
 }
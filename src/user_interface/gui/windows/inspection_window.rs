@@ -1,16 +1,29 @@
 use std::collections::HashMap;
 
 use crate::{
-    contour_operations::ContourOperationBuild as _,
-    editor::Editor,
-    user_interface::{gui::window::GlifWindow, Interface},
+    contour_operations::{
+        chain::{ChainOperation, ChainedOperation},
+        expandstroke::ExpandStrokeContour,
+        nib::NibContour,
+        roundedcorners::RoundedCornersContour,
+    },
+    editor::{
+        contour_data::{ContourData, EditorOperation},
+        convert::ConvertScope,
+        corners::CornerParams,
+        Editor,
+    },
+    tools::{dash::new_dash_contour, pap::new_pap_contour, vws::util::vws_contour_for_len},
+    user_interface::{gui, gui::window::GlifWindow, Interface},
 };
 use egui::Context;
 use glifparser::{
-    glif::{contour::MFEKContourCommon, inner::MFEKContourInnerType, point::MFEKPointCommon},
+    glif::{
+        contour::MFEKContourCommon, contour_operations::ContourOperations,
+        inner::MFEKContourInnerType, point::MFEKPointCommon,
+    },
     Handle, PointData, WhichHandle,
 };
-use MFEKmath::mfek::ResolveCubic;
 
 use super::egui_parsed_textfield;
 
@@ -18,6 +31,10 @@ pub struct InspectionWindow {
     // is this window open?
     open: bool,
     edit_buf: HashMap<String, String>,
+
+    convert_scope: ConvertScope,
+    // largest distance allowed between a cubic and its quadratic conversion, in units
+    quad_tolerance: f32,
 }
 
 impl InspectionWindow {
@@ -25,6 +42,8 @@ impl InspectionWindow {
         InspectionWindow {
             open: false,
             edit_buf: HashMap::new(),
+            convert_scope: ConvertScope::Selection,
+            quad_tolerance: 1.,
         }
     }
 }
@@ -55,25 +74,141 @@ impl GlifWindow for InspectionWindow {
                         .expect("Editor should have valid selection!");
                     // do contour stuff
 
+                    // the point we're inspecting is stale after a conversion, so we mustn't write it back
+                    let mut converted = false;
                     ui.collapsing("Contour", |ui| {
                         let mut contour = v.get_active_layer_ref().outline[ci].clone();
                         let ci = v.contour_idx.expect("Expected a selected contour w/o one");
 
                         ui.label(format!("Type: {:?}", contour.get_type()));
 
-                        if contour.get_type() != MFEKContourInnerType::Cubic {
-                            if ui.button("Convert to Cubic").clicked() {
-                                v.begin_modification("Made contour a cubic.", true);
-                                v.get_active_layer_mut().outline[ci] = contour.to_cubic();
+                        ui.collapsing("Convert", |ui| {
+                            ui.horizontal(|ui| {
+                                ui.radio_value(&mut self.convert_scope, ConvertScope::Selection, "Selected");
+                                ui.radio_value(&mut self.convert_scope, ConvertScope::Layer, "Layer");
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Tolerance");
+                                self.quad_tolerance = egui_parsed_textfield(
+                                    ui,
+                                    "quad_tolerance",
+                                    self.quad_tolerance,
+                                    &mut self.edit_buf,
+                                );
+                            });
+
+                            let mut to = None;
+                            if ui.button("To Cubic").clicked() {
+                                to = Some(MFEKContourInnerType::Cubic);
+                            }
+                            if ui.button("To Quadratic").clicked() {
+                                to = Some(MFEKContourInnerType::Quad);
+                            }
+                            if ui.button("To Hyperbezier").clicked() {
+                                to = Some(MFEKContourInnerType::Hyper);
+                            }
+
+                            if let Some(to) = to {
+                                let targets = v.conversion_targets(self.convert_scope);
+                                v.convert_contours(targets, to, self.quad_tolerance);
+                                converted = true;
+                            }
+                        });
+
+                        ui.collapsing("Operations", |ui| {
+                            let original = v.operation_stack(ci);
+                            let mut stack = original.clone();
+                            let last = stack.len().saturating_sub(1);
+                            let mut moved = None;
+                            let mut removed = None;
+                            for (idx, chained) in stack.iter_mut().enumerate() {
+                                ui.horizontal(|ui| {
+                                    let name = chained.operation.name();
+                                    ui.checkbox(&mut chained.enabled, name);
+                                    if ui.add_enabled(idx > 0, egui::Button::new("Up")).clicked() {
+                                        moved = Some((idx, idx - 1));
+                                    }
+                                    let down = egui::Button::new("Down");
+                                    if ui.add_enabled(idx < last, down).clicked() {
+                                        moved = Some((idx, idx + 1));
+                                    }
+                                    if ui.button("Remove").clicked() {
+                                        removed = Some(idx);
+                                    }
+                                });
+                            }
+                            if let Some((a, b)) = moved {
+                                stack.swap(a, b);
+                            }
+                            if let Some(idx) = removed {
+                                stack.remove(idx);
+                            }
+
+                            let len = contour.len();
+                            egui::ComboBox::new("add_contour_operation", "")
+                                .selected_text("Add Operation")
+                                .show_ui(ui, |ui| {
+                                    let mut added = None;
+                                    if ui.selectable_label(false, "Variable Width Stroke").clicked() {
+                                        let data = vws_contour_for_len(len, 10.);
+                                        let vws = ContourOperations::VariableWidthStroke { data };
+                                        added = Some(ChainOperation::Contour(vws));
+                                    }
+                                    if ui.selectable_label(false, "Pattern Along Path").clicked() {
+                                        match v.clipboard_layer() {
+                                            Some(layer) => {
+                                                let data = new_pap_contour(layer.outline);
+                                                let pap =
+                                                    ContourOperations::PatternAlongPath { data };
+                                                added = Some(ChainOperation::Contour(pap));
+                                            }
+                                            None => gui::error!(
+                                                "The clipboard doesn't hold a layer to use as a pattern."
+                                            ),
+                                        }
+                                    }
+                                    if ui.selectable_label(false, "Dash Along Path").clicked() {
+                                        let data = new_dash_contour();
+                                        let dash = ContourOperations::DashAlongPath { data };
+                                        added = Some(ChainOperation::Contour(dash));
+                                    }
+                                    if ui.selectable_label(false, "Expand Stroke").clicked() {
+                                        let stroke = ExpandStrokeContour::default();
+                                        let stroke = EditorOperation::ExpandStroke(stroke);
+                                        added = Some(ChainOperation::Editor(stroke));
+                                    }
+                                    if ui.selectable_label(false, "Nib").clicked() {
+                                        let nib = EditorOperation::Nib(NibContour::default());
+                                        added = Some(ChainOperation::Editor(nib));
+                                    }
+                                    if ui.selectable_label(false, "Rounded Corners").clicked() {
+                                        let radius = CornerParams::default().radius;
+                                        let rounded = RoundedCornersContour::new(len, radius);
+                                        let rounded = EditorOperation::RoundedCorners(rounded);
+                                        added = Some(ChainOperation::Editor(rounded));
+                                    }
+                                    if let Some(operation) = added {
+                                        stack.push(ChainedOperation::new(operation));
+                                    }
+                                });
+
+                            if stack != original {
+                                v.begin_modification(
+                                    "Modified contour operations with inspector.",
+                                    false,
+                                );
+                                v.set_operation_stack(ci, stack);
                                 v.end_modification();
                             }
-                        }
+                        });
 
-                        if contour.operation().is_some() {
+                        let editor_operation = v.contour_data(ci).and_then(|d| d.operation.clone());
+                        if contour.operation().is_some() || editor_operation.is_some() {
                             if ui.button("Apply Contour Operation").clicked() {
-                                let outline = contour.operation().build(&contour);
+                                let outline = v.build_contour_operation(ci);
 
                                 v.begin_modification("Modified contour with inspector.", true);
+                                v.set_contour_data(ci, ContourData::default());
                                 v.get_active_layer_mut().outline.remove(ci);
                                 for c in outline {
                                     v.get_active_layer_mut().outline.insert(ci, c);
@@ -89,10 +224,32 @@ impl GlifWindow for InspectionWindow {
 
                                 v.begin_modification("Modified contour with inspector.", true);
                                 v.get_active_layer_mut().outline[ci].set_operation(None);
+                                v.set_contour_data(ci, ContourData::default());
                                 v.end_modification();
                             }
                         }
 
+                        if let Some(EditorOperation::RoundedCorners(mut rounded)) = editor_operation {
+                            if let Some(handle) = rounded.handles.get_mut(pi) {
+                                ui.label("Corner Radius");
+                                let radius = egui_parsed_textfield(
+                                    ui,
+                                    "corner_radius",
+                                    handle.radius,
+                                    &mut self.edit_buf,
+                                );
+                                if radius != handle.radius {
+                                    handle.radius = radius.max(0.);
+                                    let mut data = v.contour_data(ci).cloned().unwrap_or_default();
+                                    data.operation = Some(EditorOperation::RoundedCorners(rounded));
+
+                                    v.begin_modification("Modified contour with inspector.", true);
+                                    v.set_contour_data(ci, data);
+                                    v.end_modification();
+                                }
+                            }
+                        }
+
                         let mut open = contour.is_open();
                         ui.checkbox(&mut open, "Open");
 
@@ -108,6 +265,9 @@ impl GlifWindow for InspectionWindow {
                             v.end_modification();
                         }
                     });
+                    if converted {
+                        return;
+                    }
                     ui.collapsing("Point", |ui| {
                         ui.label("Position:");
                         point.set_position(
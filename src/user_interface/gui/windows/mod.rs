@@ -8,6 +8,7 @@ pub mod grid_window;
 pub mod inspection_window;
 pub mod layer_list;
 pub mod tool_window;
+pub mod transform_window;
 
 pub fn egui_parsed_textfield<D>(
     ui: &mut Ui,
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+
+use super::egui_parsed_textfield;
+use crate::{
+    editor::{
+        transform::{BoundingBoxAnchor, TransformOperation, TransformTarget},
+        Editor,
+    },
+    user_interface::{gui::window::GlifWindow, Interface},
+};
+use egui::Context;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransformOrigin {
+    Anchor(BoundingBoxAnchor),
+    Pivot,
+    Exact(f32, f32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OperationKind {
+    Move,
+    Scale,
+    Rotate,
+    Skew,
+    Mirror,
+}
+
+pub struct TransformWindow {
+    // is this window open?
+    open: bool,
+    edit_buf: HashMap<String, String>,
+
+    target: TransformTarget,
+    origin: TransformOrigin,
+    kind: OperationKind,
+
+    move_by: (f64, f64),
+    // in percent
+    scale: (f64, f64),
+    uniform: bool,
+    angle: f64,
+    skew: (f64, f64),
+    mirror_horizontal: bool,
+
+    last: Option<(TransformOperation, TransformTarget, TransformOrigin)>,
+}
+
+impl TransformWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            edit_buf: HashMap::new(),
+            target: TransformTarget::Selection,
+            origin: TransformOrigin::Anchor(BoundingBoxAnchor::Center),
+            kind: OperationKind::Move,
+            move_by: (0., 0.),
+            scale: (100., 100.),
+            uniform: true,
+            angle: 0.,
+            skew: (0., 0.),
+            mirror_horizontal: true,
+            last: None,
+        }
+    }
+
+    fn operation(&self) -> TransformOperation {
+        match self.kind {
+            OperationKind::Move => TransformOperation::Move {
+                x: self.move_by.0,
+                y: self.move_by.1,
+            },
+            OperationKind::Scale => {
+                let y = if self.uniform { self.scale.0 } else { self.scale.1 };
+                TransformOperation::Scale {
+                    x: self.scale.0 / 100.,
+                    y: y / 100.,
+                }
+            }
+            OperationKind::Rotate => TransformOperation::Rotate { angle: self.angle },
+            OperationKind::Skew => TransformOperation::Skew {
+                x: self.skew.0,
+                y: self.skew.1,
+            },
+            OperationKind::Mirror => TransformOperation::Mirror {
+                horizontal: self.mirror_horizontal,
+            },
+        }
+    }
+
+    /// Resolves the origin against the editor's current state, so repeating a transform uses the
+    /// bounding box as it is now.
+    fn resolve_origin(
+        v: &Editor,
+        target: TransformTarget,
+        origin: TransformOrigin,
+    ) -> Option<(f32, f32)> {
+        match origin {
+            TransformOrigin::Anchor(anchor) => v
+                .build_target_bounding_box(target)
+                .map(|rect| anchor.position(&rect)),
+            TransformOrigin::Pivot => v.pivot_point,
+            TransformOrigin::Exact(x, y) => Some((x, y)),
+        }
+    }
+
+    fn apply(
+        v: &mut Editor,
+        operation: TransformOperation,
+        target: TransformTarget,
+        origin: TransformOrigin,
+    ) -> bool {
+        match Self::resolve_origin(v, target, origin) {
+            Some(origin) => {
+                v.transform(target, operation.to_affine(origin));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn build_origin(&mut self, ui: &mut egui::Ui, v: &Editor) {
+        ui.label("Origin");
+        let is_anchor = matches!(self.origin, TransformOrigin::Anchor(..));
+        if ui.radio(is_anchor, "Bounding box").clicked() && !is_anchor {
+            self.origin = TransformOrigin::Anchor(BoundingBoxAnchor::Center);
+        }
+        if let TransformOrigin::Anchor(current) = self.origin {
+            egui::Grid::new("transform_anchor_grid").show(ui, |ui| {
+                for (idx, anchor) in BoundingBoxAnchor::ALL.into_iter().enumerate() {
+                    if ui.radio(current == anchor, "").clicked() {
+                        self.origin = TransformOrigin::Anchor(anchor);
+                    }
+                    if idx % 3 == 2 {
+                        ui.end_row();
+                    }
+                }
+            });
+        }
+
+        let is_pivot = self.origin == TransformOrigin::Pivot;
+        ui.add_enabled_ui(v.pivot_point.is_some(), |ui| {
+            if ui.radio(is_pivot, "Pivot").clicked() {
+                self.origin = TransformOrigin::Pivot;
+            }
+        });
+
+        let is_exact = matches!(self.origin, TransformOrigin::Exact(..));
+        if ui.radio(is_exact, "Coordinates").clicked() && !is_exact {
+            self.origin = TransformOrigin::Exact(0., 0.);
+        }
+        if let TransformOrigin::Exact(x, y) = self.origin {
+            ui.horizontal(|ui| {
+                let x = egui_parsed_textfield(ui, "origin_x", x, &mut self.edit_buf);
+                let y = egui_parsed_textfield(ui, "origin_y", y, &mut self.edit_buf);
+                self.origin = TransformOrigin::Exact(x, y);
+            });
+        }
+    }
+
+    fn build_operation(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_label("Operation")
+            .selected_text(format!("{:?}", self.kind))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.kind, OperationKind::Move, "Move");
+                ui.selectable_value(&mut self.kind, OperationKind::Scale, "Scale");
+                ui.selectable_value(&mut self.kind, OperationKind::Rotate, "Rotate");
+                ui.selectable_value(&mut self.kind, OperationKind::Skew, "Skew");
+                ui.selectable_value(&mut self.kind, OperationKind::Mirror, "Mirror");
+            });
+
+        match self.kind {
+            OperationKind::Move => {
+                ui.horizontal(|ui| {
+                    ui.label("X");
+                    self.move_by.0 = egui_parsed_textfield(ui, "move_x", self.move_by.0, &mut self.edit_buf);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Y");
+                    self.move_by.1 = egui_parsed_textfield(ui, "move_y", self.move_by.1, &mut self.edit_buf);
+                });
+            }
+            OperationKind::Scale => {
+                ui.checkbox(&mut self.uniform, "Uniform");
+                ui.horizontal(|ui| {
+                    ui.label(if self.uniform { "%" } else { "X %" });
+                    self.scale.0 = egui_parsed_textfield(ui, "scale_x", self.scale.0, &mut self.edit_buf);
+                });
+                if !self.uniform {
+                    ui.horizontal(|ui| {
+                        ui.label("Y %");
+                        self.scale.1 = egui_parsed_textfield(ui, "scale_y", self.scale.1, &mut self.edit_buf);
+                    });
+                }
+            }
+            OperationKind::Rotate => {
+                ui.horizontal(|ui| {
+                    ui.label("Angle");
+                    self.angle = egui_parsed_textfield(ui, "angle", self.angle, &mut self.edit_buf);
+                });
+            }
+            OperationKind::Skew => {
+                ui.horizontal(|ui| {
+                    ui.label("X°");
+                    self.skew.0 = egui_parsed_textfield(ui, "skew_x", self.skew.0, &mut self.edit_buf);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Y°");
+                    self.skew.1 = egui_parsed_textfield(ui, "skew_y", self.skew.1, &mut self.edit_buf);
+                });
+            }
+            OperationKind::Mirror => {
+                ui.radio_value(&mut self.mirror_horizontal, true, "Horizontally");
+                ui.radio_value(&mut self.mirror_horizontal, false, "Vertically");
+            }
+        }
+    }
+}
+
+impl GlifWindow for TransformWindow {
+    fn open(&self) -> bool {
+        self.open
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    fn build(&mut self, ctx: &Context, v: &mut Editor, _i: &mut Interface) {
+        let mut open = self.open;
+        egui::Window::new("Transform")
+            .resizable(true)
+            .collapsible(true)
+            .open(&mut open)
+            .enabled(!v.is_modifying())
+            .constrain(true)
+            .default_width(150.)
+            .show(ctx, |ui| {
+                ui.label("Apply to");
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.target, TransformTarget::Selection, "Selection");
+                    ui.radio_value(&mut self.target, TransformTarget::Layer, "Layer");
+                    ui.radio_value(&mut self.target, TransformTarget::AllLayers, "All layers");
+                });
+
+                ui.separator();
+                self.build_origin(ui, v);
+
+                ui.separator();
+                self.build_operation(ui);
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Apply").clicked() {
+                        let operation = self.operation();
+                        if Self::apply(v, operation, self.target, self.origin) {
+                            self.last = Some((operation, self.target, self.origin));
+                        }
+                    }
+
+                    ui.add_enabled_ui(self.last.is_some(), |ui| {
+                        if ui.button("Repeat Last").clicked() {
+                            let (operation, target, origin) = self.last.unwrap();
+                            Self::apply(v, operation, target, origin);
+                        }
+                    });
+                });
+            });
+        self.open = open;
+    }
+}
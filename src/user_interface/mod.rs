@@ -4,6 +4,7 @@ pub mod gui;
 pub mod mouse_input;
 pub mod popout;
 pub mod sdl;
+pub mod snap;
 
 use std::rc::Rc;
 
@@ -42,6 +43,7 @@ pub struct Interface {
 
     pub context: Option<(f32, f32)>,
     pub grid: Grid,
+    pub snap: snap::SnapSettings,
     pub mouse_info: MouseInfo,
     pub viewport: Viewport,
 
@@ -84,6 +86,7 @@ impl Interface {
 
             context: None,
             grid: Grid::default(),
+            snap: snap::SnapSettings::default(),
             mouse_info: MouseInfo::default(),
             viewport: Viewport::default(),
 
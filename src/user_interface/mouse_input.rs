@@ -1,7 +1,10 @@
 use sdl2::mouse::MouseButton;
 use skia_safe as skia;
 
-use crate::{command::CommandMod, user_interface::Interface};
+use crate::{
+    command::CommandMod,
+    user_interface::{snap::snap_to_grid, Interface},
+};
 
 /// This struct stores the editor's mouse state.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -59,48 +62,7 @@ impl MouseInfo {
         let raw_mposition = mposition;
 
         if i.grid.show {
-            let mpos = (mposition.0, mposition.1);
-
-            let mut candidates = vec![];
-
-            let standard_snap = (
-                (mpos.0 / i.grid.spacing + i.grid.offset).round() * i.grid.spacing,
-                (mpos.1 / i.grid.spacing + i.grid.offset).round() * i.grid.spacing,
-            );
-
-            let dist = f32::sqrt(
-                f32::powi(standard_snap.0 - mpos.0, 2)
-                    + f32::powi(standard_snap.1 - mposition.1, 2),
-            );
-
-            candidates.push((dist, standard_snap));
-
-            if let Some(slope) = &i.grid.slope {
-                let slope_max = f32::min(f32::abs(*slope), 1.);
-                let x = mpos.0 - mpos.1 / slope;
-                let s = (i.grid.spacing / slope_max).abs();
-                let c = (x / s + 0.5).floor() * s;
-                let c2 = c * -slope;
-
-                let horizontal_candidate = ((standard_snap.1 - c2) / slope, standard_snap.1);
-                let dist = f32::sqrt(
-                    f32::powi(horizontal_candidate.0 - mpos.0, 2)
-                        + f32::powi(horizontal_candidate.1 - mposition.1, 2),
-                );
-
-                candidates.push((dist, horizontal_candidate));
-
-                let vertical_candidate = (standard_snap.0, slope * standard_snap.0 + c2);
-                let dist = f32::sqrt(
-                    f32::powi(vertical_candidate.0 - mpos.0, 2)
-                        + f32::powi(vertical_candidate.1 - mposition.1, 2),
-                );
-
-                candidates.push((dist, vertical_candidate));
-            }
-
-            candidates.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
-            mposition = candidates[0].1;
+            mposition = snap_to_grid(&i.grid, mposition);
         }
 
         MouseInfo {
@@ -0,0 +1,322 @@
+//! Snapping for behaviors which drag things around the canvas. Behaviors ask for a snapped position
+//! with [`snap`], passing a [`SnapQuery`] describing what they're dragging, and draw the returned
+//! [`SnapResult`] so the user can see what they snapped to.
+
+use glifparser::glif::{contour::MFEKContourCommon, point::MFEKPointCommon};
+use glifrenderer::grid::Grid;
+use skia_safe::{Canvas, Paint, PaintStyle, Path};
+
+use crate::constants::{SNAP_DISTANCE, SNAP_INDICATOR_STROKE};
+use crate::editor::Editor;
+use crate::user_interface::Interface;
+
+use std::collections::HashSet;
+
+/// Which targets are snapped to. Each can be toggled from the View menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SnapSettings {
+    pub enabled: bool,
+    /// Snap to the grid, only when it's shown.
+    pub grid: bool,
+    /// Snap to guidelines, including metrics lines and the baseline.
+    pub guidelines: bool,
+    /// Snap to other on-curve points.
+    pub points: bool,
+    /// Snap horizontally or vertically into line with the dragged point's neighbours.
+    pub alignment: bool,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            grid: true,
+            guidelines: true,
+            points: true,
+            alignment: true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapTarget {
+    Grid,
+    Guideline,
+    Point,
+    Alignment,
+}
+
+/// One active snap: the target snapped to and where on it we snapped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Snap {
+    pub target: SnapTarget,
+    pub from: (f32, f32),
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SnapResult {
+    pub position: (f32, f32),
+    pub snaps: Vec<Snap>,
+}
+
+/// Describes what's being dragged so it isn't snapped to itself.
+#[derive(Clone, Debug, Default)]
+pub struct SnapQuery {
+    /// Points which are being moved, as (contour_idx, point_idx).
+    pub exclude_points: HashSet<(usize, usize)>,
+    /// Guideline being moved, as an index into the glyph's guidelines followed by the editor's.
+    pub exclude_guideline: Option<usize>,
+    /// Positions the dragged point may line up with horizontally or vertically.
+    pub align_to: Vec<(f32, f32)>,
+}
+
+impl SnapQuery {
+    /// A query for dragging the on-curve point at (ci, pi), aligning it with its neighbours.
+    pub fn for_point(v: &Editor, ci: usize, pi: usize, exclude_points: HashSet<(usize, usize)>) -> Self {
+        let contour = &v.get_active_layer_ref().outline[ci];
+        let len = contour.len();
+        let mut neighbours = vec![];
+        if pi > 0 {
+            neighbours.push(pi - 1);
+        } else if !contour.is_open() {
+            neighbours.push(len - 1);
+        }
+        if pi + 1 < len {
+            neighbours.push(pi + 1);
+        } else if !contour.is_open() {
+            neighbours.push(0);
+        }
+
+        let align_to = neighbours
+            .into_iter()
+            .filter(|npi| *npi != pi && !exclude_points.contains(&(ci, *npi)))
+            .filter_map(|npi| contour.get_point(npi).map(|p| (p.x(), p.y())))
+            .collect();
+
+        Self {
+            exclude_points,
+            exclude_guideline: None,
+            align_to,
+        }
+    }
+}
+
+/// Rounds a position to the nearest grid intersection, taking the grid's slope into account.
+pub fn snap_to_grid(grid: &Grid, mpos: (f32, f32)) -> (f32, f32) {
+    let mut candidates = vec![];
+
+    let standard_snap = (
+        (mpos.0 / grid.spacing + grid.offset).round() * grid.spacing,
+        (mpos.1 / grid.spacing + grid.offset).round() * grid.spacing,
+    );
+
+    let dist = f32::sqrt(
+        f32::powi(standard_snap.0 - mpos.0, 2) + f32::powi(standard_snap.1 - mpos.1, 2),
+    );
+
+    candidates.push((dist, standard_snap));
+
+    if let Some(slope) = &grid.slope {
+        let slope_max = f32::min(f32::abs(*slope), 1.);
+        let x = mpos.0 - mpos.1 / slope;
+        let s = (grid.spacing / slope_max).abs();
+        let c = (x / s + 0.5).floor() * s;
+        let c2 = c * -slope;
+
+        let horizontal_candidate = ((standard_snap.1 - c2) / slope, standard_snap.1);
+        let dist = f32::sqrt(
+            f32::powi(horizontal_candidate.0 - mpos.0, 2)
+                + f32::powi(horizontal_candidate.1 - mpos.1, 2),
+        );
+
+        candidates.push((dist, horizontal_candidate));
+
+        let vertical_candidate = (standard_snap.0, slope * standard_snap.0 + c2);
+        let dist = f32::sqrt(
+            f32::powi(vertical_candidate.0 - mpos.0, 2)
+                + f32::powi(vertical_candidate.1 - mpos.1, 2),
+        );
+
+        candidates.push((dist, vertical_candidate));
+    }
+
+    candidates.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    candidates[0].1
+}
+
+/// Constrains `position` to the nearest 45° direction from `origin`, keeping its distance along
+/// that direction. This is what holding Shift does while placing points and handles.
+pub fn constrain_angle(origin: (f32, f32), position: (f32, f32)) -> (f32, f32) {
+    let (dx, dy) = (position.0 - origin.0, position.1 - origin.1);
+    let step = std::f32::consts::FRAC_PI_4;
+    let angle = (f32::atan2(dy, dx) / step).round() * step;
+    let (ux, uy) = (angle.cos(), angle.sin());
+    let along = dx * ux + dy * uy;
+    (origin.0 + ux * along, origin.1 + uy * along)
+}
+
+// An axis snap: the coordinate on that axis, and the point it came from for the indicator.
+type AxisCandidate = (f32, Snap);
+
+fn closest_axis(candidates: Vec<AxisCandidate>, value: f32, threshold: f32) -> Option<AxisCandidate> {
+    candidates
+        .into_iter()
+        .filter(|(c, _)| (c - value).abs() < threshold)
+        .min_by(|a, b| (a.0 - value).abs().partial_cmp(&(b.0 - value).abs()).unwrap())
+}
+
+/// Snaps a raw (unsnapped) glyph space position according to the interface's snap settings.
+///
+/// On-curve points win over everything else. Otherwise each axis is snapped independently to
+/// horizontal/vertical guidelines and aligned neighbours, falling back to angled guidelines and
+/// finally to the grid.
+pub fn snap(v: &Editor, i: &Interface, position: (f32, f32), query: &SnapQuery) -> SnapResult {
+    let settings = i.snap;
+    let mut result = SnapResult {
+        position,
+        snaps: vec![],
+    };
+    if !settings.enabled {
+        return result;
+    }
+
+    let threshold = SNAP_DISTANCE / i.viewport.factor;
+    let distance = |a: (f32, f32), b: (f32, f32)| f32::hypot(a.0 - b.0, a.1 - b.1);
+
+    if settings.points {
+        let mut closest: Option<((f32, f32), f32)> = None;
+        for (ci, contour) in v.get_active_layer_ref().outline.iter().enumerate() {
+            for pi in 0..contour.len() {
+                if query.exclude_points.contains(&(ci, pi)) {
+                    continue;
+                }
+                let point = contour.get_point(pi).unwrap();
+                let pos = (point.x(), point.y());
+                let d = distance(pos, position);
+                if d < threshold && closest.map(|(_, cd)| d < cd).unwrap_or(true) {
+                    closest = Some((pos, d));
+                }
+            }
+        }
+
+        if let Some((pos, _)) = closest {
+            result.position = pos;
+            result.snaps.push(Snap {
+                target: SnapTarget::Point,
+                from: pos,
+            });
+            return result;
+        }
+    }
+
+    let mut x_candidates: Vec<AxisCandidate> = vec![];
+    let mut y_candidates: Vec<AxisCandidate> = vec![];
+    let mut angled: Option<((f32, f32), f32)> = None;
+
+    if settings.guidelines {
+        // The baseline isn't a guideline but is drawn like one.
+        y_candidates.push((0., Snap { target: SnapTarget::Guideline, from: (position.0, 0.) }));
+
+        let guidelines = v.with_glyph(|glyph| glyph.guidelines.clone());
+        for (idx, guide) in guidelines.iter().chain(v.guidelines.iter()).enumerate() {
+            if query.exclude_guideline == Some(idx) {
+                continue;
+            }
+
+            let angle = f32::from(guide.angle).rem_euclid(180.);
+            let at = (guide.at.x, guide.at.y);
+            if angle.abs() < f32::EPSILON {
+                y_candidates.push((at.1, Snap { target: SnapTarget::Guideline, from: (position.0, at.1) }));
+            } else if (angle - 90.).abs() < f32::EPSILON {
+                x_candidates.push((at.0, Snap { target: SnapTarget::Guideline, from: (at.0, position.1) }));
+            } else {
+                let (dx, dy) = (angle.to_radians().cos(), angle.to_radians().sin());
+                let along = (position.0 - at.0) * dx + (position.1 - at.1) * dy;
+                let foot = (at.0 + dx * along, at.1 + dy * along);
+                let d = distance(foot, position);
+                if d < threshold && angled.map(|(_, ad)| d < ad).unwrap_or(true) {
+                    angled = Some((foot, d));
+                }
+            }
+        }
+    }
+
+    if settings.alignment {
+        for pos in &query.align_to {
+            x_candidates.push((pos.0, Snap { target: SnapTarget::Alignment, from: *pos }));
+            y_candidates.push((pos.1, Snap { target: SnapTarget::Alignment, from: *pos }));
+        }
+    }
+
+    let x_snap = closest_axis(x_candidates, position.0, threshold);
+    let y_snap = closest_axis(y_candidates, position.1, threshold);
+
+    if x_snap.is_none() && y_snap.is_none() {
+        if let Some((foot, _)) = angled {
+            result.position = foot;
+            result.snaps.push(Snap {
+                target: SnapTarget::Guideline,
+                from: foot,
+            });
+            return result;
+        }
+    }
+
+    if settings.grid && i.grid.show {
+        let grid_position = snap_to_grid(&i.grid, position);
+        if x_snap.is_none() || y_snap.is_none() {
+            result.snaps.push(Snap {
+                target: SnapTarget::Grid,
+                from: grid_position,
+            });
+        }
+        result.position = grid_position;
+    }
+
+    if let Some((x, snap)) = x_snap {
+        result.position.0 = x;
+        result.snaps.push(snap);
+    }
+    if let Some((y, snap)) = y_snap {
+        result.position.1 = y;
+        result.snaps.push(snap);
+    }
+
+    result
+}
+
+/// Draws an indicator for each active snap. Alignment and guideline snaps draw a line from what was
+/// snapped to, points get a ring, and grid intersections a small cross.
+pub fn draw_snap_indicator(i: &Interface, canvas: &mut Canvas, result: &SnapResult) {
+    let factor = i.viewport.factor;
+    let mut paint = Paint::default();
+    paint.set_anti_alias(true);
+    paint.set_style(PaintStyle::Stroke);
+    paint.set_color(SNAP_INDICATOR_STROKE);
+    paint.set_stroke_width(1. / factor);
+
+    let (x, y) = result.position;
+    let size = SNAP_DISTANCE / factor;
+    for snap in &result.snaps {
+        match snap.target {
+            SnapTarget::Point => {
+                canvas.draw_circle((x, y), size, &paint);
+            }
+            SnapTarget::Grid => {
+                let mut path = Path::new();
+                path.move_to((x - size / 2., y));
+                path.line_to((x + size / 2., y));
+                path.move_to((x, y - size / 2.));
+                path.line_to((x, y + size / 2.));
+                canvas.draw_path(&path, &paint);
+            }
+            SnapTarget::Guideline | SnapTarget::Alignment => {
+                let mut path = Path::new();
+                path.move_to(snap.from);
+                path.line_to((x, y));
+                canvas.draw_path(&path, &paint);
+                canvas.draw_circle(snap.from, size / 3., &paint);
+            }
+        }
+    }
+}